@@ -1,8 +1,10 @@
-use serde::Deserialize;
-use std::path::PathBuf;
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 
 /// Type of skill item
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum SkillType {
     Skill,
     Agent,
@@ -11,6 +13,10 @@ pub enum SkillType {
 }
 
 impl SkillType {
+    /// Every item type, in install order
+    pub const ALL: [SkillType; 4] =
+        [SkillType::Skill, SkillType::Agent, SkillType::Command, SkillType::Rule];
+
     pub fn dir_name(&self) -> &'static str {
         match self {
             SkillType::Skill => "skills",
@@ -20,6 +26,21 @@ impl SkillType {
         }
     }
 
+    /// Singular lowercase name (e.g. "skill"), as used in config and output
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SkillType::Skill => "skill",
+            SkillType::Agent => "agent",
+            SkillType::Command => "command",
+            SkillType::Rule => "rule",
+        }
+    }
+
+    /// Parse a singular lowercase name (see `as_str`)
+    pub fn from_name(name: &str) -> Option<SkillType> {
+        SkillType::ALL.into_iter().find(|t| t.as_str() == name)
+    }
+
     /// Alternative directory names for the resources format
     pub fn alt_dir_names(&self) -> &'static [&'static str] {
         match self {
@@ -35,6 +56,162 @@ pub struct ResourceMeta {
     pub name: Option<String>,
     pub author: Option<String>,
     pub description: Option<String>,
+    pub homepage: Option<String>,
+    pub repository: Option<String>,
+    pub license: Option<String>,
+    /// Release of the bundle, e.g. `1.2`
+    #[serde(default, deserialize_with = "deserialize_version")]
+    pub version: Option<String>,
+    /// Bundles to install before this one
+    #[serde(default)]
+    pub requires: Vec<String>,
+    /// Keywords to find the bundle by (`tag:` in `skm list`)
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// Read a `version` written as text or as a bare number (`version: 1.2`)
+pub fn deserialize_version<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Version {
+        Text(String),
+        Int(u64),
+        Float(f64),
+    }
+    Ok(Option::<Version>::deserialize(deserializer)?.map(|v| match v {
+        Version::Text(text) => text,
+        Version::Int(n) => n.to_string(),
+        // Debug keeps the `.0` of `1.0`
+        Version::Float(n) => format!("{:?}", n),
+    }))
+}
+
+/// Order two versions by their dot-separated parts, numerically where both
+/// parts are numbers (`1.10` > `1.9`). A leading `v` and `+build` metadata are
+/// ignored and missing parts count as zero, so `1.0` equals `1`. A pre-release
+/// (`1.0-beta`) sorts below the release it leads up to.
+pub fn compare_versions(a: &str, b: &str) -> std::cmp::Ordering {
+    use std::cmp::Ordering;
+
+    // Split into the release and its pre-release, if any
+    let split = |v: &str| {
+        let v = v.trim();
+        let v = v.strip_prefix(['v', 'V']).unwrap_or(v);
+        let v = v.split_once('+').map_or(v, |(v, _)| v);
+        match v.split_once('-') {
+            Some((release, pre)) => (release.to_string(), Some(pre.to_string())),
+            None => (v.to_string(), None),
+        }
+    };
+    let ((a, a_pre), (b, b_pre)) = (split(a), split(b));
+    compare_parts(&a, &b, true).then_with(|| match (a_pre, b_pre) {
+        (None, None) => Ordering::Equal,
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (Some(a), Some(b)) => compare_parts(&a, &b, false),
+    })
+}
+
+/// Compare dot-separated parts, numbers below words (`1` < `beta`). Missing
+/// parts count as zero when `pad`, otherwise the shorter list sorts first.
+fn compare_parts(a: &str, b: &str, pad: bool) -> std::cmp::Ordering {
+    use std::cmp::Ordering;
+
+    let (a, b): (Vec<&str>, Vec<&str>) = (a.split('.').collect(), b.split('.').collect());
+    for i in 0..a.len().max(b.len()) {
+        let (x, y) = match (a.get(i), b.get(i)) {
+            (Some(x), Some(y)) => (*x, *y),
+            (x, y) if pad => (x.copied().unwrap_or("0"), y.copied().unwrap_or("0")),
+            (None, _) => return Ordering::Less,
+            (_, None) => return Ordering::Greater,
+        };
+        let order = match (x.parse::<u64>(), y.parse::<u64>()) {
+            (Ok(x), Ok(y)) => x.cmp(&y),
+            (Ok(_), Err(_)) => Ordering::Less,
+            (Err(_), Ok(_)) => Ordering::Greater,
+            (Err(_), Err(_)) => x.cmp(y),
+        };
+        if order != Ordering::Equal {
+            return order;
+        }
+    }
+    Ordering::Equal
+}
+
+/// Whether `skm update` should refresh a bundle installed at `recorded` whose
+/// source is now at `current`: only if the version increased, or if either
+/// version is unknown
+pub fn version_needs_refresh(recorded: Option<&str>, current: Option<&str>) -> bool {
+    match (recorded, current) {
+        (Some(recorded), Some(current)) => {
+            compare_versions(current, recorded) == std::cmp::Ordering::Greater
+        }
+        _ => true,
+    }
+}
+
+impl ResourceMeta {
+    /// Parse YAML metadata, falling back to reading the known keys line by line
+    /// when the document as a whole is invalid, so one bad field doesn't lose the rest.
+    pub fn parse_lenient(yaml: &str) -> Self {
+        if let Ok(meta) = serde_yaml::from_str::<ResourceMeta>(yaml) {
+            return meta;
+        }
+
+        let mut meta = ResourceMeta::default();
+        for line in yaml.lines() {
+            // Only top-level `key: value` lines
+            if line.starts_with(char::is_whitespace) {
+                continue;
+            }
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let slot = match key.trim() {
+                "name" => &mut meta.name,
+                "author" => &mut meta.author,
+                "description" => &mut meta.description,
+                "homepage" => &mut meta.homepage,
+                "repository" => &mut meta.repository,
+                "license" => &mut meta.license,
+                "version" => &mut meta.version,
+                _ => continue,
+            };
+            if let Some(value) = Self::scalar_value(value) {
+                slot.get_or_insert(value);
+            }
+        }
+        meta
+    }
+
+    /// Read a single scalar value: valid YAML if possible, otherwise the raw text
+    /// with surrounding quotes removed
+    fn scalar_value(raw: &str) -> Option<String> {
+        let raw = raw.trim();
+        if raw.is_empty() {
+            return None;
+        }
+        if let Ok(serde_yaml::Value::String(value)) = serde_yaml::from_str(raw) {
+            return Some(value);
+        }
+        let unquoted = raw
+            .strip_prefix('"')
+            .and_then(|r| r.strip_suffix('"'))
+            .or_else(|| raw.strip_prefix('\'').and_then(|r| r.strip_suffix('\'')))
+            .unwrap_or(raw);
+        Some(unquoted.to_string())
+    }
+}
+
+/// Read a text file, replacing invalid UTF-8 (e.g. Latin-1 author names)
+/// instead of failing
+fn read_lossy(path: &Path) -> Option<String> {
+    let bytes = std::fs::read(path).ok()?;
+    Some(String::from_utf8_lossy(&bytes).into_owned())
 }
 
 /// Metadata for a bundle (author, description, etc.)
@@ -44,6 +221,49 @@ pub struct BundleMeta {
     pub author: Option<String>,
     /// Description of the bundle
     pub description: Option<String>,
+    /// Project website
+    pub homepage: Option<String>,
+    /// Source repository URL
+    pub repository: Option<String>,
+    /// License name or SPDX identifier (e.g., "MIT")
+    pub license: Option<String>,
+    /// Release of the bundle (`version` in meta.yaml or skm.toml)
+    pub version: Option<String>,
+    /// Bundles that have to be installed first (`requires` in meta.yaml or skm.toml)
+    pub requires: Vec<String>,
+    /// Keywords to find the bundle by (`tags` in meta.yaml or skm.toml)
+    pub tags: Vec<String>,
+}
+
+impl BundleMeta {
+    /// Labelled provenance fields that are set, for showing before installing
+    pub fn details(&self) -> Vec<(&'static str, &str)> {
+        [
+            ("Author", &self.author),
+            ("Version", &self.version),
+            ("Homepage", &self.homepage),
+            ("Repository", &self.repository),
+            ("License", &self.license),
+        ]
+        .into_iter()
+        .filter_map(|(label, value)| value.as_deref().map(|v| (label, v)))
+        .collect()
+    }
+}
+
+impl From<ResourceMeta> for BundleMeta {
+    fn from(meta: ResourceMeta) -> Self {
+        BundleMeta {
+            author: meta.author,
+            description: meta.description,
+            homepage: meta.homepage,
+            repository: meta.repository,
+            license: meta.license,
+            version: meta.version,
+            requires: meta.requires,
+            tags: meta.tags,
+        }
+    }
 }
 
 /// A single skill/agent/command file
@@ -66,7 +286,6 @@ pub struct Bundle {
     /// Bundle name (e.g., "cl", "gastro")
     pub name: String,
     /// Path to the bundle directory
-    #[allow(dead_code)]
     pub path: PathBuf,
     /// Skills in this bundle
     pub skills: Vec<SkillFile>,
@@ -80,6 +299,98 @@ pub struct Bundle {
     pub meta: BundleMeta,
 }
 
+/// One installable file of a bundle, as listed by `skm items`
+#[derive(Debug, Serialize, PartialEq)]
+pub struct BundleItem {
+    #[serde(rename = "type")]
+    pub skill_type: SkillType,
+    pub name: String,
+}
+
+/// Fill `current` from `incoming` if it's still empty, warning when a
+/// second resource disagrees with the value already kept
+fn merge_meta_field(
+    current: &mut Option<String>,
+    incoming: Option<String>,
+    field: &str,
+    bundle_name: &str,
+) {
+    let Some(incoming) = incoming.filter(|v| !v.trim().is_empty()) else {
+        return;
+    };
+    match current {
+        Some(kept) if !kept.trim().is_empty() => {
+            if *kept != incoming {
+                eprintln!(
+                    "  {}: bundle '{}' has conflicting {} values ('{}' and '{}'), keeping '{}'",
+                    "Warning".yellow(),
+                    bundle_name,
+                    field,
+                    kept,
+                    incoming,
+                    kept
+                );
+            }
+        }
+        _ => *current = Some(incoming),
+    }
+}
+
+/// Paths of the entries in `dir`. An entry that can't be read is warned about
+/// and left out, so one bad file doesn't hide the rest of a source.
+pub fn dir_entries(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    Ok(std::fs::read_dir(dir)?
+        .filter_map(|entry| match entry {
+            Ok(entry) => Some(entry.path()),
+            Err(e) => {
+                warn_skipped_entry(dir, &e.to_string());
+                None
+            }
+        })
+        .collect())
+}
+
+/// Warn that an entry of `dir` was skipped while scanning
+fn warn_skipped_entry(dir: &Path, reason: &str) {
+    eprintln!(
+        "  {}: skipping an entry of {}: {}",
+        "Warning".yellow(),
+        dir.display(),
+        reason
+    );
+}
+
+/// Whether `dir` is a symlink whose target lies outside `root`.
+///
+/// Sources are read through symlinks only while they stay inside the source, so a
+/// `skills -> /etc` link can't make skm read (and install) arbitrary files.
+pub fn symlink_escapes(dir: &Path, root: &Path) -> bool {
+    let is_link = dir
+        .symlink_metadata()
+        .map(|m| m.file_type().is_symlink())
+        .unwrap_or(false);
+    if !is_link {
+        return false;
+    }
+    match (dir.canonicalize(), root.canonicalize()) {
+        (Ok(target), Ok(root)) => !target.starts_with(root),
+        // Dangling links have nothing to read anyway
+        _ => true,
+    }
+}
+
+/// Warn that a symlink escaping the source was skipped
+pub fn warn_symlink_escapes(dir: &Path) {
+    eprintln!(
+        "  {}: skipping {}, a symlink pointing outside the source",
+        "Warning".yellow(),
+        dir.display()
+    );
+}
+
+/// Repository docs that are never treated as loose skill files
+const LOOSE_SKIP_FILES: &[&str] = &["README", "CHANGELOG", "LICENSE", "CONTRIBUTING"];
+
 impl Bundle {
     /// Create a searchable string for fuzzy matching
     pub fn search_string(&self) -> String {
@@ -90,6 +401,7 @@ impl Bundle {
         if let Some(desc) = &self.meta.description {
             parts.push(desc.clone());
         }
+        parts.extend(self.meta.tags.iter().cloned());
         // Add skill/command names for searching
         for skill in &self.skills {
             parts.push(skill.name.clone());
@@ -99,6 +411,98 @@ impl Bundle {
         }
         parts.join(" ")
     }
+
+    /// Rank how well this bundle matches a search query (lower is better):
+    /// exact name, then name prefix, then name substring, then description,
+    /// then any other searchable text. `author:` and `tag:` filters in the query
+    /// have to match as well. `None` if it doesn't match at all.
+    pub fn relevance(&self, query: &str) -> Option<u8> {
+        let query = BundleQuery::parse(query);
+        if !query.matches_filters(self) {
+            return None;
+        }
+        if query.text.is_empty() {
+            return Some(0);
+        }
+        let query = query.text.to_lowercase();
+        let name = self.name.to_lowercase();
+        let description = self
+            .meta
+            .description
+            .as_deref()
+            .unwrap_or_default()
+            .to_lowercase();
+
+        if name == query {
+            Some(0)
+        } else if name.starts_with(&query) {
+            Some(1)
+        } else if name.contains(&query) {
+            Some(2)
+        } else if description.contains(&query) {
+            Some(3)
+        } else if self.search_string().to_lowercase().contains(&query) {
+            Some(4)
+        } else {
+            None
+        }
+    }
+}
+
+/// A bundle search: free text plus `author:name` and `tag:name` filters, which
+/// only match their own field
+#[derive(Debug, Default, PartialEq)]
+pub struct BundleQuery {
+    /// The words that aren't filters
+    pub text: String,
+    pub authors: Vec<String>,
+    pub tags: Vec<String>,
+}
+
+impl BundleQuery {
+    pub fn parse(query: &str) -> Self {
+        let mut parsed = BundleQuery::default();
+        let mut words = Vec::new();
+        for word in query.split_whitespace() {
+            match word.split_once(':') {
+                Some((key, value)) if !value.is_empty() && key.eq_ignore_ascii_case("author") => {
+                    parsed.authors.push(value.to_lowercase())
+                }
+                Some((key, value)) if !value.is_empty() && key.eq_ignore_ascii_case("tag") => {
+                    parsed.tags.push(value.to_lowercase())
+                }
+                _ => words.push(word),
+            }
+        }
+        parsed.text = words.join(" ");
+        parsed
+    }
+
+    /// Whether `bundle` passes every filter. An author matches as a whole or by
+    /// one of its words (`author:alice` finds "Alice Smith"); tags match exactly.
+    /// Both ignore case.
+    pub fn matches_filters(&self, bundle: &Bundle) -> bool {
+        let author = bundle.meta.author.as_deref().unwrap_or_default().to_lowercase();
+        let by = |name: &String| author == *name || author.split_whitespace().any(|w| w == name);
+        self.authors.iter().all(by)
+            && self
+                .tags
+                .iter()
+                .all(|tag| bundle.meta.tags.iter().any(|t| t.to_lowercase() == *tag))
+    }
+}
+
+/// Order `(source, bundle)` pairs for display. With a query, non-matching
+/// bundles are dropped and the rest sorted by relevance; ties (and the no-query
+/// case) fall back to alphabetical order, keeping source priority for equal names.
+pub fn sort_by_relevance(bundles: &mut Vec<(String, Bundle)>, query: Option<&str>) {
+    match query.filter(|q| !q.is_empty()) {
+        Some(query) => {
+            bundles.retain(|(_, b)| b.relevance(query).is_some());
+            bundles.sort_by_key(|(_, b)| (b.relevance(query), b.name.to_lowercase()));
+        }
+        None => bundles.sort_by_key(|(_, b)| b.name.to_lowercase()),
+    }
 }
 
 impl Bundle {
@@ -114,6 +518,9 @@ impl Bundle {
         let agents = Self::scan_type(&path, SkillType::Agent)?;
         let commands = Self::scan_type(&path, SkillType::Command)?;
         let rules = Self::scan_type(&path, SkillType::Rule)?;
+        let meta = Self::load_meta_yaml(&path)
+            .map(BundleMeta::from)
+            .unwrap_or_default();
 
         Ok(Bundle {
             name,
@@ -122,10 +529,56 @@ impl Bundle {
             agents,
             commands,
             rules,
-            meta: BundleMeta::default(),
+            meta,
         })
     }
 
+    /// Create a bundle from a plain directory of `.md` files (no component
+    /// subdirectories), treating every file as `skill_type`
+    pub fn from_loose_files(path: PathBuf, skill_type: SkillType) -> anyhow::Result<Self> {
+        let name = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .ok_or_else(|| anyhow::anyhow!("Invalid bundle path"))?
+            .to_string();
+
+        let mut files = vec![];
+        for file in dir_entries(&path)? {
+            let is_markdown = file.is_file() && file.extension().is_some_and(|e| e == "md");
+            if !is_markdown {
+                continue;
+            }
+            let stem = file.file_stem().and_then(|n| n.to_str()).unwrap_or("");
+            if stem.is_empty() || LOOSE_SKIP_FILES.contains(&stem.to_uppercase().as_str()) {
+                continue;
+            }
+            files.push(SkillFile {
+                name: stem.to_string(),
+                path: file,
+                skill_type,
+                source_dir: None,
+            });
+        }
+        files.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut bundle = Bundle {
+            name,
+            path,
+            skills: vec![],
+            agents: vec![],
+            commands: vec![],
+            rules: vec![],
+            meta: BundleMeta::default(),
+        };
+        match skill_type {
+            SkillType::Skill => bundle.skills = files,
+            SkillType::Agent => bundle.agents = files,
+            SkillType::Command => bundle.commands = files,
+            SkillType::Rule => bundle.rules = files,
+        }
+        Ok(bundle)
+    }
+
     /// Create multiple bundles from a resources-format directory
     /// Each resource folder becomes its own bundle (for community repos)
     /// Structure: resources/{skills,commands,agents,cursor-rules}/resource-name/{meta.yaml,*.md}
@@ -154,10 +607,7 @@ impl Bundle {
                     continue;
                 }
 
-                for entry in std::fs::read_dir(&type_dir)? {
-                    let entry = entry?;
-                    let resource_dir = entry.path();
-
+                for resource_dir in dir_entries(&type_dir)? {
                     if !resource_dir.is_dir() {
                         continue;
                     }
@@ -180,10 +630,6 @@ impl Bundle {
                     )? {
                         let bundle_name = skill_file.name.clone();
                         let bundle = bundles.entry(bundle_name.clone()).or_insert_with(|| {
-                            let meta = BundleMeta {
-                                author: resource_meta.author.clone(),
-                                description: resource_meta.description.clone(),
-                            };
                             Bundle {
                                 name: bundle_name,
                                 path: resource_dir.clone(),
@@ -191,9 +637,57 @@ impl Bundle {
                                 agents: vec![],
                                 commands: vec![],
                                 rules: vec![],
-                                meta,
+                                meta: BundleMeta::default(),
                             }
                         });
+                        // Several resource folders can share a bundle name:
+                        // keep the first non-empty value of each field
+                        merge_meta_field(
+                            &mut bundle.meta.author,
+                            resource_meta.author,
+                            "author",
+                            &bundle.name,
+                        );
+                        merge_meta_field(
+                            &mut bundle.meta.description,
+                            resource_meta.description,
+                            "description",
+                            &bundle.name,
+                        );
+                        merge_meta_field(
+                            &mut bundle.meta.homepage,
+                            resource_meta.homepage,
+                            "homepage",
+                            &bundle.name,
+                        );
+                        merge_meta_field(
+                            &mut bundle.meta.repository,
+                            resource_meta.repository,
+                            "repository",
+                            &bundle.name,
+                        );
+                        merge_meta_field(
+                            &mut bundle.meta.license,
+                            resource_meta.license,
+                            "license",
+                            &bundle.name,
+                        );
+                        merge_meta_field(
+                            &mut bundle.meta.version,
+                            resource_meta.version,
+                            "version",
+                            &bundle.name,
+                        );
+                        for required in resource_meta.requires {
+                            if !bundle.meta.requires.contains(&required) {
+                                bundle.meta.requires.push(required);
+                            }
+                        }
+                        for tag in resource_meta.tags {
+                            if !bundle.meta.tags.contains(&tag) {
+                                bundle.meta.tags.push(tag);
+                            }
+                        }
 
                         match skill_type {
                             SkillType::Skill => bundle.skills.push(skill_file),
@@ -212,15 +706,15 @@ impl Bundle {
     }
 
     /// Check if a path uses the resources format
-    pub fn is_resources_format(path: &PathBuf) -> bool {
+    pub fn is_resources_format(path: &Path) -> bool {
         path.join("resources").is_dir()
     }
 
     /// Check if a path uses the Anthropic/marketplace format
     /// Structure: skills/{name}/SKILL.md at the root level
-    pub fn is_anthropic_format(path: &PathBuf) -> bool {
+    pub fn is_anthropic_format(path: &Path) -> bool {
         let skills_dir = path.join("skills");
-        if !skills_dir.is_dir() {
+        if !skills_dir.is_dir() || symlink_escapes(&skills_dir, path) {
             return false;
         }
 
@@ -244,13 +738,14 @@ impl Bundle {
         if !skills_dir.exists() {
             return Ok(vec![]);
         }
+        if symlink_escapes(&skills_dir, &path) {
+            warn_symlink_escapes(&skills_dir);
+            return Ok(vec![]);
+        }
 
         let mut bundles = vec![];
 
-        for entry in std::fs::read_dir(&skills_dir)? {
-            let entry = entry?;
-            let skill_dir = entry.path();
-
+        for skill_dir in dir_entries(&skills_dir)? {
             if !skill_dir.is_dir() {
                 continue;
             }
@@ -274,10 +769,7 @@ impl Bundle {
                 .and_then(|fm| fm.name.clone())
                 .unwrap_or_else(|| folder_name.to_string());
 
-            let meta = BundleMeta {
-                author: frontmatter.as_ref().and_then(|fm| fm.author.clone()),
-                description: frontmatter.as_ref().and_then(|fm| fm.description.clone()),
-            };
+            let meta = frontmatter.map(BundleMeta::from).unwrap_or_default();
 
             let skill_file = SkillFile {
                 name: name.clone(),
@@ -302,52 +794,55 @@ impl Bundle {
     }
 
     /// Extract full metadata from YAML frontmatter in a markdown file
-    fn extract_frontmatter(path: &PathBuf) -> Option<ResourceMeta> {
-        let content = std::fs::read_to_string(path).ok()?;
-        if !content.starts_with("---") {
-            return None;
-        }
-
-        // Find end of frontmatter
-        let rest = &content[3..];
-        let end_idx = rest.find("---")?;
-        let frontmatter = &rest[..end_idx];
+    fn extract_frontmatter(path: &Path) -> Option<ResourceMeta> {
+        Self::frontmatter_meta(&read_lossy(path)?)
+    }
 
-        serde_yaml::from_str(frontmatter).ok()
+    /// Metadata from the YAML frontmatter of markdown `content`
+    pub fn frontmatter_meta(content: &str) -> Option<ResourceMeta> {
+        // Unterminated frontmatter has no metadata
+        let (yaml, Some(_)) = crate::frontmatter::split(content)? else {
+            return None;
+        };
+        Some(ResourceMeta::parse_lenient(yaml))
     }
 
     /// Load metadata from meta.yaml file
-    fn load_meta_yaml(dir: &PathBuf) -> Option<ResourceMeta> {
+    fn load_meta_yaml(dir: &Path) -> Option<ResourceMeta> {
         let meta_path = dir.join("meta.yaml");
         if !meta_path.exists() {
             return None;
         }
-        let content = std::fs::read_to_string(&meta_path).ok()?;
-        serde_yaml::from_str(&content).ok()
+        let content = read_lossy(&meta_path)?;
+        Some(ResourceMeta::parse_lenient(&content))
     }
 
     /// Scan a subdirectory for skill files.
     /// Handles BOTH flat .md files AND {name}/SKILL.md directory format.
-    fn scan_type(bundle_path: &PathBuf, skill_type: SkillType) -> anyhow::Result<Vec<SkillFile>> {
+    fn scan_type(bundle_path: &Path, skill_type: SkillType) -> anyhow::Result<Vec<SkillFile>> {
         let type_dir = bundle_path.join(skill_type.dir_name());
 
         if !type_dir.exists() {
             return Ok(vec![]);
         }
 
-        let mut files = vec![];
+        // Component dirs may link anywhere in the source holding the bundle
+        let source_root = bundle_path.parent().unwrap_or(bundle_path);
+        if symlink_escapes(&type_dir, source_root) {
+            warn_symlink_escapes(&type_dir);
+            return Ok(vec![]);
+        }
 
-        for entry in std::fs::read_dir(&type_dir)? {
-            let entry = entry?;
-            let path = entry.path();
+        let mut files = vec![];
 
+        for path in dir_entries(&type_dir)? {
             if path.is_file() && path.extension().is_some_and(|e| e == "md") {
                 // Flat .md file (e.g., commands/commit.md)
-                let name = path
-                    .file_stem()
-                    .and_then(|n| n.to_str())
-                    .ok_or_else(|| anyhow::anyhow!("Invalid file name"))?
-                    .to_string();
+                let Some(name) = path.file_stem().and_then(|n| n.to_str()) else {
+                    warn_skipped_entry(&type_dir, "file name is not valid UTF-8");
+                    continue;
+                };
+                let name = name.to_string();
 
                 files.push(SkillFile {
                     name,
@@ -420,7 +915,7 @@ impl Bundle {
     /// Scan a single resource folder for meta.yaml and content .md file
     /// Returns both the skill file and the metadata
     fn scan_resource_folder_with_meta(
-        resource_dir: &PathBuf,
+        resource_dir: &Path,
         skill_type: SkillType,
         folder_name: &str,
     ) -> anyhow::Result<Option<(SkillFile, ResourceMeta)>> {
@@ -453,10 +948,7 @@ impl Bundle {
         }
 
         // Fall back to any .md file (excluding meta files)
-        for entry in std::fs::read_dir(resource_dir)? {
-            let entry = entry?;
-            let path = entry.path();
-
+        for path in dir_entries(resource_dir)? {
             if path.is_file() && path.extension().is_some_and(|e| e == "md") {
                 return Ok(Some((
                     SkillFile {
@@ -483,6 +975,19 @@ impl Bundle {
         }
     }
 
+    /// Every installable item, grouped by type (skills, agents, commands, rules)
+    pub fn items(&self) -> Vec<BundleItem> {
+        [SkillType::Skill, SkillType::Agent, SkillType::Command, SkillType::Rule]
+            .into_iter()
+            .flat_map(|skill_type| {
+                self.files_of_type(skill_type).iter().map(move |f| BundleItem {
+                    skill_type,
+                    name: f.name.clone(),
+                })
+            })
+            .collect()
+    }
+
     /// Check if bundle is empty (no files)
     pub fn is_empty(&self) -> bool {
         self.skills.is_empty()
@@ -490,6 +995,14 @@ impl Bundle {
             && self.commands.is_empty()
             && self.rules.is_empty()
     }
+
+    /// The types this bundle has files of, in `SkillType::ALL` order
+    pub fn types(&self) -> Vec<SkillType> {
+        SkillType::ALL
+            .into_iter()
+            .filter(|t| !self.files_of_type(*t).is_empty())
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -511,11 +1024,11 @@ mod tests {
         let dir = tempdir().unwrap();
 
         // Without resources/ directory
-        assert!(!Bundle::is_resources_format(&dir.path().to_path_buf()));
+        assert!(!Bundle::is_resources_format(dir.path()));
 
         // With resources/ directory
         fs::create_dir(dir.path().join("resources")).unwrap();
-        assert!(Bundle::is_resources_format(&dir.path().to_path_buf()));
+        assert!(Bundle::is_resources_format(dir.path()));
     }
 
     #[test]
@@ -545,6 +1058,67 @@ mod tests {
         assert_eq!(bundles[0].skills[0].name, "My Awesome Skill");
     }
 
+    #[test]
+    fn test_meta_yaml_provenance_fields() {
+        let dir = tempdir().unwrap();
+        let skill_folder = dir.path().join("resources/skills/pdf");
+        fs::create_dir_all(&skill_folder).unwrap();
+        fs::write(
+            skill_folder.join("meta.yaml"),
+            "name: pdf\nauthor: alice\nhomepage: https://example.com/pdf\n\
+             repository: https://github.com/alice/pdf\nlicense: MIT\n",
+        )
+        .unwrap();
+        fs::write(skill_folder.join("skill.md"), "# PDF").unwrap();
+
+        let bundles = Bundle::list_from_resources_path(dir.path().to_path_buf()).unwrap();
+        let meta = &bundles[0].meta;
+        assert_eq!(meta.homepage.as_deref(), Some("https://example.com/pdf"));
+        assert_eq!(meta.repository.as_deref(), Some("https://github.com/alice/pdf"));
+        assert_eq!(meta.license.as_deref(), Some("MIT"));
+        assert_eq!(
+            meta.details(),
+            vec![
+                ("Author", "alice"),
+                ("Homepage", "https://example.com/pdf"),
+                ("Repository", "https://github.com/alice/pdf"),
+                ("License", "MIT"),
+            ]
+        );
+        assert!(BundleMeta::default().details().is_empty());
+    }
+
+    #[test]
+    fn test_resources_sharing_a_bundle_merge_meta() {
+        let dir = tempdir().unwrap();
+        let skill_folder = dir.path().join("resources/skills/helper-skill");
+        let command_folder = dir.path().join("resources/commands/helper-cmd");
+        fs::create_dir_all(&skill_folder).unwrap();
+        fs::create_dir_all(&command_folder).unwrap();
+        fs::write(
+            skill_folder.join("meta.yaml"),
+            "name: helper\ndescription: Skill side\n",
+        )
+        .unwrap();
+        fs::write(skill_folder.join("skill.md"), "# Skill").unwrap();
+        fs::write(
+            command_folder.join("meta.yaml"),
+            "name: helper\nauthor: alice\ndescription: Command side\n",
+        )
+        .unwrap();
+        fs::write(command_folder.join("command.md"), "# Command").unwrap();
+
+        let bundles = Bundle::list_from_resources_path(dir.path().to_path_buf()).unwrap();
+        assert_eq!(bundles.len(), 1);
+        let bundle = &bundles[0];
+        // The first non-empty value of each field wins
+        assert_eq!(bundle.meta.author.as_deref(), Some("alice"));
+        assert_eq!(bundle.meta.description.as_deref(), Some("Skill side"));
+        // Each resource keeps its own folder for companion files
+        assert_eq!(bundle.skills[0].source_dir.as_deref(), Some(skill_folder.as_path()));
+        assert_eq!(bundle.commands[0].source_dir.as_deref(), Some(command_folder.as_path()));
+    }
+
     #[test]
     fn test_resources_format_cursor_rules() {
         let dir = tempdir().unwrap();
@@ -612,22 +1186,60 @@ mod tests {
     }
 
     // Anthropic format tests
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinked_skills_dir_must_stay_in_source() {
+        use crate::source::{LocalSource, Source};
+        use std::os::unix::fs::symlink;
+
+        let outside = tempdir().unwrap();
+        fs::create_dir_all(outside.path().join("secret")).unwrap();
+        fs::write(outside.path().join("secret/SKILL.md"), "# Secret").unwrap();
+
+        let source = tempdir().unwrap();
+        fs::create_dir_all(source.path().join("shared/helper")).unwrap();
+        fs::write(source.path().join("shared/helper/SKILL.md"), "# Helper").unwrap();
+        let bundle_dir = source.path().join("tools");
+        fs::create_dir_all(&bundle_dir).unwrap();
+
+        // A link to a sibling inside the source is followed
+        symlink(source.path().join("shared"), bundle_dir.join("skills")).unwrap();
+        let bundle = Bundle::from_path(bundle_dir.clone()).unwrap();
+        assert_eq!(bundle.skills.len(), 1);
+        assert_eq!(bundle.skills[0].name, "helper");
+
+        // A link out of the source is not
+        fs::remove_file(bundle_dir.join("skills")).unwrap();
+        symlink(outside.path(), bundle_dir.join("skills")).unwrap();
+        let bundle = Bundle::from_path(bundle_dir).unwrap();
+        assert!(bundle.skills.is_empty());
+
+        // Nor is an Anthropic-format `skills` dir at the source root
+        let anthropic = tempdir().unwrap();
+        symlink(outside.path(), anthropic.path().join("skills")).unwrap();
+        assert!(!Bundle::is_anthropic_format(anthropic.path()));
+        let bundles = LocalSource::new(anthropic.path().to_path_buf())
+            .list_bundles()
+            .unwrap();
+        assert!(bundles.is_empty());
+    }
+
     #[test]
     fn test_anthropic_format_detection() {
         let dir = tempdir().unwrap();
 
         // Without skills/ directory
-        assert!(!Bundle::is_anthropic_format(&dir.path().to_path_buf()));
+        assert!(!Bundle::is_anthropic_format(dir.path()));
 
         // With skills/ directory but no SKILL.md
         fs::create_dir(dir.path().join("skills")).unwrap();
-        assert!(!Bundle::is_anthropic_format(&dir.path().to_path_buf()));
+        assert!(!Bundle::is_anthropic_format(dir.path()));
 
         // With skills/{name}/SKILL.md
         let skill_dir = dir.path().join("skills").join("my-skill");
         fs::create_dir_all(&skill_dir).unwrap();
         fs::write(skill_dir.join("SKILL.md"), "# Skill content").unwrap();
-        assert!(Bundle::is_anthropic_format(&dir.path().to_path_buf()));
+        assert!(Bundle::is_anthropic_format(dir.path()));
     }
 
     #[test]
@@ -752,6 +1364,173 @@ mod tests {
         assert_eq!(meta.unwrap().name, None);
     }
 
+    #[test]
+    fn test_sort_by_relevance_ranks_exact_name_first() {
+        let bundle = |name: &str, description: &str| Bundle {
+            name: name.to_string(),
+            path: PathBuf::from(name),
+            skills: vec![],
+            agents: vec![],
+            commands: vec![],
+            rules: vec![],
+            meta: BundleMeta {
+                description: Some(description.to_string()),
+                ..Default::default()
+            },
+        };
+        let mut bundles: Vec<(String, Bundle)> = vec![
+            bundle("auto-commit", "Commits for you"),
+            bundle("changelog", "Summarize each commit"),
+            bundle("commit", "Write commit messages"),
+            bundle("commit-lint", "Lint messages"),
+            bundle("pdf", "PDF tools"),
+        ]
+        .into_iter()
+        .map(|b| ("src".to_string(), b))
+        .collect();
+
+        sort_by_relevance(&mut bundles, Some("Commit"));
+        let names: Vec<&str> = bundles.iter().map(|(_, b)| b.name.as_str()).collect();
+        assert_eq!(names, vec!["commit", "commit-lint", "auto-commit", "changelog"]);
+
+        sort_by_relevance(&mut bundles, None);
+        let names: Vec<&str> = bundles.iter().map(|(_, b)| b.name.as_str()).collect();
+        assert_eq!(names, vec!["auto-commit", "changelog", "commit", "commit-lint"]);
+    }
+
+    #[test]
+    fn test_author_and_tag_filters() {
+        let bundle = |name: &str, author: &str, tags: &[&str]| Bundle {
+            name: name.to_string(),
+            path: PathBuf::from(name),
+            skills: vec![],
+            agents: vec![],
+            commands: vec![],
+            rules: vec![],
+            meta: BundleMeta {
+                author: Some(author.to_string()),
+                description: Some("Reviewed with alice".to_string()),
+                tags: tags.iter().map(|t| t.to_string()).collect(),
+                ..Default::default()
+            },
+        };
+        let all: Vec<(String, Bundle)> = vec![
+            bundle("commit", "alice", &["git"]),
+            bundle("pdf", "Alice Smith", &["Docs"]),
+            bundle("review", "bob", &["git"]),
+            bundle("sql", "alicent", &[]),
+        ]
+        .into_iter()
+        .map(|b| ("src".to_string(), b))
+        .collect();
+        let search = |query: &str| {
+            let mut bundles = all.clone();
+            sort_by_relevance(&mut bundles, Some(query));
+            bundles.into_iter().map(|(_, b)| b.name).collect::<Vec<_>>()
+        };
+
+        assert_eq!(search("author:alice"), vec!["commit", "pdf"]);
+        assert_eq!(search("tag:git"), vec!["commit", "review"]);
+        assert_eq!(search("tag:docs"), vec!["pdf"]);
+        assert_eq!(search("Author:ALICE tag:git"), vec!["commit"]);
+        assert_eq!(search("author:alice pdf"), vec!["pdf"]);
+
+        let query = BundleQuery::parse("pdf author:bob forms tag:");
+        assert_eq!(query.text, "pdf forms tag:");
+        assert_eq!(query.authors, vec!["bob"]);
+        assert!(query.tags.is_empty());
+    }
+
+    #[test]
+    fn test_version_parsing() {
+        let dir = tempdir().unwrap();
+        let version = |yaml: &str| {
+            fs::write(dir.path().join("meta.yaml"), yaml).unwrap();
+            Bundle::load_meta_yaml(dir.path()).unwrap().version
+        };
+        assert_eq!(version("version: \"1.10\"\n"), Some("1.10".to_string()));
+        assert_eq!(version("version: 1.0\n"), Some("1.0".to_string()));
+        assert_eq!(version("version: 2\n"), Some("2".to_string()));
+        assert_eq!(version("name: pdf\n"), None);
+        // Falling back to line-by-line reading keeps the text as written
+        assert_eq!(version("version: 1.10\nbroken: [\n"), Some("1.10".to_string()));
+
+        let meta = BundleMeta::from(ResourceMeta::parse_lenient("version: v3.1.0\n"));
+        assert_eq!(meta.version.as_deref(), Some("v3.1.0"));
+        assert!(meta.details().contains(&("Version", "v3.1.0")));
+    }
+
+    #[test]
+    fn test_version_comparison() {
+        use std::cmp::Ordering;
+
+        assert_eq!(compare_versions("1.10", "1.9"), Ordering::Greater);
+        assert_eq!(compare_versions("1.0", "1"), Ordering::Equal);
+        assert_eq!(compare_versions("v2.0.1", "2.0"), Ordering::Greater);
+        assert_eq!(compare_versions("1.0-beta", "1.0-alpha"), Ordering::Greater);
+        assert_eq!(compare_versions("1.0-beta", "1.0"), Ordering::Less);
+        assert_eq!(compare_versions("1.0-alpha", "1.0-beta"), Ordering::Less);
+        assert_eq!(compare_versions("1.0-rc.2", "1.0-rc.10"), Ordering::Less);
+        assert_eq!(compare_versions("1.0+x", "1.0"), Ordering::Equal);
+        assert_eq!(compare_versions("1.1-beta", "1.0"), Ordering::Greater);
+        assert_eq!(compare_versions("0.9", "1.0"), Ordering::Less);
+
+        assert!(version_needs_refresh(Some("1.0"), Some("1.1")));
+        assert!(!version_needs_refresh(Some("1.1"), Some("1.1")));
+        assert!(!version_needs_refresh(Some("2.0"), Some("1.9")));
+        assert!(version_needs_refresh(Some("1.0-beta"), Some("1.0")));
+        assert!(!version_needs_refresh(Some("1.0"), Some("1.0+build.7")));
+        // Without both versions there's nothing to compare
+        assert!(version_needs_refresh(None, Some("1.0")));
+        assert!(version_needs_refresh(Some("1.0"), None));
+    }
+
+    #[test]
+    fn test_lenient_meta_keeps_valid_fields() {
+        let dir = tempdir().unwrap();
+
+        // Unbalanced quote in description breaks the YAML document
+        fs::write(
+            dir.path().join("meta.yaml"),
+            "name: helper\nauthor: \"Jos\u{e9}\" M\nbroken: [unclosed\ndescription: \"Half quoted\n",
+        )
+        .unwrap();
+        let meta = Bundle::load_meta_yaml(dir.path()).unwrap();
+        assert_eq!(meta.name, Some("helper".to_string()));
+        assert_eq!(meta.author, Some("\"Jos\u{e9}\" M".to_string()));
+        assert_eq!(meta.description, Some("\"Half quoted".to_string()));
+
+        // Latin-1 bytes don't make the file unreadable
+        fs::write(
+            dir.path().join("meta.yaml"),
+            b"name: latin\nauthor: Jos\xe9\n".as_slice(),
+        )
+        .unwrap();
+        let meta = Bundle::load_meta_yaml(dir.path()).unwrap();
+        assert_eq!(meta.name, Some("latin".to_string()));
+        assert_eq!(meta.author, Some("Jos\u{fffd}".to_string()));
+    }
+
+    #[test]
+    fn test_extract_frontmatter_dashes_in_value() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("test.md");
+
+        fs::write(
+            &file,
+            "---\nname: Dash Skill\ndescription: \"before --- after\"\nauthor: Someone---Else\n---\n\n# Content\n\n---\n",
+        )
+        .unwrap();
+        let meta = Bundle::extract_frontmatter(&file).unwrap();
+        assert_eq!(meta.name, Some("Dash Skill".to_string()));
+        assert_eq!(meta.description, Some("before --- after".to_string()));
+        assert_eq!(meta.author, Some("Someone---Else".to_string()));
+
+        // Unterminated frontmatter is not parsed
+        fs::write(&file, "---\nname: Open\n").unwrap();
+        assert!(Bundle::extract_frontmatter(&file).is_none());
+    }
+
     #[test]
     fn test_anthropic_format_sets_source_dir() {
         let dir = tempdir().unwrap();
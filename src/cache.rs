@@ -0,0 +1,209 @@
+use anyhow::Result;
+use colored::Colorize;
+use std::path::{Path, PathBuf};
+
+use crate::config::{Config, SourceConfig};
+use crate::source::{ArchiveSource, GitSource};
+use crate::stats::{dir_size, format_size};
+
+/// Where one configured git or archive source is cached (`skm cache`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct CacheEntry {
+    /// Source name, if the config gives one
+    pub name: Option<String>,
+    pub url: String,
+    /// The shared clone (or extracted archive); commit checkouts from
+    /// `skm sync` sit next to it
+    pub path: PathBuf,
+}
+
+impl CacheEntry {
+    /// The cache of every configured git and archive source, in config order
+    pub fn from_config(config: &Config) -> Vec<CacheEntry> {
+        config
+            .source_configs()
+            .iter()
+            .filter_map(|s| match s {
+                SourceConfig::Git { url, name, .. } => {
+                    let git = GitSource::new(url.clone()).ok()?;
+                    Some(CacheEntry {
+                        name: name.clone(),
+                        url: url.clone(),
+                        path: git.cache_path().to_path_buf(),
+                    })
+                }
+                SourceConfig::Archive { url, name, .. } => {
+                    let archive = ArchiveSource::new(url.clone()).ok()?;
+                    Some(CacheEntry {
+                        name: name.clone(),
+                        url: url.clone(),
+                        path: archive.cache_path().to_path_buf(),
+                    })
+                }
+                SourceConfig::Local { .. } => None,
+            })
+            .collect()
+    }
+
+    /// The source name, or its URL when unnamed
+    pub fn label(&self) -> &str {
+        self.name.as_deref().unwrap_or(&self.url)
+    }
+
+    /// Whether `name` refers to this source (by name or URL)
+    pub fn matches(&self, name: &str) -> bool {
+        self.name.as_deref() == Some(name) || self.url == name
+    }
+
+    /// The clone and any `repo@commit` checkouts next to it that exist
+    fn dirs(&self) -> Vec<PathBuf> {
+        let mut dirs = Vec::new();
+        if self.path.exists() {
+            dirs.push(self.path.clone());
+        }
+        let (Some(parent), Some(repo)) = (self.path.parent(), self.path.file_name()) else {
+            return dirs;
+        };
+        let prefix = format!("{}@", repo.to_string_lossy());
+        if let Ok(entries) = std::fs::read_dir(parent) {
+            let mut pinned: Vec<PathBuf> = entries
+                .filter_map(|e| e.ok())
+                .filter(|e| e.file_name().to_string_lossy().starts_with(&prefix))
+                .map(|e| e.path())
+                .filter(|p| p.is_dir())
+                .collect();
+            pinned.sort();
+            dirs.extend(pinned);
+        }
+        dirs
+    }
+
+    /// Disk usage of the cache in bytes, or `None` when nothing is cached
+    pub fn size(&self) -> Option<u64> {
+        let dirs = self.dirs();
+        (!dirs.is_empty()).then(|| dirs.iter().map(|d| dir_size(d)).sum())
+    }
+
+    /// Delete the cache so the next use clones again. Returns the bytes freed,
+    /// or `None` when nothing was cached.
+    pub fn clear(&self) -> Result<Option<u64>> {
+        let size = self.size();
+        for dir in self.dirs() {
+            std::fs::remove_dir_all(&dir)?;
+        }
+        Ok(size)
+    }
+}
+
+/// Print every git source's cache path and size
+pub fn list(entries: &[CacheEntry], cache_root: &Path) {
+    if entries.is_empty() {
+        println!("{}", "No git sources configured.".yellow());
+        return;
+    }
+
+    println!("{} {}", "Git source caches in".bold(), cache_root.display());
+    let mut total = 0;
+    for entry in entries {
+        let size = match entry.size() {
+            Some(bytes) => {
+                total += bytes;
+                format_size(bytes).cyan()
+            }
+            None => "not cloned".dimmed(),
+        };
+        println!("  {} {}", entry.label(), size);
+        println!("    {}", entry.path.display().to_string().dimmed());
+    }
+    println!();
+    println!("{} {}", "Total:".bold(), format_size(total).cyan());
+}
+
+/// Remove the cache of the source called `name`, or every cache under
+/// `cache_root` (including ones of sources no longer configured)
+pub fn clear(entries: &[CacheEntry], name: Option<&str>, cache_root: &Path) -> Result<()> {
+    let Some(name) = name else {
+        if !cache_root.exists() {
+            println!("{}", "Nothing cached.".yellow());
+            return Ok(());
+        }
+        let bytes = dir_size(cache_root);
+        std::fs::remove_dir_all(cache_root)?;
+        println!(
+            "{} Cleared all git caches ({})",
+            "✓".green(),
+            format_size(bytes)
+        );
+        return Ok(());
+    };
+
+    let entry = entries.iter().find(|e| e.matches(name)).ok_or_else(|| {
+        anyhow::anyhow!("No git source named `{}` (see `skm cache list`)", name)
+    })?;
+    match entry.clear()? {
+        Some(bytes) => println!(
+            "{} Cleared cache of {} ({}); it will be cloned again on next use",
+            "✓".green(),
+            entry.label(),
+            format_size(bytes)
+        ),
+        None => println!("{} {}", "Nothing cached for".yellow(), entry.label()),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn entry(cache_root: &Path, repo: &str) -> CacheEntry {
+        CacheEntry {
+            name: Some(repo.to_string()),
+            url: format!("https://example.com/{}", repo),
+            path: cache_root.join("example.com").join(repo),
+        }
+    }
+
+    #[test]
+    fn test_size_counts_clone_and_pinned_checkouts() {
+        let cache = tempdir().unwrap();
+        let skills = entry(cache.path(), "skills");
+        let other = entry(cache.path(), "skills-extra");
+        let missing = entry(cache.path(), "missing");
+        fs::create_dir_all(&skills.path).unwrap();
+        fs::write(skills.path.join("file"), [0u8; 100]).unwrap();
+        let pinned = cache.path().join("example.com/skills@abc123");
+        fs::create_dir_all(&pinned).unwrap();
+        fs::write(pinned.join("file"), [0u8; 50]).unwrap();
+        fs::create_dir_all(&other.path).unwrap();
+        fs::write(other.path.join("file"), [0u8; 7]).unwrap();
+
+        assert_eq!(skills.size(), Some(150));
+        assert_eq!(other.size(), Some(7));
+        assert_eq!(missing.size(), None);
+    }
+
+    #[test]
+    fn test_clear_removes_one_cache_or_all() {
+        let cache = tempdir().unwrap();
+        let root = cache.path().join("skm");
+        let entries = vec![entry(&root, "skills"), entry(&root, "tools")];
+        for e in &entries {
+            fs::create_dir_all(&e.path).unwrap();
+            fs::write(e.path.join("file"), "x").unwrap();
+        }
+        fs::create_dir_all(root.join("example.com/skills@abc123")).unwrap();
+
+        clear(&entries, Some("skills"), &root).unwrap();
+        assert!(!entries[0].path.exists());
+        assert!(!root.join("example.com/skills@abc123").exists());
+        assert!(entries[1].path.exists());
+
+        assert!(clear(&entries, Some("unknown"), &root).is_err());
+
+        clear(&entries, None, &root).unwrap();
+        assert!(!root.exists());
+    }
+}
@@ -0,0 +1,129 @@
+use serde::Serialize;
+
+use crate::bundle::Bundle;
+use crate::config::Config;
+
+/// Every source's bundles, for `skm list --json`
+#[derive(Debug, Serialize, Default, PartialEq)]
+pub struct Catalog {
+    pub sources: Vec<CatalogSource>,
+    /// Sources that could not be read, as `source - error`
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct CatalogSource {
+    pub source: String,
+    pub bundles: Vec<CatalogBundle>,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct CatalogBundle {
+    pub name: String,
+    pub description: Option<String>,
+    pub author: Option<String>,
+    pub version: Option<String>,
+    pub files: FileCounts,
+}
+
+/// Number of files of each type in a bundle
+#[derive(Debug, Serialize, Default, PartialEq)]
+pub struct FileCounts {
+    pub skills: usize,
+    pub agents: usize,
+    pub commands: usize,
+    pub rules: usize,
+}
+
+impl From<&Bundle> for CatalogBundle {
+    fn from(bundle: &Bundle) -> Self {
+        CatalogBundle {
+            name: bundle.name.clone(),
+            description: bundle.meta.description.clone(),
+            author: bundle.meta.author.clone(),
+            version: bundle.meta.version.clone(),
+            files: FileCounts {
+                skills: bundle.skills.len(),
+                agents: bundle.agents.len(),
+                commands: bundle.commands.len(),
+                rules: bundle.rules.len(),
+            },
+        }
+    }
+}
+
+impl Catalog {
+    /// List every source's bundles, limited to those changed since `since` when
+    /// given. A source that fails to list becomes a warning instead of an error.
+    pub fn collect(config: &Config, since: Option<&str>) -> Self {
+        let mut catalog = Catalog::default();
+        for (source, listed) in config.list_all_bundles() {
+            let listed = match since {
+                Some(since) => listed.and_then(|mut bundles| {
+                    let changed = source.changed_since(since)?.unwrap_or_default();
+                    bundles.retain(|b| changed.contains(&b.name));
+                    Ok(bundles)
+                }),
+                None => listed,
+            };
+            match listed {
+                Ok(bundles) => catalog.sources.push(CatalogSource {
+                    source: source.display_path(),
+                    bundles: bundles.iter().map(CatalogBundle::from).collect(),
+                }),
+                Err(e) => catalog
+                    .warnings
+                    .push(format!("{} - {:#}", source.display_path(), e)),
+            }
+        }
+        catalog
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::SourceConfig;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_catalog_serializes_bundles_and_warnings() {
+        let dir = tempdir().unwrap();
+        let pdf = dir.path().join("pdf");
+        fs::create_dir_all(pdf.join("skills/fill")).unwrap();
+        fs::write(pdf.join("skills/fill/SKILL.md"), "---\ndescription: Fill\n---\n").unwrap();
+        fs::create_dir_all(pdf.join("commands")).unwrap();
+        fs::write(pdf.join("commands/extract.md"), "# Extract\n").unwrap();
+        fs::write(pdf.join("commands/merge.md"), "# Merge\n").unwrap();
+        let meta = "author: Alice\ndescription: PDF tools\nversion: 1.2\n";
+        fs::write(pdf.join("meta.yaml"), meta).unwrap();
+        // A file where a source folder is expected can't be listed
+        let broken = dir.path().join("notes.txt");
+        fs::write(&broken, "not a source\n").unwrap();
+        let config = Config::new(vec![
+            SourceConfig::local(dir.path().to_string_lossy().to_string(), None),
+            SourceConfig::local(broken.to_string_lossy().to_string(), None),
+        ]);
+
+        let catalog = Catalog::collect(&config, None);
+        let value = serde_json::to_value(&catalog).unwrap();
+        assert_eq!(
+            value["sources"][0]["bundles"],
+            serde_json::json!([{
+                "name": "pdf",
+                "description": "PDF tools",
+                "author": "Alice",
+                "version": "1.2",
+                "files": {"skills": 1, "agents": 0, "commands": 2, "rules": 0},
+            }])
+        );
+        assert_eq!(catalog.sources.len(), 1);
+        assert_eq!(catalog.warnings.len(), 1);
+        assert!(
+            catalog.warnings[0].starts_with(&broken.display().to_string()),
+            "{}",
+            catalog.warnings[0]
+        );
+    }
+}
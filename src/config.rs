@@ -1,14 +1,75 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
-use crate::source::{GitSource, LocalSource, Source};
+use crate::bundle::SkillType;
+use crate::source::{ArchiveSource, FilteredSource, GitSource, LocalSource, Source};
+use crate::target::{check_dest_template, DestTemplates, Tool};
 
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct Config {
     #[serde(default)]
     pub default_tool: String,
 
+    /// Ids of the tools skm installs to and looks for installs of (empty = all)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub enabled_tools: Vec<String>,
+
+    /// Executable run on every installed file (stdin -> stdout); needs `--allow-exec`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transform_hook: Option<String>,
+
+    /// Local names for bundles, e.g. `my-commit = "fg/commit"`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub alias: BTreeMap<String, String>,
+
+    /// How many sources to read at once (default: number of CPUs); `--jobs` overrides
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jobs: Option<usize>,
+
+    /// Clone `https://github.com/...` sources through this base URL instead;
+    /// `SKM_GIT_MIRROR` overrides it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_mirror: Option<String>,
+
+    /// Namespace put before the bundle name of everything installed, e.g.
+    /// `acme` installs skills as `.claude/skills/acme-{bundle}-{name}/`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub install_prefix: Option<String>,
+
+    /// Only surface bundles with these names, from any source (empty = all)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow: Vec<String>,
+
+    /// Never surface bundles with these names, from any source
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deny: Vec<String>,
+
+    /// Install path templates per tool and type, e.g.
+    /// `[destinations.claude] skill = ".claude/skills/{bundle}-{name}/SKILL.md"`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub destinations: BTreeMap<String, BTreeMap<String, String>>,
+
+    /// Directory each tool's global installs go into, e.g.
+    /// `[global_dirs] claude = ".config/claude"`; relative paths are under `$HOME`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub global_dirs: BTreeMap<String, String>,
+
+    #[serde(default)]
+    sources: Vec<SourceConfig>,
+
+    /// Sources merged in from `sources.d/*.toml`, left out when saving
+    #[serde(skip)]
+    dropin_sources: Vec<SourceConfig>,
+}
+
+/// The bundles of one source, or why they couldn't be listed
+pub type SourceListing = Result<Vec<crate::bundle::Bundle>>;
+
+/// A `sources.d/*.toml` drop-in file: just `[[sources]]` entries
+#[derive(Debug, Deserialize)]
+struct DropIn {
     #[serde(default)]
     sources: Vec<SourceConfig>,
 }
@@ -21,12 +82,40 @@ pub enum SourceConfig {
         path: String,
         #[serde(default, skip_serializing_if = "Option::is_none")]
         name: Option<String>,
+        /// Only surface these bundles (empty = all)
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        include: Vec<String>,
+        /// Hide these bundles
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        exclude: Vec<String>,
+        /// Type for folders of loose `.md` files (default: command)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        loose_type: Option<SkillType>,
     },
     #[serde(rename = "git")]
     Git {
         url: String,
         #[serde(default, skip_serializing_if = "Option::is_none")]
         name: Option<String>,
+        /// Only surface these bundles (empty = all)
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        include: Vec<String>,
+        /// Hide these bundles
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        exclude: Vec<String>,
+    },
+    /// A `.tar.gz` or `.zip` of bundles downloaded from a URL
+    #[serde(rename = "archive")]
+    Archive {
+        url: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
+        /// Only surface these bundles (empty = all)
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        include: Vec<String>,
+        /// Hide these bundles
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        exclude: Vec<String>,
     },
 }
 
@@ -35,41 +124,92 @@ impl Config {
     pub fn new(sources: Vec<SourceConfig>) -> Self {
         Config {
             default_tool: "claude".to_string(),
+            enabled_tools: Vec::new(),
+            transform_hook: None,
+            alias: BTreeMap::new(),
+            jobs: None,
+            git_mirror: None,
+            install_prefix: None,
+            allow: Vec::new(),
+            deny: Vec::new(),
+            destinations: BTreeMap::new(),
+            global_dirs: BTreeMap::new(),
             sources,
+            dropin_sources: Vec::new(),
         }
     }
 
-    /// Load config from file, return None if it doesn't exist
+    /// Load config, return None if it doesn't exist.
+    /// `SKM_CONFIG` (inline TOML) wins over `SKM_CONFIG_FILE`, which wins over the default path.
     pub fn load() -> Result<Option<Self>> {
+        if let Some(inline) = std::env::var_os(CONFIG_ENV) {
+            return Self::from_inline(&inline.to_string_lossy()).map(Some);
+        }
+
         let config_path = Self::config_path()?;
 
+        if std::env::var_os(CONFIG_FILE_ENV).is_some() && !config_path.exists() {
+            anyhow::bail!(
+                "{} points to a missing file: {}",
+                CONFIG_FILE_ENV,
+                config_path.display()
+            );
+        }
+
         if config_path.exists() {
             let content = std::fs::read_to_string(&config_path)?;
-            let config: Config = toml::from_str(&content)?;
+            let mut config: Config = toml::from_str(&content).with_context(|| {
+                format!(
+                    "Invalid config at {} (run `skm config check` for details)",
+                    config_path.display()
+                )
+            })?;
+            config.merge_dropins(&Self::dropin_dir()?)?;
             Ok(Some(config))
         } else {
             Ok(None)
         }
     }
 
+    /// Parse a config provided inline through the `SKM_CONFIG` environment variable
+    fn from_inline(content: &str) -> Result<Self> {
+        toml::from_str(content).with_context(|| {
+            format!(
+                "Invalid config in ${} (run `skm config check` for details)",
+                CONFIG_ENV
+            )
+        })
+    }
+
+    /// Inline config contents from `SKM_CONFIG`, if set
+    pub fn inline_env() -> Option<String> {
+        std::env::var_os(CONFIG_ENV).map(|v| v.to_string_lossy().into_owned())
+    }
+
     /// Load config from file or return default with ~/.claude-skills as source
     pub fn load_or_default() -> Result<Self> {
         if let Some(config) = Self::load()? {
             Ok(config)
         } else {
             // Fallback default - used when no config exists and not in interactive mode
-            Ok(Config {
-                default_tool: "claude".to_string(),
-                sources: vec![SourceConfig::Local {
-                    path: "~/.claude-skills".to_string(),
-                    name: None,
-                }],
-            })
+            let mut config =
+                Config::new(vec![SourceConfig::local("~/.claude-skills".to_string(), None)]);
+            if Self::inline_env().is_none() {
+                config.merge_dropins(&Self::dropin_dir()?)?;
+            }
+            Ok(config)
         }
     }
 
     /// Save config to file
     pub fn save(&self) -> Result<()> {
+        if std::env::var_os(CONFIG_ENV).is_some() {
+            anyhow::bail!(
+                "Config is provided inline via ${} and can't be modified; edit the variable instead",
+                CONFIG_ENV
+            );
+        }
+
         let config_path = Self::config_path()?;
 
         // Create parent directory if needed
@@ -77,75 +217,533 @@ impl Config {
             std::fs::create_dir_all(parent)?;
         }
 
-        let content = toml::to_string_pretty(self)?;
-        std::fs::write(&config_path, content)?;
+        std::fs::write(&config_path, self.to_toml()?)?;
+
+        Ok(())
+    }
+
+    /// Whether a source was merged in from a `sources.d` drop-in file
+    pub fn is_dropin(&self, source: &SourceConfig) -> bool {
+        self.dropin_sources
+            .iter()
+            .any(|d| d.display() == source.display())
+    }
+
+    /// The config file contents, without sources that came from drop-in files
+    fn to_toml(&self) -> Result<String> {
+        let own = Config {
+            default_tool: self.default_tool.clone(),
+            enabled_tools: self.enabled_tools.clone(),
+            transform_hook: self.transform_hook.clone(),
+            alias: self.alias.clone(),
+            jobs: self.jobs,
+            git_mirror: self.git_mirror.clone(),
+            install_prefix: self.install_prefix.clone(),
+            allow: self.allow.clone(),
+            deny: self.deny.clone(),
+            destinations: self.destinations.clone(),
+            global_dirs: self.global_dirs.clone(),
+            sources: self
+                .sources
+                .iter()
+                .filter(|s| !self.is_dropin(s))
+                .cloned()
+                .collect(),
+            dropin_sources: Vec::new(),
+        };
+        Ok(toml::to_string_pretty(&own)?)
+    }
+
+    /// Directory of drop-in source files, next to the config file
+    fn dropin_dir() -> Result<PathBuf> {
+        let config_path = Self::config_path()?;
+        Ok(config_path
+            .parent()
+            .map(|p| p.join("sources.d"))
+            .unwrap_or_else(|| PathBuf::from("sources.d")))
+    }
+
+    /// Append the sources of every `*.toml` file in `dir`, in file name order.
+    /// Sources already configured keep their place and settings.
+    fn merge_dropins(&mut self, dir: &Path) -> Result<()> {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return Ok(());
+        };
+        let mut files: Vec<PathBuf> = entries
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| p.is_file() && p.extension().is_some_and(|e| e == "toml"))
+            .collect();
+        files.sort();
 
+        for file in files {
+            let content = std::fs::read_to_string(&file)?;
+            let dropin: DropIn = toml::from_str(&content)
+                .with_context(|| format!("Invalid drop-in config at {}", file.display()))?;
+            for source in dropin.sources {
+                if self.sources.iter().any(|s| s.display() == source.display()) {
+                    continue;
+                }
+                self.dropin_sources.push(source.clone());
+                self.sources.push(source);
+            }
+        }
         Ok(())
     }
 
-    /// Get the config file path
+    /// Get the config file path (`SKM_CONFIG_FILE` overrides the default location)
     pub fn config_path() -> Result<PathBuf> {
+        if let Some(path) = std::env::var_os(CONFIG_FILE_ENV) {
+            return Ok(expand_tilde(&path.to_string_lossy()));
+        }
         let proj_dirs = directories::ProjectDirs::from("", "", "skm")
             .ok_or_else(|| anyhow::anyhow!("Could not determine config directory"))?;
         Ok(proj_dirs.config_dir().join("config.toml"))
     }
 
-    /// Check if config file exists
+    /// Check if a config exists (inline via `SKM_CONFIG` or on disk)
     pub fn exists() -> Result<bool> {
+        if std::env::var_os(CONFIG_ENV).is_some() {
+            return Ok(true);
+        }
         let config_path = Self::config_path()?;
         Ok(config_path.exists())
     }
 
+    /// Validate raw config file contents without loading any sources.
+    /// Returns a list of human-readable problems (empty when the config is valid).
+    pub fn check(content: &str) -> Vec<String> {
+        let value: toml::Value = match toml::from_str(content) {
+            Ok(v) => v,
+            Err(e) => return vec![format!("invalid TOML: {}", e.to_string().trim_end())],
+        };
+
+        let mut issues = Vec::new();
+
+        let table = match value.as_table() {
+            Some(t) => t,
+            None => return vec!["config must be a TOML table".to_string()],
+        };
+
+        for key in table.keys() {
+            if !KNOWN_CONFIG_KEYS.contains(&key.as_str()) {
+                issues.push(format!("unknown field `{}`", key));
+            }
+        }
+
+        if let Some(jobs) = table.get("jobs") {
+            if jobs.as_integer().is_none_or(|n| n < 1) {
+                issues.push("`jobs` must be a positive integer".to_string());
+            }
+        }
+
+        if let Some(tool) = table.get("default_tool") {
+            match tool.as_str() {
+                Some(t) if t.is_empty() || KNOWN_TOOLS.contains(&t) => {}
+                Some(t) => issues.push(format!(
+                    "unknown default_tool `{}` (expected one of: {})",
+                    t,
+                    KNOWN_TOOLS.join(", ")
+                )),
+                None => issues.push("`default_tool` must be a string".to_string()),
+            }
+        }
+
+        if let Some(tools) = table.get("enabled_tools") {
+            let ids = tools
+                .as_array()
+                .and_then(|a| a.iter().map(|t| t.as_str()).collect::<Option<Vec<_>>>());
+            match ids {
+                Some(ids) => {
+                    for id in ids.iter().filter(|id| !KNOWN_TOOLS.contains(id)) {
+                        issues.push(format!(
+                            "unknown tool `{}` in enabled_tools (expected one of: {})",
+                            id,
+                            KNOWN_TOOLS.join(", ")
+                        ));
+                    }
+                    let default = table.get("default_tool").and_then(|t| t.as_str());
+                    if let Some(default) = default.filter(|d| !d.is_empty() && !ids.contains(d)) {
+                        issues.push(format!("default_tool `{}` is not in enabled_tools", default));
+                    }
+                }
+                None => issues.push("`enabled_tools` must be a list of tool names".to_string()),
+            }
+        }
+
+        if let Some(alias) = table.get("alias") {
+            match alias.as_table() {
+                Some(aliases) => {
+                    for (name, target) in aliases {
+                        if name.contains('/') {
+                            issues.push(format!("alias `{}`: name must not contain `/`", name));
+                        }
+                        match target.as_str() {
+                            Some(t) if !t.is_empty() => {}
+                            _ => issues.push(format!(
+                                "alias `{}`: target must be a non-empty string like \"source/bundle\"",
+                                name
+                            )),
+                        }
+                    }
+                }
+                None => issues.push("`alias` must be a table of name = \"target\"".to_string()),
+            }
+        }
+
+        if let Some(prefix) = table.get("install_prefix") {
+            match prefix.as_str() {
+                Some(p)
+                    if !p.is_empty() && !p.starts_with('.') && !p.contains(['/', '\\', ':']) => {}
+                Some(p) => issues.push(format!(
+                    "`install_prefix` `{}` must be non-empty, without `/` or `:`, \
+                     and not start with `.`",
+                    p
+                )),
+                None => issues.push("`install_prefix` must be a string".to_string()),
+            }
+        }
+
+        if let Some(destinations) = table.get("destinations") {
+            issues.extend(check_destinations(destinations));
+        }
+
+        if let Some(dirs) = table.get("global_dirs") {
+            match dirs.as_table() {
+                Some(dirs) => {
+                    for (tool, dir) in dirs {
+                        if !KNOWN_TOOLS.contains(&tool.as_str()) {
+                            issues.push(format!(
+                                "global_dirs: unknown tool `{}` (expected one of: {})",
+                                tool,
+                                KNOWN_TOOLS.join(", ")
+                            ));
+                        } else if dir.as_str().is_none_or(|d| d.is_empty()) {
+                            issues.push(format!("global_dirs.{}: must be a directory path", tool));
+                        }
+                    }
+                }
+                None => issues.push("`global_dirs` must be a table of tool = \"dir\"".to_string()),
+            }
+        }
+
+        for key in ["allow", "deny"] {
+            let Some(names) = table.get(key) else {
+                continue;
+            };
+            let is_names = names
+                .as_array()
+                .is_some_and(|a| a.iter().all(|n| n.as_str().is_some_and(|n| !n.is_empty())));
+            if !is_names {
+                issues.push(format!("`{}` must be a list of bundle names", key));
+            }
+        }
+
+        let sources = match table.get("sources") {
+            None => return issues,
+            Some(toml::Value::Array(a)) => a,
+            Some(_) => {
+                issues.push("`sources` must be an array of tables ([[sources]])".to_string());
+                return issues;
+            }
+        };
+
+        // (index, value) of the first source that used a given name / location
+        let mut seen_names: Vec<(usize, String)> = Vec::new();
+        let mut seen_locations: Vec<(usize, String)> = Vec::new();
+
+        for (i, source) in sources.iter().enumerate() {
+            let label = format!("source #{}", i + 1);
+            let entry = match source.as_table() {
+                Some(t) => t,
+                None => {
+                    issues.push(format!("{}: must be a table", label));
+                    continue;
+                }
+            };
+
+            let (location_key, known_keys): (&str, &[&str]) =
+                match entry.get("type").and_then(|t| t.as_str()) {
+                    Some("local") => ("path", KNOWN_LOCAL_KEYS),
+                    Some("git") => ("url", KNOWN_GIT_KEYS),
+                    Some("archive") => ("url", KNOWN_ARCHIVE_KEYS),
+                    Some(other) => {
+                        issues.push(format!(
+                            "{}: unknown type `{}` (expected one of: {})",
+                            label,
+                            other,
+                            KNOWN_SOURCE_TYPES.join(", ")
+                        ));
+                        continue;
+                    }
+                    None => {
+                        issues.push(format!(
+                            "{}: missing `type` (expected one of: {})",
+                            label,
+                            KNOWN_SOURCE_TYPES.join(", ")
+                        ));
+                        continue;
+                    }
+                };
+
+            for key in entry.keys() {
+                if key != "type" && !known_keys.contains(&key.as_str()) {
+                    issues.push(format!("{}: unknown field `{}`", label, key));
+                }
+            }
+
+            match entry.get(location_key).and_then(|v| v.as_str()) {
+                Some(location) => {
+                    if let Some((first, _)) = seen_locations.iter().find(|(_, l)| l == location) {
+                        issues.push(format!(
+                            "{}: duplicate {} `{}` (already listed as source #{})",
+                            label,
+                            location_key,
+                            location,
+                            first + 1
+                        ));
+                    } else {
+                        seen_locations.push((i, location.to_string()));
+                    }
+                }
+                None => issues.push(format!("{}: missing `{}`", label, location_key)),
+            }
+
+            if let Some(name) = entry.get("name") {
+                match name.as_str() {
+                    Some(name) => {
+                        if let Some((first, _)) = seen_names.iter().find(|(_, n)| n == name) {
+                            issues.push(format!(
+                                "{}: duplicate name `{}` (already used by source #{})",
+                                label,
+                                name,
+                                first + 1
+                            ));
+                        } else {
+                            seen_names.push((i, name.to_string()));
+                        }
+                    }
+                    None => issues.push(format!("{}: `name` must be a string", label)),
+                }
+            }
+        }
+
+        // Catch anything the structural checks above missed (e.g. wrong value types)
+        if issues.is_empty() {
+            match toml::from_str::<Config>(content) {
+                Ok(config) => {
+                    issues.extend(config.cache_collisions());
+                    issues.extend(config.nested_sources());
+                }
+                Err(e) => issues.push(e.message().trim().to_string()),
+            }
+        }
+
+        issues
+    }
+
     /// Get all configured sources as Source trait objects
     pub fn sources(&self) -> Vec<Box<dyn Source>> {
         self.sources
             .iter()
-            .filter_map(|s| match s {
-                SourceConfig::Local { path, .. } => {
-                    let expanded = expand_tilde(path);
-                    Some(Box::new(LocalSource::new(expanded)) as Box<dyn Source>)
+            .filter_map(|s| match self.source_for(s) {
+                Ok(source) => Some(source),
+                Err(e) => {
+                    eprintln!("Warning: Could not initialize source {}: {}", s.display(), e);
+                    None
                 }
-                SourceConfig::Git { url, .. } => match GitSource::new(url.clone()) {
-                    Ok(source) => Some(Box::new(source) as Box<dyn Source>),
-                    Err(e) => {
-                        eprintln!("Warning: Could not initialize git source {}: {}", url, e);
-                        None
-                    }
-                },
             })
             .collect()
     }
 
+    /// Build a configured source, limited by the global `allow`/`deny` lists
+    pub fn source_for(&self, source: &SourceConfig) -> Result<Box<dyn Source>> {
+        let source = source.to_source(self.git_mirror().as_deref())?;
+        Ok(self.filtered(source))
+    }
+
+    /// Limit `source` by the global `allow`/`deny` lists
+    pub fn filtered(&self, source: Box<dyn Source>) -> Box<dyn Source> {
+        if self.allow.is_empty() && self.deny.is_empty() {
+            source
+        } else {
+            Box::new(FilteredSource::new(source, self.allow.clone(), self.deny.clone()))
+        }
+    }
+
+    /// Whether `enabled_tools` lets skm install to and discover `tool`. Unknown
+    /// ids (reported by `skm config check`) are ignored, so a list of typos
+    /// doesn't disable every tool.
+    pub fn tool_enabled(&self, tool: &Tool) -> bool {
+        self.enabled_tools.iter().all(|id| Tool::from_id(id).is_none())
+            || self.enabled_tools.iter().any(|id| id == tool.id())
+    }
+
+    /// The tools skm works with, in `Tool::ALL` order
+    pub fn tools(&self) -> Vec<Tool> {
+        Tool::ALL.into_iter().filter(|t| self.tool_enabled(t)).collect()
+    }
+
+    /// Where global installs of `tool` go: its `global_dirs` entry, or the
+    /// tool's default (see `Tool::global_target`)
+    pub fn global_target(&self, tool: &Tool) -> PathBuf {
+        match self.global_dirs.get(tool.id()) {
+            Some(dir) if dir.starts_with('~') || Path::new(dir).is_absolute() => {
+                expand_tilde(dir)
+            }
+            Some(dir) => dirs_home().unwrap_or_else(|| PathBuf::from(".")).join(dir),
+            None => tool.global_target(),
+        }
+    }
+
+    /// The enabled tools that have a global location, with that location.
+    /// Cursor and Windsurf only have one when it is configured in `global_dirs`.
+    pub fn global_targets(&self) -> Vec<(Tool, PathBuf)> {
+        self.tools()
+            .into_iter()
+            .filter(|t| {
+                !matches!(t, Tool::Cursor | Tool::Windsurf) || self.global_dirs.contains_key(t.id())
+            })
+            .map(|t| (t, self.global_target(&t)))
+            .collect()
+    }
+
+    /// Whether the global `allow`/`deny` lists let `bundle_name` through
+    pub fn allows_bundle(&self, bundle_name: &str) -> bool {
+        (self.allow.is_empty() || self.allow.iter().any(|n| n == bundle_name))
+            && !self.deny.iter().any(|n| n == bundle_name)
+    }
+
+    /// Fail when the global `allow`/`deny` lists block `bundle_name`
+    pub fn check_bundle_allowed(&self, bundle_name: &str) -> Result<()> {
+        if !self.allows_bundle(bundle_name) {
+            anyhow::bail!(
+                "Bundle `{}` is blocked by the `allow`/`deny` lists in the config",
+                bundle_name
+            );
+        }
+        Ok(())
+    }
+
     /// Get git sources for update command
     pub fn git_sources(&self) -> Vec<GitSource> {
         self.sources
             .iter()
             .filter_map(|s| match s {
-                SourceConfig::Git { url, .. } => GitSource::new(url.clone()).ok(),
+                SourceConfig::Git { url, .. } => GitSource::new(url.clone())
+                    .map(|git| git.with_mirror(self.git_mirror().as_deref()))
+                    .ok(),
                 _ => None,
             })
             .collect()
     }
 
+    /// Describe git sources whose URLs differ but map to the same cache
+    /// directory, so cloning one would clobber the other
+    pub fn cache_collisions(&self) -> Vec<String> {
+        let git_sources = self.git_sources();
+        let mut collisions = Vec::new();
+
+        for (i, source) in git_sources.iter().enumerate() {
+            let earlier = git_sources[..i]
+                .iter()
+                .find(|other| other.cache_path() == source.cache_path());
+            if let Some(other) = earlier {
+                if other.url() != source.url() {
+                    collisions.push(format!(
+                        "git sources `{}` and `{}` share the cache directory {}",
+                        other.url(),
+                        source.url(),
+                        source.cache_path().display()
+                    ));
+                }
+            }
+        }
+
+        collisions
+    }
+
+    /// Describe local sources that sit inside (or are the same directory as)
+    /// another local source, whose bundles would then be scanned twice
+    pub fn nested_sources(&self) -> Vec<String> {
+        let local: Vec<(&str, PathBuf)> = self
+            .sources
+            .iter()
+            .filter_map(|s| match s {
+                SourceConfig::Local { path, .. } => {
+                    let expanded = expand_tilde(path);
+                    Some((path.as_str(), expanded.canonicalize().unwrap_or(expanded)))
+                }
+                SourceConfig::Git { .. } | SourceConfig::Archive { .. } => None,
+            })
+            .collect();
+
+        let mut nested = Vec::new();
+        for (i, (display, path)) in local.iter().enumerate() {
+            for (other_display, other_path) in &local[i + 1..] {
+                if path == other_path {
+                    nested.push(format!(
+                        "local sources `{}` and `{}` are the same directory",
+                        display, other_display
+                    ));
+                } else if other_path.starts_with(path) {
+                    nested.push(format!(
+                        "local source `{}` is inside source `{}`; its bundles may be listed twice",
+                        other_display, display
+                    ));
+                } else if path.starts_with(other_path) {
+                    nested.push(format!(
+                        "local source `{}` is inside source `{}`; its bundles may be listed twice",
+                        display, other_display
+                    ));
+                }
+            }
+        }
+        nested
+    }
+
     /// Get raw source configs
     pub fn source_configs(&self) -> &[SourceConfig] {
         &self.sources
     }
 
-    /// Add a source to the config
-    pub fn add_source(&mut self, source: SourceConfig) {
+    /// Add a source to the config. Adding a source that is already listed does
+    /// nothing; a name another source uses, or one that looks like a path, is an error.
+    pub fn add_source(&mut self, source: SourceConfig) -> Result<()> {
         // Check if source already exists
         let exists = self.sources.iter().any(|s| match (s, &source) {
             (SourceConfig::Local { path: p1, .. }, SourceConfig::Local { path: p2, .. }) => {
                 p1 == p2
             }
             (SourceConfig::Git { url: u1, .. }, SourceConfig::Git { url: u2, .. }) => u1 == u2,
+            (SourceConfig::Archive { url: u1, .. }, SourceConfig::Archive { url: u2, .. }) => {
+                u1 == u2
+            }
             _ => false,
         });
 
-        if !exists {
-            self.sources.push(source);
+        if exists {
+            return Ok(());
+        }
+
+        if let Some(name) = source.name() {
+            if name.is_empty() || name.contains(['/', '\\']) {
+                anyhow::bail!(
+                    "Source name must be non-empty and must not contain '/' or '\\': {}",
+                    name
+                );
+            }
+            if let Some(other) = self.sources.iter().find(|s| s.name() == Some(name)) {
+                anyhow::bail!(
+                    "A source named `{}` already exists ({}); pick another name with --name",
+                    name,
+                    other.display()
+                );
+            }
         }
+
+        self.sources.push(source);
+        Ok(())
     }
 
     /// Move a source from one position to another (for priority)
@@ -165,19 +763,61 @@ impl Config {
         let input_expanded = expand_tilde(path_or_url);
 
         self.sources.retain(|s| match s {
-            SourceConfig::Local { path, name } => {
+            SourceConfig::Local { path, name, .. } => {
                 // Compare both the raw string and expanded paths, and also by name
                 path != path_or_url
                     && expand_tilde(path) != input_expanded
                     && name.as_deref() != Some(path_or_url)
             }
-            SourceConfig::Git { url, name } => {
+            SourceConfig::Git { url, name, .. } | SourceConfig::Archive { url, name, .. } => {
                 url != path_or_url && name.as_deref() != Some(path_or_url)
             }
         });
         self.sources.len() < initial_len
     }
 
+    /// Base URL to clone GitHub sources through, from `SKM_GIT_MIRROR` or the config
+    pub fn git_mirror(&self) -> Option<String> {
+        std::env::var(GIT_MIRROR_ENV)
+            .ok()
+            .filter(|m| !m.is_empty())
+            .or_else(|| self.git_mirror.clone())
+    }
+
+    /// The `[destinations]` templates, checked and keyed by tool and type
+    pub fn dest_templates(&self) -> Result<DestTemplates> {
+        let mut templates = Vec::new();
+        for (tool_id, by_type) in &self.destinations {
+            let tool = Tool::from_id(tool_id)
+                .with_context(|| format!("destinations: unknown tool `{}`", tool_id))?;
+            for (type_name, template) in by_type {
+                let skill_type = SkillType::from_name(type_name).with_context(|| {
+                    format!("destinations.{}: unknown type `{}`", tool_id, type_name)
+                })?;
+                check_dest_template(template).map_err(|e| {
+                    anyhow::anyhow!("destinations.{}.{}: template {}", tool_id, type_name, e)
+                })?;
+                templates.push((tool, skill_type, template.clone()));
+            }
+        }
+        Ok(DestTemplates::new(templates))
+    }
+
+    /// Number of sources to read in parallel
+    pub fn jobs(&self) -> usize {
+        self.jobs
+            .unwrap_or_else(crate::parallel::default_jobs)
+            .max(1)
+    }
+
+    /// List every source's bundles, reading up to `jobs()` sources at once.
+    /// Results are in source (priority) order.
+    pub fn list_all_bundles(&self) -> Vec<(Box<dyn Source>, SourceListing)> {
+        let sources = self.sources();
+        let results = crate::parallel::map(&sources, self.jobs(), |s| s.list_bundles());
+        sources.into_iter().zip(results).collect()
+    }
+
     /// Find a bundle by name across all sources
     pub fn find_bundle(
         &self,
@@ -196,6 +836,50 @@ impl Config {
         Ok(None)
     }
 
+    /// Find a bundle by reference: `source/bundle` or a plain bundle name
+    pub fn find_bundle_ref(
+        &self,
+        reference: &str,
+    ) -> Result<Option<(Box<dyn Source>, crate::bundle::Bundle)>> {
+        let Some((source_name, bundle_name)) = reference.split_once('/') else {
+            return self.find_bundle(reference);
+        };
+        let Some((source, _)) = self.find_source_by_name(source_name) else {
+            return Ok(None);
+        };
+        let bundle = source
+            .list_bundles()?
+            .into_iter()
+            .find(|b| b.name == bundle_name);
+        Ok(bundle.map(|b| (source, b)))
+    }
+
+    /// Path of the configured transform hook, with `~` expanded
+    pub fn transform_hook_path(&self) -> Option<PathBuf> {
+        self.transform_hook.as_deref().map(expand_tilde)
+    }
+
+    /// Resolve a local alias to the bundle reference it stands for
+    pub fn resolve_alias(&self, name: &str) -> Option<&str> {
+        self.alias.get(name).map(String::as_str)
+    }
+
+    /// Add or replace an alias. Returns the previous target, if any.
+    pub fn add_alias(&mut self, name: &str, target: &str) -> Result<Option<String>> {
+        if name.is_empty() || name.contains('/') {
+            anyhow::bail!("Alias name must be non-empty and must not contain '/': {}", name);
+        }
+        if target.is_empty() {
+            anyhow::bail!("Alias target must not be empty");
+        }
+        Ok(self.alias.insert(name.to_string(), target.to_string()))
+    }
+
+    /// Remove an alias. Returns true if it existed.
+    pub fn remove_alias(&mut self, name: &str) -> bool {
+        self.alias.remove(name).is_some()
+    }
+
     /// Find a bundle by prefix match across all sources.
     /// Legacy fallback: used when no install manifest exists (pre-manifest installs).
     /// Installed skills use `{bundle}-{name}` folder names, so when exact matching
@@ -229,16 +913,7 @@ impl Config {
     pub fn find_source_by_name(&self, name: &str) -> Option<(Box<dyn Source>, &SourceConfig)> {
         for source_config in &self.sources {
             if source_config.name() == Some(name) {
-                let source: Option<Box<dyn Source>> = match source_config {
-                    SourceConfig::Local { path, .. } => {
-                        let expanded = expand_tilde(path);
-                        Some(Box::new(LocalSource::new(expanded)))
-                    }
-                    SourceConfig::Git { url, .. } => GitSource::new(url.clone())
-                        .ok()
-                        .map(|s| Box::new(s) as Box<dyn Source>),
-                };
-                if let Some(source) = source {
+                if let Ok(source) = self.source_for(source_config) {
                     return Some((source, source_config));
                 }
             }
@@ -248,11 +923,96 @@ impl Config {
 }
 
 impl SourceConfig {
+    pub fn local(path: String, name: Option<String>) -> Self {
+        SourceConfig::Local {
+            path,
+            name,
+            include: Vec::new(),
+            exclude: Vec::new(),
+            loose_type: None,
+        }
+    }
+
+    pub fn git(url: String, name: Option<String>) -> Self {
+        SourceConfig::Git {
+            url,
+            name,
+            include: Vec::new(),
+            exclude: Vec::new(),
+        }
+    }
+
+    pub fn archive(url: String, name: Option<String>) -> Self {
+        SourceConfig::Archive {
+            url,
+            name,
+            include: Vec::new(),
+            exclude: Vec::new(),
+        }
+    }
+
+    /// The source for a location given on the command line: an archive URL,
+    /// a git URL, or else a local path
+    pub fn from_location(location: String, name: Option<String>) -> Self {
+        if ArchiveSource::is_archive_url(&location) {
+            SourceConfig::archive(location, name)
+        } else if is_git_url(&location) {
+            SourceConfig::git(location, name)
+        } else {
+            SourceConfig::local(location, name)
+        }
+    }
+
+    /// Build the source, limited to the configured `include`/`exclude` bundles.
+    /// Git sources are cloned through `git_mirror` when one is given.
+    pub fn to_source(&self, git_mirror: Option<&str>) -> Result<Box<dyn Source>> {
+        let (source, include, exclude): (Box<dyn Source>, _, _) = match self {
+            SourceConfig::Local {
+                path,
+                include,
+                exclude,
+                loose_type,
+                ..
+            } => {
+                let mut local = LocalSource::new(expand_tilde(path));
+                if let Some(loose_type) = loose_type {
+                    local = local.with_loose_type(*loose_type);
+                }
+                (Box::new(local), include, exclude)
+            }
+            SourceConfig::Git {
+                url,
+                include,
+                exclude,
+                ..
+            } => {
+                let git = GitSource::new(url.clone())?.with_mirror(git_mirror);
+                (Box::new(git), include, exclude)
+            }
+            SourceConfig::Archive {
+                url,
+                include,
+                exclude,
+                ..
+            } => (Box::new(ArchiveSource::new(url.clone())?), include, exclude),
+        };
+
+        if include.is_empty() && exclude.is_empty() {
+            Ok(source)
+        } else {
+            Ok(Box::new(FilteredSource::new(
+                source,
+                include.clone(),
+                exclude.clone(),
+            )))
+        }
+    }
+
     /// Get display string for this source
     pub fn display(&self) -> &str {
         match self {
             SourceConfig::Local { path, .. } => path,
-            SourceConfig::Git { url, .. } => url,
+            SourceConfig::Git { url, .. } | SourceConfig::Archive { url, .. } => url,
         }
     }
 
@@ -261,15 +1021,106 @@ impl SourceConfig {
         match self {
             SourceConfig::Local { name, .. } => name.as_deref(),
             SourceConfig::Git { name, .. } => name.as_deref(),
+            SourceConfig::Archive { name, .. } => name.as_deref(),
+        }
+    }
+}
+
+/// Environment variable holding the entire config as inline TOML
+pub const CONFIG_ENV: &str = "SKM_CONFIG";
+
+/// Environment variable pointing at an alternate config file
+pub const CONFIG_FILE_ENV: &str = "SKM_CONFIG_FILE";
+
+/// Environment variable overriding the `git_mirror` config key
+pub const GIT_MIRROR_ENV: &str = "SKM_GIT_MIRROR";
+
+/// Top-level keys accepted in config.toml
+const KNOWN_CONFIG_KEYS: &[&str] = &[
+    "default_tool",
+    "enabled_tools",
+    "transform_hook",
+    "alias",
+    "jobs",
+    "git_mirror",
+    "install_prefix",
+    "destinations",
+    "global_dirs",
+    "allow",
+    "deny",
+    "sources",
+];
+
+/// Keys accepted on a `type = "local"` source entry (besides `type`)
+const KNOWN_LOCAL_KEYS: &[&str] = &["path", "name", "include", "exclude", "loose_type"];
+
+/// Keys accepted on a `type = "git"` source entry (besides `type`)
+const KNOWN_GIT_KEYS: &[&str] = &["url", "name", "include", "exclude"];
+
+/// Keys accepted on a `type = "archive"` source entry (besides `type`)
+const KNOWN_ARCHIVE_KEYS: &[&str] = &["url", "name", "include", "exclude"];
+
+const KNOWN_SOURCE_TYPES: &[&str] = &["local", "git", "archive"];
+
+const KNOWN_TOOLS: &[&str] = &["claude", "opencode", "cursor", "codex", "windsurf"];
+
+/// Problems in a `[destinations]` table (see `Config::check`)
+fn check_destinations(destinations: &toml::Value) -> Vec<String> {
+    let expected = "`destinations` must be a table of [destinations.<tool>] tables";
+    let Some(tools) = destinations.as_table() else {
+        return vec![expected.to_string()];
+    };
+
+    let mut issues = Vec::new();
+    for (tool, by_type) in tools {
+        if !KNOWN_TOOLS.contains(&tool.as_str()) {
+            issues.push(format!(
+                "destinations: unknown tool `{}` (expected one of: {})",
+                tool,
+                KNOWN_TOOLS.join(", ")
+            ));
+        }
+        let Some(by_type) = by_type.as_table() else {
+            issues.push(expected.to_string());
+            continue;
+        };
+        for (type_name, template) in by_type {
+            if SkillType::from_name(type_name).is_none() {
+                issues.push(format!(
+                    "destinations.{}: unknown type `{}` (expected skill, agent, command or rule)",
+                    tool, type_name
+                ));
+            }
+            match template.as_str() {
+                Some(t) => {
+                    if let Err(e) = check_dest_template(t) {
+                        issues.push(format!("destinations.{}.{}: template {}", tool, type_name, e));
+                    }
+                }
+                None => issues.push(format!(
+                    "destinations.{}.{}: template must be a string",
+                    tool, type_name
+                )),
+            }
         }
     }
+    issues
+}
+
+/// Whether a source location refers to a git repository rather than a local
+/// path or a downloadable archive
+pub fn is_git_url(location: &str) -> bool {
+    !ArchiveSource::is_archive_url(location)
+        && (location.starts_with("https://")
+            || location.starts_with("git@")
+            || location.ends_with(".git"))
 }
 
 /// Expand ~ to home directory
-fn expand_tilde(path: &str) -> PathBuf {
-    if path.starts_with("~/") {
+pub fn expand_tilde(path: &str) -> PathBuf {
+    if let Some(rest) = path.strip_prefix("~/") {
         if let Some(home) = dirs_home() {
-            return home.join(&path[2..]);
+            return home.join(rest);
         }
     } else if path == "~" {
         if let Some(home) = dirs_home() {
@@ -279,6 +1130,25 @@ fn expand_tilde(path: &str) -> PathBuf {
     PathBuf::from(path)
 }
 
+/// The current directory, or an error explaining what to do instead when it
+/// can't be read (typically because it was deleted out from under the shell)
+pub fn working_dir(hint: &str) -> Result<PathBuf> {
+    working_dir_from(std::env::current_dir, hint)
+}
+
+fn working_dir_from(
+    current_dir: impl FnOnce() -> std::io::Result<PathBuf>,
+    hint: &str,
+) -> Result<PathBuf> {
+    current_dir().map_err(|e| {
+        anyhow::anyhow!(
+            "Cannot read the current directory ({}); it may have been deleted. {}",
+            e,
+            hint
+        )
+    })
+}
+
 fn dirs_home() -> Option<PathBuf> {
     std::env::var_os("HOME").map(PathBuf::from)
 }
@@ -300,10 +1170,547 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_check_valid_config() {
+        let content = r#"
+default_tool = "claude"
+
+[[sources]]
+type = "local"
+path = "~/.claude-skills"
+
+[[sources]]
+type = "git"
+url = "https://github.com/example/skills"
+name = "ex"
+"#;
+        assert!(Config::check(content).is_empty());
+    }
+
+    #[test]
+    fn test_check_reports_bad_toml() {
+        let issues = Config::check("default_tool = \"claude\"\n[[sources]\ntype = \"local\"\n");
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].starts_with("invalid TOML"));
+    }
+
+    #[test]
+    fn test_check_reports_malformed_config() {
+        let content = r#"
+default_tool = "claude"
+defualt_tool = "cursor"
+
+[[sources]]
+type = "lcoal"
+path = "~/skills"
+
+[[sources]]
+type = "git"
+url = "https://github.com/example/skills"
+name = "fg"
+branch = "main"
+
+[[sources]]
+type = "local"
+path = "~/other"
+name = "fg"
+
+[[sources]]
+type = "git"
+url = "https://github.com/example/skills"
+"#;
+        let issues = Config::check(content);
+        assert!(issues.contains(&"unknown field `defualt_tool`".to_string()));
+        assert!(issues.contains(
+            &"source #1: unknown type `lcoal` (expected one of: local, git, archive)".to_string()
+        ));
+        assert!(issues.contains(&"source #2: unknown field `branch`".to_string()));
+        assert!(issues
+            .contains(&"source #3: duplicate name `fg` (already used by source #2)".to_string()));
+        assert!(issues.contains(
+            &"source #4: duplicate url `https://github.com/example/skills` (already listed as source #2)"
+                .to_string()
+        ));
+        assert_eq!(issues.len(), 5);
+    }
+
+    #[test]
+    fn test_check_reports_missing_fields() {
+        let content = "[[sources]]\npath = \"~/skills\"\n\n[[sources]]\ntype = \"git\"\n";
+        let issues = Config::check(content);
+        assert_eq!(
+            issues,
+            vec![
+                "source #1: missing `type` (expected one of: local, git, archive)".to_string(),
+                "source #2: missing `url`".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_inline_config_used_for_list() {
+        let dir = tempfile::tempdir().unwrap();
+        let commands = dir.path().join("inline-bundle/commands");
+        std::fs::create_dir_all(&commands).unwrap();
+        std::fs::write(commands.join("hello.md"), "# Hello").unwrap();
+
+        let inline = format!(
+            "default_tool = \"cursor\"\n\n[[sources]]\ntype = \"local\"\npath = \"{}\"\n",
+            dir.path().display()
+        );
+        let config = Config::from_inline(&inline).unwrap();
+        assert_eq!(config.default_tool, "cursor");
+
+        let sources = config.sources();
+        assert_eq!(sources.len(), 1);
+        let bundles = sources[0].list_bundles().unwrap();
+        assert_eq!(bundles.len(), 1);
+        assert_eq!(bundles[0].name, "inline-bundle");
+    }
+
+    #[test]
+    fn test_inline_config_error_names_variable() {
+        let err = Config::from_inline("sources = [").unwrap_err();
+        assert!(err.to_string().contains("$SKM_CONFIG"));
+    }
+
+    #[test]
+    fn test_dropin_sources_are_listed_but_not_saved() {
+        let skills = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(skills.path().join("team-tools/commands")).unwrap();
+        std::fs::write(skills.path().join("team-tools/commands/deploy.md"), "# Deploy").unwrap();
+
+        let dropins = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dropins.path().join("20-team.toml"),
+            format!(
+                "[[sources]]\ntype = \"local\"\npath = \"{}\"\n",
+                skills.path().display()
+            ),
+        )
+        .unwrap();
+        // Merged in name order; entries already configured are not repeated
+        std::fs::write(
+            dropins.path().join("10-base.toml"),
+            "[[sources]]\ntype = \"git\"\nurl = \"https://github.com/example/skills\"\n\n\
+             [[sources]]\ntype = \"local\"\npath = \"~/mine\"\n",
+        )
+        .unwrap();
+        std::fs::write(dropins.path().join("notes.txt"), "ignored").unwrap();
+
+        let mut config = Config::new(vec![SourceConfig::local("~/mine".to_string(), None)]);
+        config.merge_dropins(dropins.path()).unwrap();
+
+        let locations: Vec<&str> = config.source_configs().iter().map(|s| s.display()).collect();
+        assert_eq!(locations.len(), 3);
+        assert_eq!(locations[1], "https://github.com/example/skills");
+        assert_eq!(locations[2], skills.path().to_str().unwrap());
+
+        // What `skm list` shows
+        let local = &config.source_configs()[2];
+        let bundles = local.to_source(None).unwrap().list_bundles().unwrap();
+        assert_eq!(bundles[0].name, "team-tools");
+
+        let saved: Config = toml::from_str(&config.to_toml().unwrap()).unwrap();
+        assert_eq!(saved.source_configs().len(), 1);
+        assert_eq!(saved.source_configs()[0].display(), "~/mine");
+    }
+
     #[test]
     fn test_default_config() {
         let config = Config::load_or_default().unwrap();
         assert_eq!(config.default_tool, "claude");
         assert!(!config.sources.is_empty());
     }
+
+    #[test]
+    fn test_check_alias_table() {
+        let content = r#"
+[alias]
+my-commit = "fg/commit"
+"bad/name" = "fg/other"
+empty = ""
+"#;
+        let issues = Config::check(content);
+        assert_eq!(
+            issues,
+            vec![
+                "alias `bad/name`: name must not contain `/`".to_string(),
+                "alias `empty`: target must be a non-empty string like \"source/bundle\"".to_string(),
+            ]
+        );
+        assert!(Config::check("[alias]\nmy-commit = \"fg/commit\"\n").is_empty());
+    }
+
+    #[test]
+    fn test_check_install_prefix() {
+        assert!(Config::check("install_prefix = \"acme\"\n").is_empty());
+        assert_eq!(
+            Config::check("install_prefix = \"acme/tools\"\n"),
+            vec!["`install_prefix` `acme/tools` must be non-empty, without `/` or `:`, \
+                  and not start with `.`"
+                .to_string()]
+        );
+        assert_eq!(
+            Config::check("install_prefix = 1\n"),
+            vec!["`install_prefix` must be a string".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_check_destinations_table() {
+        let content = r#"
+[destinations.claude]
+skill = ".config/claude/{bundle}-{name}/SKILL.md"
+agent = ".claude/agents/{name}.md"
+
+[destinations.vim]
+plugin = "{bundle}/{name}.md"
+"#;
+        let issues = Config::check(content);
+        assert_eq!(
+            issues,
+            vec![
+                "destinations.claude.agent: template must contain {bundle}".to_string(),
+                "destinations: unknown tool `vim` \
+                 (expected one of: claude, opencode, cursor, codex, windsurf)"
+                    .to_string(),
+                "destinations.vim: unknown type `plugin` (expected skill, agent, command or rule)"
+                    .to_string(),
+            ]
+        );
+
+        let config: Config = toml::from_str(
+            "[destinations.claude]\nskill = \".config/claude/{bundle}-{name}/SKILL.md\"\n",
+        )
+        .unwrap();
+        let templates = config.dest_templates().unwrap();
+        assert_eq!(
+            templates.get(Tool::Claude, SkillType::Skill),
+            Some(".config/claude/{bundle}-{name}/SKILL.md")
+        );
+        assert_eq!(templates.get(Tool::Claude, SkillType::Agent), None);
+    }
+
+    #[test]
+    fn test_alias_resolves_to_source_bundle() {
+        let root = tempfile::tempdir().unwrap();
+        for source in ["first", "second"] {
+            let commands = root.path().join(source).join("commit/commands");
+            std::fs::create_dir_all(&commands).unwrap();
+            std::fs::write(commands.join(format!("{}.md", source)), "# Commit").unwrap();
+        }
+
+        let content = format!(
+            r#"
+[alias]
+my-commit = "b/commit"
+
+[[sources]]
+type = "local"
+path = "{}"
+name = "a"
+
+[[sources]]
+type = "local"
+path = "{}"
+name = "b"
+"#,
+            root.path().join("first").display(),
+            root.path().join("second").display()
+        );
+        let mut config = Config::from_inline(&content).unwrap();
+
+        let target = config.resolve_alias("my-commit").unwrap();
+        assert_eq!(target, "b/commit");
+        let (source, bundle) = config.find_bundle_ref(target).unwrap().unwrap();
+        assert_eq!(bundle.name, "commit");
+        assert!(source.display_path().ends_with("second"));
+        assert_eq!(bundle.commands[0].name, "second");
+
+        // Plain names still search sources in priority order
+        let (source, _) = config.find_bundle_ref("commit").unwrap().unwrap();
+        assert!(source.display_path().ends_with("first"));
+        assert!(config.find_bundle_ref("c/commit").unwrap().is_none());
+
+        assert!(config.add_alias("x/y", "a/commit").is_err());
+        assert_eq!(
+            config.add_alias("my-commit", "a/commit").unwrap(),
+            Some("b/commit".to_string())
+        );
+        assert!(config.remove_alias("my-commit"));
+        assert!(config.resolve_alias("my-commit").is_none());
+    }
+
+    #[test]
+    fn test_cache_collisions_detected() {
+        let config = Config::new(vec![
+            SourceConfig::git("https://github.com/example/skills".to_string(), None),
+            SourceConfig::git("https://github.com/other/skills".to_string(), None),
+            SourceConfig::git("git@github.com:example/skills.git".to_string(), None),
+        ]);
+
+        let collisions = config.cache_collisions();
+        assert_eq!(collisions.len(), 1);
+        assert!(collisions[0].contains(
+            "`https://github.com/example/skills` and `git@github.com:example/skills.git`"
+        ));
+
+        let content = r#"
+[[sources]]
+type = "git"
+url = "https://github.com/example/skills"
+
+[[sources]]
+type = "git"
+url = "https://github.com/example/skills.git"
+"#;
+        let issues = Config::check(content);
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].contains("share the cache directory"));
+    }
+
+    #[test]
+    fn test_nested_local_source_is_reported() {
+        let root = tempfile::tempdir().unwrap();
+        let outer = root.path().join("skills");
+        std::fs::create_dir_all(outer.join("sub")).unwrap();
+        std::fs::create_dir_all(root.path().join("skills-extra")).unwrap();
+
+        let mut config = Config::new(vec![SourceConfig::local(outer.display().to_string(), None)]);
+        config
+            .add_source(SourceConfig::local(
+                root.path().join("skills-extra").display().to_string(),
+                None,
+            ))
+            .unwrap();
+        let git = SourceConfig::git("https://github.com/example/skills".to_string(), None);
+        config.add_source(git).unwrap();
+        assert!(config.nested_sources().is_empty());
+
+        let sub = SourceConfig::local(outer.join("sub").display().to_string(), None);
+        config.add_source(sub).unwrap();
+        let nested = config.nested_sources();
+        assert_eq!(nested.len(), 1);
+        assert!(nested[0].starts_with(&format!(
+            "local source `{}` is inside source `{}`",
+            outer.join("sub").display(),
+            outer.display()
+        )));
+    }
+
+    #[test]
+    fn test_source_include_exclude_filters_bundles() {
+        let root = tempfile::tempdir().unwrap();
+        for bundle in ["commit", "review", "docs"] {
+            let commands = root.path().join(bundle).join("commands");
+            std::fs::create_dir_all(&commands).unwrap();
+            std::fs::write(commands.join("run.md"), "# Run").unwrap();
+        }
+        let path = root.path().display();
+
+        let names = |content: &str| -> Vec<String> {
+            let config = Config::from_inline(content).unwrap();
+            let mut names: Vec<String> = config.sources()[0]
+                .list_bundles()
+                .unwrap()
+                .into_iter()
+                .map(|b| b.name)
+                .collect();
+            names.sort();
+            names
+        };
+
+        let included = format!(
+            "[[sources]]\ntype = \"local\"\npath = \"{}\"\ninclude = [\"commit\"]\n",
+            path
+        );
+        assert_eq!(names(&included), vec!["commit"]);
+        assert!(Config::check(&included).is_empty());
+
+        let excluded = format!(
+            "[[sources]]\ntype = \"local\"\npath = \"{}\"\nexclude = [\"docs\"]\n",
+            path
+        );
+        assert_eq!(names(&excluded), vec!["commit", "review"]);
+
+        // Filtered-out bundles can't be installed by name either
+        let config = Config::from_inline(&included).unwrap();
+        assert!(config.find_bundle("review").unwrap().is_none());
+        assert!(config.find_bundle("commit").unwrap().is_some());
+    }
+
+    #[test]
+    fn test_global_allow_and_deny_filter_every_source() {
+        let (first, second) = (tempfile::tempdir().unwrap(), tempfile::tempdir().unwrap());
+        for (root, bundles) in [(&first, ["commit", "review"]), (&second, ["docs", "crypto"])] {
+            for bundle in bundles {
+                let commands = root.path().join(bundle).join("commands");
+                std::fs::create_dir_all(&commands).unwrap();
+                std::fs::write(commands.join("run.md"), "# Run").unwrap();
+            }
+        }
+        let sources = format!(
+            "[[sources]]\ntype = \"local\"\npath = \"{}\"\n\n\
+             [[sources]]\ntype = \"local\"\npath = \"{}\"\n",
+            first.path().display(),
+            second.path().display()
+        );
+        let names = |config: &Config| -> Vec<String> {
+            let mut names: Vec<String> = config
+                .list_all_bundles()
+                .into_iter()
+                .flat_map(|(_, listed)| listed.unwrap())
+                .map(|b| b.name)
+                .collect();
+            names.sort();
+            names
+        };
+
+        let denied = format!("deny = [\"crypto\"]\n\n{}", sources);
+        assert!(Config::check(&denied).is_empty(), "{:?}", Config::check(&denied));
+        let config = Config::from_inline(&denied).unwrap();
+        assert_eq!(names(&config), vec!["commit", "docs", "review"]);
+        assert!(config.find_bundle("crypto").unwrap().is_none());
+        assert!(!config.allows_bundle("crypto"));
+
+        let allowed = format!("allow = [\"commit\", \"docs\"]\n\n{}", sources);
+        let config = Config::from_inline(&allowed).unwrap();
+        assert_eq!(names(&config), vec!["commit", "docs"]);
+        assert!(config.find_bundle("review").unwrap().is_none());
+
+        // Both: the allowlist, minus anything denied
+        let both = format!("allow = [\"commit\", \"docs\"]\ndeny = [\"docs\"]\n\n{}", sources);
+        assert_eq!(names(&Config::from_inline(&both).unwrap()), vec!["commit"]);
+
+        assert_eq!(
+            Config::check("deny = \"crypto\"\n"),
+            vec!["`deny` must be a list of bundle names"]
+        );
+    }
+
+    #[test]
+    fn test_enabled_tools_limit_the_tools() {
+        let config = Config::from_inline("enabled_tools = [\"claude\", \"opencode\"]\n").unwrap();
+        assert_eq!(config.tools(), vec![Tool::Claude, Tool::OpenCode]);
+        assert!(!config.tool_enabled(&Tool::Codex));
+        assert_eq!(Config::new(vec![]).tools(), Tool::ALL.to_vec());
+        // Unknown ids are ignored rather than leaving no tools enabled
+        let typo = Config::from_inline("enabled_tools = [\"claud\"]\n").unwrap();
+        assert_eq!(typo.tools(), Tool::ALL.to_vec());
+        let mixed = Config::from_inline("enabled_tools = [\"claud\", \"codex\"]\n").unwrap();
+        assert_eq!(mixed.tools(), vec![Tool::Codex]);
+
+        let fine = "default_tool = \"claude\"\nenabled_tools = [\"claude\"]\n";
+        assert!(Config::check(fine).is_empty());
+        assert_eq!(
+            Config::check("default_tool = \"codex\"\nenabled_tools = [\"claude\", \"vim\"]\n"),
+            vec![
+                "unknown tool `vim` in enabled_tools \
+                 (expected one of: claude, opencode, cursor, codex, windsurf)",
+                "default_tool `codex` is not in enabled_tools",
+            ]
+        );
+        assert_eq!(
+            Config::check("enabled_tools = \"claude\"\n"),
+            vec!["`enabled_tools` must be a list of tool names"]
+        );
+    }
+
+    #[test]
+    fn test_global_dirs_set_where_global_installs_go() {
+        let home = PathBuf::from(std::env::var("HOME").unwrap());
+        let config = Config::from_inline(
+            "[global_dirs]\nclaude = \".config/claude\"\ncodex = \"/opt/codex\"\n\
+             cursor = \"~/cursor-rules\"\n",
+        )
+        .unwrap();
+        assert_eq!(config.global_target(&Tool::Claude), home.join(".config/claude"));
+        assert_eq!(config.global_target(&Tool::Codex), PathBuf::from("/opt/codex"));
+        assert_eq!(config.global_target(&Tool::Cursor), home.join("cursor-rules"));
+        assert_eq!(config.global_target(&Tool::OpenCode), Tool::OpenCode.global_target());
+
+        let targets: Vec<Tool> = config.global_targets().into_iter().map(|(t, _)| t).collect();
+        assert_eq!(targets, vec![Tool::Claude, Tool::OpenCode, Tool::Cursor, Tool::Codex]);
+        let defaults = Config::new(vec![]).global_targets();
+        assert_eq!(defaults.len(), 3);
+        assert!(defaults.iter().all(|(t, dir)| *dir == t.global_target()));
+
+        assert_eq!(
+            Config::check("[global_dirs]\nvim = \"x\"\nclaude = \"\"\n"),
+            vec![
+                "global_dirs.claude: must be a directory path",
+                "global_dirs: unknown tool `vim` \
+                 (expected one of: claude, opencode, cursor, codex, windsurf)",
+            ]
+        );
+        assert_eq!(
+            Config::check("global_dirs = \"~/.claude\"\n"),
+            vec!["`global_dirs` must be a table of tool = \"dir\""]
+        );
+    }
+
+    #[test]
+    fn test_add_source_rejects_duplicate_names() {
+        let fg = SourceConfig::local("~/fg".to_string(), Some("fg".into()));
+        let mut config = Config::new(vec![fg.clone()]);
+
+        // Re-adding the same source is a no-op
+        config.add_source(fg).unwrap();
+        assert_eq!(config.source_configs().len(), 1);
+
+        let other = SourceConfig::git("https://github.com/acme/fg".to_string(), Some("fg".into()));
+        let err = config.add_source(other).unwrap_err();
+        assert!(err.to_string().contains("A source named `fg` already exists (~/fg)"), "{}", err);
+        assert_eq!(config.source_configs().len(), 1);
+
+        let url = "https://github.com/acme/fg".to_string();
+        config.add_source(SourceConfig::git(url.clone(), Some("acme".into()))).unwrap();
+        assert_eq!(config.find_source_by_name("acme").unwrap().1.display(), url);
+    }
+
+    #[test]
+    fn test_add_source_rejects_path_like_names() {
+        let mut config = Config::new(Vec::new());
+        for name in ["team/skills", "..\\skills", ""] {
+            let source = SourceConfig::local("~/skills".to_string(), Some(name.to_string()));
+            let err = config.add_source(source).unwrap_err();
+            assert!(err.to_string().contains("must not contain '/'"), "{}", err);
+        }
+        assert!(config.source_configs().is_empty());
+    }
+
+    #[test]
+    fn test_source_location_detects_archives() {
+        let kind = |location: &str| match SourceConfig::from_location(location.to_string(), None) {
+            SourceConfig::Local { .. } => "local",
+            SourceConfig::Git { .. } => "git",
+            SourceConfig::Archive { .. } => "archive",
+        };
+        assert_eq!(kind("https://example.com/skills.tar.gz"), "archive");
+        assert_eq!(kind("https://example.com/releases/skills-1.0.zip"), "archive");
+        assert_eq!(kind("https://github.com/acme/skills"), "git");
+        assert_eq!(kind("git@github.com:acme/skills.git"), "git");
+        assert_eq!(kind("~/skills"), "local");
+
+        let issues = Config::check(
+            "[[sources]]\ntype = \"archive\"\nurl = \"https://example.com/s.tgz\"\nname = \"s\"\n",
+        );
+        assert!(issues.is_empty(), "{:?}", issues);
+    }
+
+    #[test]
+    fn test_working_dir_failure_is_explained() {
+        let err = working_dir_from(
+            || Err(std::io::Error::from(std::io::ErrorKind::NotFound)),
+            "pass --to <dir>",
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("Cannot read the current directory"));
+        assert!(err.contains("pass --to <dir>"));
+
+        let dir = working_dir_from(|| Ok(PathBuf::from("/work")), "unused").unwrap();
+        assert_eq!(dir, PathBuf::from("/work"));
+    }
 }
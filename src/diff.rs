@@ -0,0 +1,378 @@
+//! Drift between a bundle's installed files and what its source would install
+//! now: line-based unified diffs (`skm diff`) or a status per file (`skm verify`).
+
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+
+use crate::bundle::SkillType;
+use crate::config::Config;
+use crate::install::{install_bundle_from_source, render_install, InstallOptions};
+use crate::install_manifest::InstallManifest;
+use crate::target::Tool;
+
+/// Lines of unchanged context around each change
+const CONTEXT: usize = 3;
+
+/// How an installed bundle differs from its source. Paths are relative to the
+/// install target.
+#[derive(Debug, Default, PartialEq)]
+pub struct BundleDiff {
+    /// Files whose installed content differs, with a unified diff of each
+    pub changed: Vec<(PathBuf, String)>,
+    /// Files the source would install that aren't installed
+    pub only_in_source: Vec<PathBuf>,
+    /// Installed files, per the install manifest, that the source no longer writes
+    pub only_installed: Vec<PathBuf>,
+}
+
+impl BundleDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changed.is_empty() && self.only_in_source.is_empty() && self.only_installed.is_empty()
+    }
+}
+
+/// How one installed file compares to what its source would install
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FileStatus {
+    Ok,
+    Modified,
+    Missing,
+}
+
+impl FileStatus {
+    pub fn label(&self) -> &'static str {
+        match self {
+            FileStatus::Ok => "OK",
+            FileStatus::Modified => "MODIFIED",
+            FileStatus::Missing => "MISSING",
+        }
+    }
+}
+
+/// Destinations an install would write, with their content
+type Rendered = Vec<(PathBuf, Vec<u8>)>;
+
+/// The name of `bundle_name` in its source, and each file installing it for
+/// `tool` would write with its content
+fn render_bundle(
+    config: &Config,
+    bundle_name: &str,
+    tool: &Tool,
+    target_dir: &Path,
+    types: &[SkillType],
+    opts: &InstallOptions,
+) -> Result<(String, Rendered)> {
+    let (source, bundle) = config
+        .find_bundle(bundle_name)?
+        .ok_or_else(|| anyhow::anyhow!("Bundle not found: {}", bundle_name))?;
+    let rendered = render_install(opts, |opts| {
+        install_bundle_from_source(source.as_ref(), &bundle.name, tool, target_dir, types, opts)
+    })?;
+    Ok((bundle.name, rendered))
+}
+
+/// Check each file installing `bundle_name` for `tool` would write against the
+/// installed copy, byte for byte. Paths are relative to the install target.
+/// Fails if none of the files is installed and no install is recorded.
+pub fn verify_bundle(
+    config: &Config,
+    bundle_name: &str,
+    tool: &Tool,
+    target_dir: &Path,
+    types: &[SkillType],
+    opts: &InstallOptions,
+) -> Result<Vec<(PathBuf, FileStatus)>> {
+    let (name, rendered) = render_bundle(config, bundle_name, tool, target_dir, types, opts)?;
+    let statuses: Vec<(PathBuf, FileStatus)> = rendered
+        .iter()
+        .map(|(dest, content)| {
+            let status = match std::fs::read(dest) {
+                Err(_) => FileStatus::Missing,
+                Ok(installed) if installed == *content => FileStatus::Ok,
+                Ok(_) => FileStatus::Modified,
+            };
+            (dest.strip_prefix(target_dir).unwrap_or(dest).to_path_buf(), status)
+        })
+        .collect();
+
+    let recorded = InstallManifest::load(tool, target_dir).bundle_names().contains(&name.as_str());
+    if !recorded && statuses.iter().all(|(_, status)| *status == FileStatus::Missing) {
+        anyhow::bail!("{} is not installed for {} in {}", name, tool.name(), target_dir.display());
+    }
+    Ok(statuses)
+}
+
+/// Compare the installed files of `bundle_name` for `tool` against what
+/// installing it from its source would write
+pub fn bundle_diff(
+    config: &Config,
+    bundle_name: &str,
+    tool: &Tool,
+    target_dir: &Path,
+    types: &[SkillType],
+    opts: &InstallOptions,
+) -> Result<BundleDiff> {
+    let (name, rendered) = render_bundle(config, bundle_name, tool, target_dir, types, opts)?;
+
+    let relative = |path: &Path| path.strip_prefix(target_dir).unwrap_or(path).to_path_buf();
+    let mut diff = BundleDiff::default();
+    for (dest, content) in &rendered {
+        let Ok(installed) = std::fs::read(dest) else {
+            diff.only_in_source.push(relative(dest));
+            continue;
+        };
+        let path = relative(dest);
+        let label = path.display().to_string();
+        let old = String::from_utf8_lossy(&installed);
+        let new = String::from_utf8_lossy(content);
+        if let Some(text) = unified(&old, &new, &format!("{} (installed)", label), &label) {
+            diff.changed.push((path, text));
+        }
+    }
+
+    let manifest = InstallManifest::load(tool, target_dir);
+    for file in manifest.files_of(&name) {
+        let dest = target_dir.join(file);
+        if dest.exists() && !rendered.iter().any(|(d, _)| *d == dest) {
+            diff.only_installed.push(file.clone());
+        }
+    }
+    Ok(diff)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Equal,
+    Delete,
+    Insert,
+}
+
+/// Unified diff turning `old` into `new`, or `None` when they are identical
+pub fn unified(old: &str, new: &str, old_label: &str, new_label: &str) -> Option<String> {
+    if old == new {
+        return None;
+    }
+    let a: Vec<&str> = old.split_inclusive('\n').collect();
+    let b: Vec<&str> = new.split_inclusive('\n').collect();
+
+    // Longest common subsequence of the suffixes a[i..] and b[j..]
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    // Each op with the line it applies to
+    let mut ops: Vec<(Op, &str)> = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            ops.push((Op::Equal, a[i]));
+            i += 1;
+            j += 1;
+        } else if i < a.len() && (j == b.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            // Deletions go before insertions, as in other diff tools
+            ops.push((Op::Delete, a[i]));
+            i += 1;
+        } else {
+            ops.push((Op::Insert, b[j]));
+            j += 1;
+        }
+    }
+
+    let mut out = format!("--- {}\n+++ {}\n", old_label, new_label);
+    for (start, end) in hunks(&ops) {
+        let before = &ops[..start];
+        let old_start = before.iter().filter(|(op, _)| *op != Op::Insert).count();
+        let new_start = before.iter().filter(|(op, _)| *op != Op::Delete).count();
+        let hunk = &ops[start..end];
+        let old_len = hunk.iter().filter(|(op, _)| *op != Op::Insert).count();
+        let new_len = hunk.iter().filter(|(op, _)| *op != Op::Delete).count();
+        out.push_str(&format!(
+            "@@ -{} +{} @@\n",
+            hunk_range(old_start, old_len),
+            hunk_range(new_start, new_len)
+        ));
+        for (op, line) in hunk {
+            let marker = match op {
+                Op::Equal => ' ',
+                Op::Delete => '-',
+                Op::Insert => '+',
+            };
+            out.push(marker);
+            out.push_str(line.strip_suffix('\n').unwrap_or(line));
+            out.push('\n');
+            if !line.ends_with('\n') {
+                out.push_str("\\ No newline at end of file\n");
+            }
+        }
+    }
+    Some(out)
+}
+
+/// `[start, end)` op ranges of each hunk: the changes plus `CONTEXT` lines
+/// around them, merging changes whose context overlaps
+fn hunks(ops: &[(Op, &str)]) -> Vec<(usize, usize)> {
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for (i, _) in ops.iter().enumerate().filter(|(_, (op, _))| *op != Op::Equal) {
+        let start = i.saturating_sub(CONTEXT);
+        let end = (i + 1 + CONTEXT).min(ops.len());
+        match hunks.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+    hunks
+}
+
+/// `start,len` as in a hunk header; 1-based, and pointing before the hunk when empty
+fn hunk_range(start: usize, len: usize) -> String {
+    if len == 0 {
+        format!("{},0", start)
+    } else {
+        format!("{},{}", start + 1, len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_unified_identical_is_none() {
+        assert_eq!(unified("a\nb\n", "a\nb\n", "old", "new"), None);
+    }
+
+    #[test]
+    fn test_unified_shows_changed_line_with_context() {
+        let old = "1\n2\n3\n4\n5\n6\n7\n8\n9\n";
+        let new = "1\n2\n3\n4\nfive\n6\n7\n8\n9\n";
+        let diff = unified(old, new, "a.md (installed)", "a.md").unwrap();
+        assert_eq!(
+            diff,
+            "--- a.md (installed)\n+++ a.md\n@@ -2,7 +2,7 @@\n 2\n 3\n 4\n-5\n+five\n 6\n 7\n 8\n"
+        );
+    }
+
+    #[test]
+    fn test_unified_marks_missing_final_newline() {
+        let diff = unified("a\n", "a\nb", "old", "new").unwrap();
+        assert!(diff.ends_with("@@ -1,1 +1,2 @@\n a\n+b\n\\ No newline at end of file\n"));
+        let diff = unified("", "a\n", "old", "new").unwrap();
+        assert!(diff.contains("@@ -0,0 +1,1 @@\n+a\n"), "{}", diff);
+    }
+
+    #[test]
+    fn test_bundle_diff_reports_edits_and_missing_files() {
+        let src = tempdir().unwrap();
+        let commands = src.path().join("pdf/commands");
+        std::fs::create_dir_all(&commands).unwrap();
+        std::fs::write(commands.join("run.md"), "# Run\n\nStep one\n").unwrap();
+        std::fs::write(commands.join("check.md"), "# Check\n").unwrap();
+        let config = Config::new(vec![crate::config::SourceConfig::local(
+            src.path().to_string_lossy().to_string(),
+            None,
+        )]);
+        let project = tempdir().unwrap();
+        let types = [SkillType::Command];
+        let diff = || {
+            bundle_diff(&config, "pdf", &Tool::Claude, project.path(), &types, &Default::default())
+                .unwrap()
+        };
+
+        let (source, _) = config.find_bundle("pdf").unwrap().unwrap();
+        let install = || {
+            install_bundle_from_source(
+                source.as_ref(),
+                "pdf",
+                &Tool::Claude,
+                project.path(),
+                &types,
+                &InstallOptions::default(),
+            )
+            .unwrap()
+        };
+        install();
+        assert!(diff().is_empty(), "a fresh install matches its source");
+
+        let installed = project.path().join(".claude/commands/pdf");
+        std::fs::write(installed.join("run.md"), "# Run\n\nStep 1\n").unwrap();
+        std::fs::remove_file(installed.join("check.md")).unwrap();
+        std::fs::write(commands.join("extra.md"), "# Extra\n").unwrap();
+        let mut manifest = InstallManifest::load(&Tool::Claude, project.path());
+        manifest.record_files("pdf", &[PathBuf::from(".claude/commands/pdf/old.md")]);
+        manifest.save(&Tool::Claude, project.path()).unwrap();
+        std::fs::write(installed.join("old.md"), "# Old\n").unwrap();
+
+        let diff = diff();
+        assert_eq!(diff.changed.len(), 1);
+        let (path, text) = &diff.changed[0];
+        assert_eq!(path, Path::new(".claude/commands/pdf/run.md"));
+        assert!(text.contains("-Step 1\n+Step one\n"), "{}", text);
+        let mut only_in_source = diff.only_in_source.clone();
+        only_in_source.sort();
+        assert_eq!(
+            only_in_source,
+            vec![
+                PathBuf::from(".claude/commands/pdf/check.md"),
+                PathBuf::from(".claude/commands/pdf/extra.md"),
+            ]
+        );
+        assert_eq!(diff.only_installed, vec![PathBuf::from(".claude/commands/pdf/old.md")]);
+    }
+
+    #[test]
+    fn test_verify_reports_ok_modified_and_missing() {
+        let src = tempdir().unwrap();
+        let agents = src.path().join("pdf/agents");
+        std::fs::create_dir_all(&agents).unwrap();
+        std::fs::write(agents.join("reader.md"), "---\nname: reader\ntools: Read\n---\nReads.\n")
+            .unwrap();
+        std::fs::write(agents.join("writer.md"), "---\nname: writer\n---\nWrites.\n").unwrap();
+        let config = Config::new(vec![crate::config::SourceConfig::local(
+            src.path().to_string_lossy().to_string(),
+            None,
+        )]);
+        let project = tempdir().unwrap();
+        let types = [SkillType::Agent];
+        let opts = InstallOptions::default();
+        let verify =
+            || verify_bundle(&config, "pdf", &Tool::OpenCode, project.path(), &types, &opts);
+
+        let err = verify().unwrap_err();
+        assert!(err.to_string().contains("pdf is not installed for OpenCode"), "{}", err);
+
+        let (source, _) = config.find_bundle("pdf").unwrap().unwrap();
+        install_bundle_from_source(
+            source.as_ref(),
+            "pdf",
+            &Tool::OpenCode,
+            project.path(),
+            &types,
+            &InstallOptions::default(),
+        )
+        .unwrap();
+        let reader = PathBuf::from(".opencode/agents/pdf-reader.md");
+        let writer = PathBuf::from(".opencode/agents/pdf-writer.md");
+        // The installed agents hold the transformed content, which verifies as OK
+        assert_eq!(
+            verify().unwrap(),
+            vec![(reader.clone(), FileStatus::Ok), (writer.clone(), FileStatus::Ok)]
+        );
+
+        let installed = project.path().join(&reader);
+        let edited = std::fs::read_to_string(&installed).unwrap().replace("Reads.", "Skims.");
+        std::fs::write(&installed, edited).unwrap();
+        std::fs::remove_file(project.path().join(&writer)).unwrap();
+        assert_eq!(
+            verify().unwrap(),
+            vec![(reader, FileStatus::Modified), (writer, FileStatus::Missing)]
+        );
+    }
+}
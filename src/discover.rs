@@ -2,10 +2,13 @@ use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
 use walkdir::WalkDir;
 
+use crate::target::Tool;
+
 /// Represents an installed skill discovered in the current directory
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct InstalledSkill {
     /// The name of the skill (derived from filename)
     pub name: String,
@@ -17,14 +20,78 @@ pub struct InstalledSkill {
     pub path: PathBuf,
     /// Optional bundle name (if detectable from path structure)
     pub bundle: Option<String>,
+    /// Whether this was found in the project or in the tool's global location
+    pub scope: Scope,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Scope {
+    Local,
+    Global,
+}
+
+impl Scope {
+    /// Tag appended to names in listings (empty for project installs)
+    pub fn tag(&self) -> &'static str {
+        match self {
+            Scope::Local => "",
+            Scope::Global => " (global)",
+        }
+    }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+impl InstalledSkill {
+    /// `bundle/name` for display, splitting combined `{bundle}-{name}` installs
+    ///
+    /// Tools other than Claude store skills under a single combined name, so the
+    /// bundle is recovered from `bundles` (the installed bundle names, longest
+    /// match wins) or, failing that, a name made of two identical halves.
+    pub fn display_name(&self, bundles: &[&str]) -> String {
+        match self.bundle.as_deref() {
+            Some(bundle) if bundle != self.name => format!("{}/{}", bundle, self.name),
+            _ => match split_combined_name(&self.name, bundles) {
+                Some((bundle, name)) => format!("{}/{}", bundle, name),
+                None => self.name.clone(),
+            },
+        }
+    }
+}
+
+/// Split a combined `{bundle}-{name}` into its parts
+fn split_combined_name<'a>(combined: &'a str, bundles: &[&str]) -> Option<(&'a str, &'a str)> {
+    let from_manifest = bundles
+        .iter()
+        .filter(|b| {
+            combined.len() > b.len() + 1
+                && combined.starts_with(*b)
+                && combined.as_bytes()[b.len()] == b'-'
+        })
+        .map(|b| b.len())
+        .max();
+    if let Some(len) = from_manifest {
+        return Some((&combined[..len], &combined[len + 1..]));
+    }
+
+    // Single-skill bundles install as `{bundle}-{bundle}`
+    let half = combined.len() / 2;
+    if combined.len() % 2 == 1
+        && combined.as_bytes()[half] == b'-'
+        && combined[..half] == combined[half + 1..]
+    {
+        return Some((&combined[..half], &combined[half + 1..]));
+    }
+    None
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum InstalledTool {
     Claude,
     OpenCode,
     Cursor,
     Codex,
+    Windsurf,
 }
 
 impl InstalledTool {
@@ -34,6 +101,7 @@ impl InstalledTool {
             InstalledTool::OpenCode => "opencode",
             InstalledTool::Cursor => "cursor",
             InstalledTool::Codex => "codex",
+            InstalledTool::Windsurf => "windsurf",
         }
     }
 
@@ -43,11 +111,24 @@ impl InstalledTool {
             InstalledTool::OpenCode => "OpenCode",
             InstalledTool::Cursor => "Cursor",
             InstalledTool::Codex => "Codex",
+            InstalledTool::Windsurf => "Windsurf",
+        }
+    }
+
+    /// The tool whose installs of `tool` this finds
+    pub fn of(tool: &Tool) -> Self {
+        match tool {
+            Tool::Claude => InstalledTool::Claude,
+            Tool::OpenCode => InstalledTool::OpenCode,
+            Tool::Cursor => InstalledTool::Cursor,
+            Tool::Codex => InstalledTool::Codex,
+            Tool::Windsurf => InstalledTool::Windsurf,
         }
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum SkillType {
     Skill,
     Agent,
@@ -66,25 +147,181 @@ impl SkillType {
     }
 }
 
+/// Marker file for folder-based commands (`commands/{name}/COMMAND.md`)
+const COMMAND_FILE: &str = "COMMAND.md";
+
+/// Detect a folder-based command at `dir`, named after the folder
+fn folder_command(dir: &Path, tool: InstalledTool) -> Option<InstalledSkill> {
+    let command_file = dir.join(COMMAND_FILE);
+    if !dir.is_dir() || !command_file.exists() {
+        return None;
+    }
+    let name = dir.file_name()?.to_str()?.to_string();
+    Some(InstalledSkill {
+        name: name.clone(),
+        skill_type: SkillType::Command,
+        tool,
+        path: command_file,
+        bundle: Some(name),
+        scope: Scope::Local,
+    })
+}
+
 /// Discover all installed skills in a directory
 pub fn discover_installed(base: &Path) -> Result<Vec<InstalledSkill>> {
+    discover_installed_for(base, &Tool::ALL)
+}
+
+/// Discover the installed skills of `tools` in a directory. Other tools'
+/// directories aren't read.
+pub fn discover_installed_for(base: &Path, tools: &[Tool]) -> Result<Vec<InstalledSkill>> {
     let mut skills = Vec::new();
+    for tool in tools {
+        skills.extend(discover_tool(InstalledTool::of(tool), base)?);
+        skills.extend(discover_recorded(tool, base));
+    }
+    Ok(skills)
+}
 
-    // Discover Claude skills
-    skills.extend(discover_claude(base)?);
+/// Files that `tool`'s install manifest in `base` records outside the tool's
+/// folder (installed through a `[destinations]` template), which scanning the
+/// folder doesn't reach. Companions of a folder item are left out: its
+/// SKILL.md stands for the whole folder.
+fn discover_recorded(tool: &Tool, base: &Path) -> Vec<InstalledSkill> {
+    let tool_dir = base.join(tool.tool_dir_name());
+    let manifest = crate::install_manifest::InstallManifest::load(tool, base);
+    let mut skills = Vec::new();
+    for entry in &manifest.bundles {
+        let paths: Vec<PathBuf> = entry
+            .files
+            .iter()
+            .map(|f| base.join(f))
+            .filter(|p| !p.starts_with(&tool_dir) && p.is_file())
+            .collect();
+        let folders: Vec<&Path> = paths
+            .iter()
+            .filter(|p| folder_type(p).is_some())
+            .filter_map(|p| p.parent())
+            .collect();
+        for path in &paths {
+            if folder_type(path).is_none() && folders.iter().any(|f| path.starts_with(f)) {
+                continue;
+            }
+            skills.push(recorded_skill(path, tool, &entry.name));
+        }
+    }
+    skills
+}
 
-    // Discover OpenCode skills
-    skills.extend(discover_opencode(base)?);
+/// The type of a folder item's main file (`SKILL.md`, `RULE.md`, `COMMAND.md`)
+fn folder_type(path: &Path) -> Option<SkillType> {
+    match path.file_name()?.to_str()? {
+        "SKILL.md" => Some(SkillType::Skill),
+        "RULE.md" => Some(SkillType::Rule),
+        COMMAND_FILE => Some(SkillType::Command),
+        _ => None,
+    }
+}
 
-    // Discover Cursor skills
-    skills.extend(discover_cursor(base)?);
+/// An install of `bundle` recorded at `path`, typed by its file name or by
+/// the `agents`/`rules` folder it's in
+fn recorded_skill(path: &Path, tool: &Tool, bundle: &str) -> InstalledSkill {
+    let in_dir = |dir: &str| path.components().any(|c| c.as_os_str() == dir);
+    let (skill_type, name) = match folder_type(path) {
+        Some(skill_type) => (skill_type, path.parent().and_then(Path::file_name)),
+        None if in_dir("agents") => (SkillType::Agent, path.file_stem()),
+        None if in_dir("rules") => (SkillType::Rule, path.file_stem()),
+        None => (SkillType::Command, path.file_stem()),
+    };
+    InstalledSkill {
+        name: name.map(|n| n.to_string_lossy().into_owned()).unwrap_or_default(),
+        skill_type,
+        tool: InstalledTool::of(tool),
+        path: path.to_path_buf(),
+        bundle: Some(bundle.to_string()),
+        scope: Scope::Local,
+    }
+}
 
-    // Discover Codex skills
-    skills.extend(discover_codex(base)?);
+/// Discover one tool's installed skills in a directory
+fn discover_tool(tool: InstalledTool, base: &Path) -> Result<Vec<InstalledSkill>> {
+    match tool {
+        InstalledTool::Claude => discover_claude(base),
+        InstalledTool::OpenCode => discover_opencode(base),
+        InstalledTool::Cursor => discover_cursor(base),
+        InstalledTool::Codex => discover_codex(base),
+        // Rules and workflows
+        InstalledTool::Windsurf => discover_windsurf(base),
+    }
+}
 
+/// How many levels below the starting directory `skm here --recursive` searches
+pub const NESTED_MAX_DEPTH: usize = 4;
+
+/// Dependency and build folders never searched for nested installs
+const NESTED_SKIP_DIRS: &[&str] = &["node_modules", "target", "vendor", "dist"];
+
+/// Discover installs of `tools` in `root` and its subdirectories up to
+/// `max_depth` levels down (e.g. monorepo packages), grouped by the directory
+/// holding the tool dirs. Hidden directories and dependency folders are not searched.
+pub fn discover_nested(
+    root: &Path,
+    max_depth: usize,
+    tools: &[Tool],
+) -> Result<Vec<(PathBuf, Vec<InstalledSkill>)>> {
+    let walker = WalkDir::new(root)
+        .max_depth(max_depth)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| {
+            let name = e.file_name().to_string_lossy();
+            e.depth() == 0
+                || (e.file_type().is_dir()
+                    && !name.starts_with('.')
+                    && !NESTED_SKIP_DIRS.contains(&name.as_ref()))
+        });
+
+    let mut found = Vec::new();
+    for entry in walker.filter_map(|e| e.ok()) {
+        let skills = discover_installed_for(entry.path(), tools)?;
+        if !skills.is_empty() {
+            found.push((entry.into_path(), skills));
+        }
+    }
+    Ok(found)
+}
+
+/// Discover skills installed in the global locations `targets` (see
+/// `Config::global_targets`), skipping any location that is `local_base` itself
+pub fn discover_global(
+    local_base: &Path,
+    targets: &[(Tool, PathBuf)],
+) -> Result<Vec<InstalledSkill>> {
+    let same_dir = |a: &Path, b: &Path| match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    };
+    let mut skills = Vec::new();
+    for (tool, base) in targets {
+        if !same_dir(base, local_base) {
+            skills.extend(discover_global_at(InstalledTool::of(tool), base)?);
+        }
+    }
     Ok(skills)
 }
 
+/// Discover one tool's skills under a global base, marked as global
+fn discover_global_at(tool: InstalledTool, base: &Path) -> Result<Vec<InstalledSkill>> {
+    let skills = discover_tool(tool, base)?;
+    Ok(skills
+        .into_iter()
+        .map(|skill| InstalledSkill {
+            scope: Scope::Global,
+            ..skill
+        })
+        .collect())
+}
+
 /// Discover Claude installed skills
 fn discover_claude(base: &Path) -> Result<Vec<InstalledSkill>> {
     let mut skills = Vec::new();
@@ -105,6 +342,18 @@ fn discover_claude(base: &Path) -> Result<Vec<InstalledSkill>> {
             .filter(|e| e.path().extension().map(|ext| ext == "md").unwrap_or(false))
         {
             let path = entry.path().to_path_buf();
+
+            // Folder-based command: .claude/commands/name/COMMAND.md
+            if path.file_name().is_some_and(|n| n == COMMAND_FILE) {
+                if let Some(command) = path
+                    .parent()
+                    .and_then(|dir| folder_command(dir, InstalledTool::Claude))
+                {
+                    skills.push(command);
+                }
+                continue;
+            }
+
             let name = path
                 .file_stem()
                 .and_then(|s| s.to_str())
@@ -127,6 +376,7 @@ fn discover_claude(base: &Path) -> Result<Vec<InstalledSkill>> {
                     tool: InstalledTool::Claude,
                     path,
                     bundle,
+                    scope: Scope::Local,
                 });
             }
         }
@@ -164,6 +414,7 @@ fn discover_claude(base: &Path) -> Result<Vec<InstalledSkill>> {
                     tool: InstalledTool::Claude,
                     path,
                     bundle,
+                    scope: Scope::Local,
                 });
             }
         }
@@ -172,9 +423,7 @@ fn discover_claude(base: &Path) -> Result<Vec<InstalledSkill>> {
     // .claude/skills/*/SKILL.md -> skills (folder-based format)
     let skills_dir = claude_dir.join("skills");
     if skills_dir.exists() {
-        for entry in std::fs::read_dir(&skills_dir)? {
-            let entry = entry?;
-            let path = entry.path();
+        for path in crate::bundle::dir_entries(&skills_dir)? {
             if path.is_dir() {
                 let skill_file = path.join("SKILL.md");
                 if skill_file.exists() {
@@ -191,6 +440,7 @@ fn discover_claude(base: &Path) -> Result<Vec<InstalledSkill>> {
                             tool: InstalledTool::Claude,
                             path: skill_file,
                             bundle: Some(name),
+                            scope: Scope::Local,
                         });
                     }
                 }
@@ -201,9 +451,7 @@ fn discover_claude(base: &Path) -> Result<Vec<InstalledSkill>> {
     // .claude/rules/*/RULE.md -> rules (folder-based format)
     let rules_dir = claude_dir.join("rules");
     if rules_dir.exists() {
-        for entry in std::fs::read_dir(&rules_dir)? {
-            let entry = entry?;
-            let path = entry.path();
+        for path in crate::bundle::dir_entries(&rules_dir)? {
             if path.is_dir() {
                 let rule_file = path.join("RULE.md");
                 if rule_file.exists() {
@@ -220,6 +468,7 @@ fn discover_claude(base: &Path) -> Result<Vec<InstalledSkill>> {
                             tool: InstalledTool::Claude,
                             path: rule_file,
                             bundle: Some(name),
+                            scope: Scope::Local,
                         });
                     }
                 }
@@ -242,9 +491,7 @@ fn discover_opencode(base: &Path) -> Result<Vec<InstalledSkill>> {
     // .opencode/skills/*/SKILL.md -> skills
     let skill_dir = opencode_dir.join("skills");
     if skill_dir.exists() {
-        for entry in std::fs::read_dir(&skill_dir)? {
-            let entry = entry?;
-            let path = entry.path();
+        for path in crate::bundle::dir_entries(&skill_dir)? {
             if path.is_dir() {
                 let skill_file = path.join("SKILL.md");
                 if skill_file.exists() {
@@ -261,6 +508,7 @@ fn discover_opencode(base: &Path) -> Result<Vec<InstalledSkill>> {
                             tool: InstalledTool::OpenCode,
                             path: skill_file,
                             bundle: Some(name),
+                            scope: Scope::Local,
                         });
                     }
                 }
@@ -271,9 +519,7 @@ fn discover_opencode(base: &Path) -> Result<Vec<InstalledSkill>> {
     // .opencode/agents/*.md -> agents
     let agent_dir = opencode_dir.join("agents");
     if agent_dir.exists() {
-        for entry in std::fs::read_dir(&agent_dir)? {
-            let entry = entry?;
-            let path = entry.path();
+        for path in crate::bundle::dir_entries(&agent_dir)? {
             if path.is_file() && path.extension().map(|e| e == "md").unwrap_or(false) {
                 let name = path
                     .file_stem()
@@ -288,6 +534,7 @@ fn discover_opencode(base: &Path) -> Result<Vec<InstalledSkill>> {
                         tool: InstalledTool::OpenCode,
                         path,
                         bundle: None,
+                        scope: Scope::Local,
                     });
                 }
             }
@@ -297,9 +544,7 @@ fn discover_opencode(base: &Path) -> Result<Vec<InstalledSkill>> {
     // .opencode/commands/*.md -> commands
     let command_dir = opencode_dir.join("commands");
     if command_dir.exists() {
-        for entry in std::fs::read_dir(&command_dir)? {
-            let entry = entry?;
-            let path = entry.path();
+        for path in crate::bundle::dir_entries(&command_dir)? {
             if path.is_file() && path.extension().map(|e| e == "md").unwrap_or(false) {
                 let name = path
                     .file_stem()
@@ -314,8 +559,11 @@ fn discover_opencode(base: &Path) -> Result<Vec<InstalledSkill>> {
                         tool: InstalledTool::OpenCode,
                         path,
                         bundle: None,
+                        scope: Scope::Local,
                     });
                 }
+            } else if let Some(command) = folder_command(&path, InstalledTool::OpenCode) {
+                skills.push(command);
             }
         }
     }
@@ -323,9 +571,7 @@ fn discover_opencode(base: &Path) -> Result<Vec<InstalledSkill>> {
     // .opencode/rules/*/RULE.md -> rules
     let rule_dir = opencode_dir.join("rules");
     if rule_dir.exists() {
-        for entry in std::fs::read_dir(&rule_dir)? {
-            let entry = entry?;
-            let path = entry.path();
+        for path in crate::bundle::dir_entries(&rule_dir)? {
             if path.is_dir() {
                 let rule_file = path.join("RULE.md");
                 if rule_file.exists() {
@@ -342,6 +588,7 @@ fn discover_opencode(base: &Path) -> Result<Vec<InstalledSkill>> {
                             tool: InstalledTool::OpenCode,
                             path: rule_file,
                             bundle: Some(name),
+                            scope: Scope::Local,
                         });
                     }
                 }
@@ -364,9 +611,7 @@ fn discover_cursor(base: &Path) -> Result<Vec<InstalledSkill>> {
     // .cursor/skills/*/SKILL.md -> skills
     let skills_dir = cursor_dir.join("skills");
     if skills_dir.exists() {
-        for entry in std::fs::read_dir(&skills_dir)? {
-            let entry = entry?;
-            let path = entry.path();
+        for path in crate::bundle::dir_entries(&skills_dir)? {
             if path.is_dir() {
                 let skill_file = path.join("SKILL.md");
                 if skill_file.exists() {
@@ -383,6 +628,7 @@ fn discover_cursor(base: &Path) -> Result<Vec<InstalledSkill>> {
                             tool: InstalledTool::Cursor,
                             path: skill_file,
                             bundle: Some(name),
+                            scope: Scope::Local,
                         });
                     }
                 }
@@ -393,9 +639,7 @@ fn discover_cursor(base: &Path) -> Result<Vec<InstalledSkill>> {
     // .cursor/agents/*.md -> agents (subagents)
     let agents_dir = cursor_dir.join("agents");
     if agents_dir.exists() {
-        for entry in std::fs::read_dir(&agents_dir)? {
-            let entry = entry?;
-            let path = entry.path();
+        for path in crate::bundle::dir_entries(&agents_dir)? {
             if path.is_file() && path.extension().map(|e| e == "md").unwrap_or(false) {
                 let name = path
                     .file_stem()
@@ -410,6 +654,7 @@ fn discover_cursor(base: &Path) -> Result<Vec<InstalledSkill>> {
                         tool: InstalledTool::Cursor,
                         path,
                         bundle: None,
+                        scope: Scope::Local,
                     });
                 }
             }
@@ -419,9 +664,7 @@ fn discover_cursor(base: &Path) -> Result<Vec<InstalledSkill>> {
     // .cursor/commands/*.md -> commands
     let commands_dir = cursor_dir.join("commands");
     if commands_dir.exists() {
-        for entry in std::fs::read_dir(&commands_dir)? {
-            let entry = entry?;
-            let path = entry.path();
+        for path in crate::bundle::dir_entries(&commands_dir)? {
             if path.is_file() && path.extension().map(|e| e == "md").unwrap_or(false) {
                 let name = path
                     .file_stem()
@@ -436,19 +679,37 @@ fn discover_cursor(base: &Path) -> Result<Vec<InstalledSkill>> {
                         tool: InstalledTool::Cursor,
                         path,
                         bundle: None,
+                        scope: Scope::Local,
                     });
                 }
+            } else if let Some(command) = folder_command(&path, InstalledTool::Cursor) {
+                skills.push(command);
             }
         }
     }
 
-    // .cursor/rules/*/RULE.md -> rules (folder-based)
+    // .cursor/rules/*/RULE.md -> rules (folder-based), .cursor/rules/*.mdc -> rules (flat)
     let rules_dir = cursor_dir.join("rules");
     if rules_dir.exists() {
-        for entry in std::fs::read_dir(&rules_dir)? {
-            let entry = entry?;
-            let path = entry.path();
-            if path.is_dir() {
+        for path in crate::bundle::dir_entries(&rules_dir)? {
+            if path.is_file() && path.extension().is_some_and(|e| e == "mdc") {
+                let name = path
+                    .file_stem()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or("")
+                    .to_string();
+
+                if !name.is_empty() {
+                    skills.push(InstalledSkill {
+                        name,
+                        skill_type: SkillType::Rule,
+                        tool: InstalledTool::Cursor,
+                        path,
+                        bundle: None,
+                        scope: Scope::Local,
+                    });
+                }
+            } else if path.is_dir() {
                 let rule_file = path.join("RULE.md");
                 if rule_file.exists() {
                     let name = path
@@ -464,6 +725,7 @@ fn discover_cursor(base: &Path) -> Result<Vec<InstalledSkill>> {
                             tool: InstalledTool::Cursor,
                             path: rule_file,
                             bundle: Some(name),
+                            scope: Scope::Local,
                         });
                     }
                 }
@@ -486,9 +748,7 @@ fn discover_codex(base: &Path) -> Result<Vec<InstalledSkill>> {
     // .codex/skills/*/SKILL.md -> skills
     let skills_dir = codex_dir.join("skills");
     if skills_dir.exists() {
-        for entry in std::fs::read_dir(&skills_dir)? {
-            let entry = entry?;
-            let path = entry.path();
+        for path in crate::bundle::dir_entries(&skills_dir)? {
             if path.is_dir() {
                 let skill_file = path.join("SKILL.md");
                 if skill_file.exists() {
@@ -505,6 +765,7 @@ fn discover_codex(base: &Path) -> Result<Vec<InstalledSkill>> {
                             tool: InstalledTool::Codex,
                             path: skill_file,
                             bundle: Some(name),
+                            scope: Scope::Local,
                         });
                     }
                 }
@@ -515,9 +776,7 @@ fn discover_codex(base: &Path) -> Result<Vec<InstalledSkill>> {
     // .codex/agents/*.md -> agents
     let agents_dir = codex_dir.join("agents");
     if agents_dir.exists() {
-        for entry in std::fs::read_dir(&agents_dir)? {
-            let entry = entry?;
-            let path = entry.path();
+        for path in crate::bundle::dir_entries(&agents_dir)? {
             if path.is_file() && path.extension().map(|e| e == "md").unwrap_or(false) {
                 let name = path
                     .file_stem()
@@ -532,6 +791,7 @@ fn discover_codex(base: &Path) -> Result<Vec<InstalledSkill>> {
                         tool: InstalledTool::Codex,
                         path,
                         bundle: None,
+                        scope: Scope::Local,
                     });
                 }
             }
@@ -541,9 +801,7 @@ fn discover_codex(base: &Path) -> Result<Vec<InstalledSkill>> {
     // .codex/commands/*.md -> commands
     let commands_dir = codex_dir.join("commands");
     if commands_dir.exists() {
-        for entry in std::fs::read_dir(&commands_dir)? {
-            let entry = entry?;
-            let path = entry.path();
+        for path in crate::bundle::dir_entries(&commands_dir)? {
             if path.is_file() && path.extension().map(|e| e == "md").unwrap_or(false) {
                 let name = path
                     .file_stem()
@@ -558,8 +816,11 @@ fn discover_codex(base: &Path) -> Result<Vec<InstalledSkill>> {
                         tool: InstalledTool::Codex,
                         path,
                         bundle: None,
+                        scope: Scope::Local,
                     });
                 }
+            } else if let Some(command) = folder_command(&path, InstalledTool::Codex) {
+                skills.push(command);
             }
         }
     }
@@ -567,9 +828,7 @@ fn discover_codex(base: &Path) -> Result<Vec<InstalledSkill>> {
     // .codex/rules/*/RULE.md -> rules
     let rules_dir = codex_dir.join("rules");
     if rules_dir.exists() {
-        for entry in std::fs::read_dir(&rules_dir)? {
-            let entry = entry?;
-            let path = entry.path();
+        for path in crate::bundle::dir_entries(&rules_dir)? {
             if path.is_dir() {
                 let rule_file = path.join("RULE.md");
                 if rule_file.exists() {
@@ -586,6 +845,7 @@ fn discover_codex(base: &Path) -> Result<Vec<InstalledSkill>> {
                             tool: InstalledTool::Codex,
                             path: rule_file,
                             bundle: Some(name),
+                            scope: Scope::Local,
                         });
                     }
                 }
@@ -596,6 +856,46 @@ fn discover_codex(base: &Path) -> Result<Vec<InstalledSkill>> {
     Ok(skills)
 }
 
+/// Discover Windsurf installed rules and workflows
+fn discover_windsurf(base: &Path) -> Result<Vec<InstalledSkill>> {
+    let mut skills = Vec::new();
+    let windsurf_dir = base.join(".windsurf");
+
+    if !windsurf_dir.exists() {
+        return Ok(skills);
+    }
+
+    // .windsurf/rules/*.md -> rules, .windsurf/workflows/*.md -> commands (both flat)
+    for (dir_name, skill_type) in [("rules", SkillType::Rule), ("workflows", SkillType::Command)] {
+        let dir = windsurf_dir.join(dir_name);
+        if !dir.exists() {
+            continue;
+        }
+        for path in crate::bundle::dir_entries(&dir)? {
+            if path.is_file() && path.extension().map(|e| e == "md").unwrap_or(false) {
+                let name = path
+                    .file_stem()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or("")
+                    .to_string();
+
+                if !name.is_empty() {
+                    skills.push(InstalledSkill {
+                        name,
+                        skill_type,
+                        tool: InstalledTool::Windsurf,
+                        path,
+                        bundle: None,
+                        scope: Scope::Local,
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(skills)
+}
+
 /// Group skills by tool, then by type
 pub fn group_by_tool(
     skills: &[InstalledSkill],
@@ -635,6 +935,46 @@ impl InstalledSkill {
     }
 }
 
+/// Size and line count of an installed file (or a sum of several)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FileStats {
+    pub bytes: u64,
+    pub lines: usize,
+}
+
+impl FileStats {
+    /// Read stats for a single file from disk
+    pub fn read(path: &Path) -> Result<Self> {
+        let bytes = std::fs::metadata(path)?.len();
+        let content = std::fs::read(path)?;
+        let lines = content.split(|b| *b == b'\n').count()
+            - usize::from(content.is_empty() || content.ends_with(b"\n"));
+        Ok(FileStats { bytes, lines })
+    }
+
+    pub fn add(&mut self, other: FileStats) {
+        self.bytes += other.bytes;
+        self.lines += other.lines;
+    }
+}
+
+/// Sum file stats per bundle; skills without a bundle count under their own name.
+/// Returns totals sorted by bundle name.
+pub fn bundle_totals(skills: &[InstalledSkill]) -> Result<Vec<(String, FileStats)>> {
+    let mut totals: HashMap<String, FileStats> = HashMap::new();
+    for skill in skills {
+        let key = skill.bundle.clone().unwrap_or_else(|| skill.name.clone());
+        totals
+            .entry(key)
+            .or_default()
+            .add(FileStats::read(&skill.path)?);
+    }
+
+    let mut totals: Vec<_> = totals.into_iter().collect();
+    totals.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(totals)
+}
+
 /// Group skills that have the same name/bundle across different tools
 pub fn group_same_skills(skills: &[InstalledSkill]) -> HashMap<String, Vec<&InstalledSkill>> {
     let mut result: HashMap<String, Vec<&InstalledSkill>> = HashMap::new();
@@ -648,8 +988,16 @@ pub fn group_same_skills(skills: &[InstalledSkill]) -> HashMap<String, Vec<&Inst
 
 /// Remove a skill file and clean up empty parent directories
 pub fn remove_skill(skill: &InstalledSkill) -> Result<()> {
-    // For skills/rules that are directories (OpenCode/Cursor skills/rules), remove the whole directory
-    if skill.skill_type == SkillType::Skill || skill.skill_type == SkillType::Rule {
+    // For skills/rules/folder commands that are directories, remove the whole directory.
+    // Flat rules (Windsurf, Cursor `.mdc`) share their directory and are removed
+    // like other files.
+    let marker = match skill.skill_type {
+        SkillType::Skill => "SKILL.md",
+        SkillType::Rule => "RULE.md",
+        SkillType::Command => COMMAND_FILE,
+        SkillType::Agent => "",
+    };
+    if skill.path.file_name().is_some_and(|n| n == marker) {
         if let Some(parent) = skill.path.parent() {
             if parent.is_dir() {
                 std::fs::remove_dir_all(parent)?;
@@ -664,7 +1012,7 @@ pub fn remove_skill(skill: &InstalledSkill) -> Result<()> {
     // Clean up empty parent directories
     let mut current = skill.path.parent();
     while let Some(parent) = current {
-        // Stop at the tool directory (.claude, .opencode, .cursor)
+        // Stop at the tool directory (.claude, .opencode, .cursor, ...)
         if let Some(name) = parent.file_name().and_then(|n| n.to_str()) {
             if name.starts_with('.') {
                 break;
@@ -759,6 +1107,33 @@ mod tests {
         assert_eq!(skills[0].tool, InstalledTool::Cursor);
     }
 
+    #[test]
+    fn test_discover_and_remove_flat_cursor_rules() {
+        let dir = tempdir().unwrap();
+        let rules_dir = dir.path().join(".cursor/rules");
+        fs::create_dir_all(rules_dir.join("folder")).unwrap();
+        fs::write(rules_dir.join("folder/RULE.md"), "# Folder rule").unwrap();
+        fs::write(rules_dir.join("style.mdc"), "---\nalwaysApply: true\n---\n# Style").unwrap();
+        fs::write(rules_dir.join("notes.txt"), "not a rule").unwrap();
+
+        let mut skills = discover_installed(dir.path()).unwrap();
+        skills.sort_by(|a, b| a.name.cmp(&b.name));
+        let found: Vec<_> = skills.iter().map(|s| (s.name.as_str(), s.skill_type)).collect();
+        assert_eq!(found, vec![("folder", SkillType::Rule), ("style", SkillType::Rule)]);
+        assert_eq!(skills[1].path, rules_dir.join("style.mdc"));
+
+        // The flat file goes; the folder rule next to it stays
+        remove_skill(&skills[1]).unwrap();
+        assert!(!rules_dir.join("style.mdc").exists());
+        assert!(rules_dir.join("folder/RULE.md").exists());
+        let names: Vec<_> = discover_installed(dir.path())
+            .unwrap()
+            .into_iter()
+            .map(|s| s.name)
+            .collect();
+        assert_eq!(names, vec!["folder"]);
+    }
+
     #[test]
     fn test_discover_cursor_agents() {
         let dir = tempdir().unwrap();
@@ -834,6 +1209,7 @@ mod tests {
                 tool: InstalledTool::Claude,
                 path: PathBuf::from("/test1"),
                 bundle: None,
+                scope: Scope::Local,
             },
             InstalledSkill {
                 name: "test2".to_string(),
@@ -841,6 +1217,7 @@ mod tests {
                 tool: InstalledTool::OpenCode,
                 path: PathBuf::from("/test2"),
                 bundle: None,
+                scope: Scope::Local,
             },
         ];
 
@@ -912,4 +1289,250 @@ mod tests {
         assert_eq!(skills[0].skill_type, SkillType::Rule);
         assert_eq!(skills[0].tool, InstalledTool::Codex);
     }
+
+    #[test]
+    fn test_disabled_tool_is_not_discovered() {
+        let dir = tempdir().unwrap();
+        let skill_dir = dir.path().join(".codex/skills/my-skill");
+        fs::create_dir_all(&skill_dir).unwrap();
+        fs::write(skill_dir.join("SKILL.md"), "# My Skill").unwrap();
+        let commands_dir = dir.path().join(".claude/commands");
+        fs::create_dir_all(&commands_dir).unwrap();
+        fs::write(commands_dir.join("commit.md"), "# Commit").unwrap();
+
+        let mut config = crate::config::Config::new(vec![]);
+        config.enabled_tools = vec!["claude".to_string(), "cursor".to_string()];
+        let tools = config.tools();
+        assert_eq!(tools, [Tool::Claude, Tool::Cursor]);
+
+        let skills = discover_installed_for(dir.path(), &tools).unwrap();
+        assert_eq!(skills.len(), 1);
+        assert_eq!(skills[0].tool, InstalledTool::Claude);
+        let nested = discover_nested(dir.path(), NESTED_MAX_DEPTH, &tools).unwrap();
+        assert_eq!(nested.len(), 1);
+        assert_eq!(nested[0].1.len(), 1);
+        assert_eq!(discover_installed(dir.path()).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_file_stats_and_bundle_totals() {
+        let dir = tempdir().unwrap();
+
+        let bundle_dir = dir.path().join(".claude/commands/mybundle");
+        fs::create_dir_all(&bundle_dir).unwrap();
+        fs::write(bundle_dir.join("one.md"), "# One\nline two\n").unwrap();
+        fs::write(bundle_dir.join("two.md"), "# Two\nno trailing newline").unwrap();
+        let loose_dir = dir.path().join(".claude/commands");
+        fs::write(loose_dir.join("loose.md"), "").unwrap();
+
+        let skills = discover_installed(dir.path()).unwrap();
+        let one = skills.iter().find(|s| s.name == "one").unwrap();
+        assert_eq!(
+            FileStats::read(&one.path).unwrap(),
+            FileStats {
+                bytes: 15,
+                lines: 2
+            }
+        );
+
+        let totals = bundle_totals(&skills).unwrap();
+        assert_eq!(
+            totals,
+            vec![
+                ("loose".to_string(), FileStats { bytes: 0, lines: 0 }),
+                (
+                    "mybundle".to_string(),
+                    FileStats {
+                        bytes: 40,
+                        lines: 4
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_windsurf_write_discover_remove_round_trip() {
+        use crate::bundle::{SkillFile, SkillType as BundleType};
+
+        let dir = tempdir().unwrap();
+        let src = dir.path().join("src");
+        fs::create_dir(&src).unwrap();
+        for (name, skill_type) in [
+            ("style", BundleType::Rule),
+            ("lint", BundleType::Rule),
+            ("deploy", BundleType::Command),
+        ] {
+            let path = src.join(format!("{}.md", name));
+            fs::write(&path, format!("# {}", name)).unwrap();
+            let file = SkillFile {
+                name: name.to_string(),
+                path,
+                skill_type,
+                source_dir: None,
+            };
+            Tool::Windsurf.write_file(dir.path(), "tb", &file).unwrap();
+        }
+
+        let mut skills = discover_installed(dir.path()).unwrap();
+        skills.sort_by(|a, b| a.name.cmp(&b.name));
+        let found: Vec<_> = skills.iter().map(|s| (s.name.as_str(), s.skill_type)).collect();
+        assert_eq!(
+            found,
+            vec![
+                ("tb-deploy", SkillType::Command),
+                ("tb-lint", SkillType::Rule),
+                ("tb-style", SkillType::Rule),
+            ]
+        );
+        assert!(skills.iter().all(|s| s.tool == InstalledTool::Windsurf));
+
+        // Flat rules share a directory, so removing one leaves the others
+        remove_skill(&skills[1]).unwrap();
+        assert!(!dir.path().join(".windsurf/rules/tb-lint.md").exists());
+        assert!(dir.path().join(".windsurf/rules/tb-style.md").exists());
+    }
+
+    #[test]
+    fn test_discover_folder_based_commands() {
+        let dir = tempdir().unwrap();
+
+        for tool_dir in [".claude", ".opencode", ".cursor", ".codex"] {
+            let command_dir = dir.path().join(tool_dir).join("commands/deploy");
+            fs::create_dir_all(&command_dir).unwrap();
+            fs::write(command_dir.join("COMMAND.md"), "# Deploy").unwrap();
+            fs::write(command_dir.join("notes.txt"), "companion").unwrap();
+        }
+
+        let skills = discover_installed(dir.path()).unwrap();
+        assert_eq!(skills.len(), 4);
+        for skill in &skills {
+            assert_eq!(skill.name, "deploy");
+            assert_eq!(skill.skill_type, SkillType::Command);
+            assert!(skill.path.ends_with("deploy/COMMAND.md"));
+        }
+
+        // Removing a folder command removes its companion files too
+        let cursor = skills
+            .iter()
+            .find(|s| s.tool == InstalledTool::Cursor)
+            .unwrap();
+        remove_skill(cursor).unwrap();
+        assert!(!dir.path().join(".cursor/commands/deploy").exists());
+    }
+
+    #[test]
+    fn test_combined_name_displays_as_bundle_slash_name() {
+        let dir = tempdir().unwrap();
+        let skill_dir = dir.path().join(".opencode/skills/test-bundle-my-skill");
+        fs::create_dir_all(&skill_dir).unwrap();
+        fs::write(skill_dir.join("SKILL.md"), "---\nname: my-skill\n---\n").unwrap();
+        let pdf_dir = dir.path().join(".opencode/skills/pdf-pdf");
+        fs::create_dir_all(&pdf_dir).unwrap();
+        fs::write(pdf_dir.join("SKILL.md"), "---\nname: pdf\n---\n").unwrap();
+
+        let mut skills = discover_installed(dir.path()).unwrap();
+        skills.sort_by(|a, b| a.name.cmp(&b.name));
+        let bundles = ["test", "test-bundle"];
+        let names: Vec<String> = skills.iter().map(|s| s.display_name(&bundles)).collect();
+        assert_eq!(names, vec!["pdf/pdf", "test-bundle/my-skill"]);
+
+        // Without a manifest entry the combined name is shown once, not doubled
+        assert_eq!(skills[1].display_name(&[]), "test-bundle-my-skill");
+    }
+
+    #[test]
+    fn test_global_and_local_installs_are_labeled() {
+        let project = tempdir().unwrap();
+        let home = tempdir().unwrap();
+        for base in [project.path(), home.path()] {
+            let dir = base.join(".claude/commands/pdf");
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("extract.md"), "# Extract").unwrap();
+        }
+
+        let mut skills = discover_installed(project.path()).unwrap();
+        skills.extend(discover_global_at(InstalledTool::Claude, home.path()).unwrap());
+
+        let labels: Vec<String> = skills
+            .iter()
+            .map(|s| format!("{}{}", s.display_name(&[]), s.scope.tag()))
+            .collect();
+        assert_eq!(labels, vec!["pdf/extract", "pdf/extract (global)"]);
+        assert_eq!(skills[1].scope, Scope::Global);
+    }
+
+    #[test]
+    fn test_discover_nested_package_installs() {
+        let dir = tempdir().unwrap();
+        let api = dir.path().join("packages/api");
+        let web = dir.path().join("packages/web");
+        fs::create_dir_all(api.join(".claude/commands")).unwrap();
+        fs::write(api.join(".claude/commands/deploy.md"), "# Deploy").unwrap();
+        fs::create_dir_all(web.join(".cursor/commands")).unwrap();
+        fs::write(web.join(".cursor/commands/lint.md"), "# Lint").unwrap();
+        // Dependencies and anything deeper than the limit are ignored
+        let dep = dir.path().join("node_modules/pkg/.claude/commands");
+        fs::create_dir_all(&dep).unwrap();
+        fs::write(dep.join("vendored.md"), "# Vendored").unwrap();
+        let deep = dir.path().join("a/b/c/d/e/.claude/commands");
+        fs::create_dir_all(&deep).unwrap();
+        fs::write(deep.join("deep.md"), "# Deep").unwrap();
+
+        let found = discover_nested(dir.path(), NESTED_MAX_DEPTH, &Tool::ALL).unwrap();
+
+        let bases: Vec<&Path> = found.iter().map(|(base, _)| base.as_path()).collect();
+        assert_eq!(bases, vec![api.as_path(), web.as_path()]);
+        assert_eq!(found[0].1[0].name, "deploy");
+        assert_eq!(found[0].1[0].tool, InstalledTool::Claude);
+        assert_eq!(found[1].1[0].name, "lint");
+        assert_eq!(found[1].1[0].tool, InstalledTool::Cursor);
+    }
+
+    #[test]
+    fn test_opencode_install_round_trip() {
+        use crate::bundle::{SkillFile, SkillType as SourceType};
+        use crate::target::WriteOptions;
+
+        let dir = tempdir().unwrap();
+        let src = dir.path().join("src");
+        fs::create_dir_all(src.join("pdf")).unwrap();
+        fs::write(src.join("pdf/SKILL.md"), "---\nname: pdf\ndescription: PDFs\n---\n").unwrap();
+        fs::write(src.join("review.md"), "---\ndescription: Review\n---\nReview it").unwrap();
+        fs::write(src.join("fill.md"), "# Fill").unwrap();
+
+        let project = dir.path().join("project");
+        for (name, path, skill_type, source_dir) in [
+            ("pdf", src.join("pdf/SKILL.md"), SourceType::Skill, Some(src.join("pdf"))),
+            ("review", src.join("review.md"), SourceType::Agent, None),
+            ("fill", src.join("fill.md"), SourceType::Command, None),
+        ] {
+            let file = SkillFile {
+                name: name.to_string(),
+                path,
+                skill_type,
+                source_dir,
+            };
+            Tool::OpenCode
+                .write_file_with(&project, "docs", &file, &WriteOptions::default())
+                .unwrap();
+        }
+
+        // Discovery reads the same plural directories the writer uses
+        let mut found = discover_installed(&project).unwrap();
+        found.sort_by_key(|s| s.skill_type.plural());
+        let found: Vec<_> = found
+            .iter()
+            .inspect(|s| assert_eq!(s.tool, InstalledTool::OpenCode))
+            .map(|s| (s.skill_type, s.bundle.as_deref(), s.display_name(&["docs"])))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (SkillType::Agent, None, "docs/review".to_string()),
+                (SkillType::Command, None, "docs/fill".to_string()),
+                (SkillType::Skill, Some("docs-pdf"), "docs/pdf".to_string()),
+            ]
+        );
+    }
 }
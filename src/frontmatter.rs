@@ -0,0 +1,362 @@
+//! YAML frontmatter: the `---`-delimited block at the top of a markdown file.
+//!
+//! The block is parsed with `serde_yaml`, so list and multi-line values, quoted
+//! strings containing `---` and indented children are read correctly. Edits
+//! replace whole top-level fields; untouched fields keep their original text.
+
+use serde_yaml::{Mapping, Value};
+
+/// A markdown file split into its frontmatter and body
+#[derive(Debug, Clone)]
+pub struct Document<'a> {
+    /// `None` when the file doesn't start with a `---` line
+    pub frontmatter: Option<Frontmatter>,
+    /// Whether the block has its closing `---`. An unclosed block is read as
+    /// frontmatter running to the end of the file.
+    pub closed: bool,
+    /// Everything after the closing fence (the whole file without frontmatter)
+    pub body: &'a str,
+}
+
+impl<'a> Document<'a> {
+    pub fn parse(content: &'a str) -> Self {
+        match split(content) {
+            None => Document {
+                frontmatter: None,
+                closed: false,
+                body: content,
+            },
+            Some((yaml, body)) => Document {
+                frontmatter: Some(Frontmatter::parse(yaml)),
+                closed: body.is_some(),
+                body: body.unwrap_or(""),
+            },
+        }
+    }
+
+    /// The frontmatter, created empty if the file has none
+    pub fn frontmatter_mut(&mut self) -> &mut Frontmatter {
+        self.frontmatter.get_or_insert_with(|| Frontmatter::parse(""))
+    }
+
+    /// The file with its (possibly edited) frontmatter; a block is always closed
+    pub fn render(&self) -> String {
+        match &self.frontmatter {
+            None => self.body.to_string(),
+            Some(fm) => format!("---\n{}---\n{}", fm.render(), self.body),
+        }
+    }
+}
+
+/// Split `content` into its raw frontmatter block and the body after the
+/// closing fence (`None` when the block is never closed). Returns `None` when
+/// the file doesn't start with frontmatter. Fences are lines of their own, so
+/// a `---` inside a value doesn't end the block.
+pub fn split(content: &str) -> Option<(&str, Option<&str>)> {
+    let mut lines = content.split_inclusive('\n');
+    let first = lines.next()?;
+    if first.trim_end() != "---" {
+        return None;
+    }
+    let start = first.len();
+    let mut offset = start;
+    for line in lines {
+        if line.trim_end() == "---" {
+            return Some((&content[start..offset], Some(&content[offset + line.len()..])));
+        }
+        offset += line.len();
+    }
+    Some((&content[start..], None))
+}
+
+/// Parse the frontmatter at the top of `content` strictly, for checking it.
+/// `Ok(None)` when there is none; `Err` describes why it doesn't parse.
+pub fn parse_strict(content: &str) -> std::result::Result<Option<Mapping>, String> {
+    match split(content) {
+        None => Ok(None),
+        Some((_, None)) => Err("frontmatter has no closing `---`".to_string()),
+        Some((yaml, Some(_))) => parse_mapping(yaml).map(Some),
+    }
+}
+
+/// Parse a YAML block that should be a mapping of fields
+fn parse_mapping(yaml: &str) -> std::result::Result<Mapping, String> {
+    match serde_yaml::from_str(yaml) {
+        Ok(Value::Mapping(fields)) => Ok(fields),
+        Ok(Value::Null) => Ok(Mapping::new()),
+        Ok(_) => Err("frontmatter is not a list of `key: value` fields".to_string()),
+        Err(e) => Err(format!("frontmatter is not valid YAML: {}", e)),
+    }
+}
+
+/// One top-level field: its key line plus any indented or list lines below it
+#[derive(Debug, Clone)]
+struct Entry {
+    /// `None` for comments and blank lines before the first field
+    key: Option<String>,
+    text: String,
+}
+
+/// Parsed frontmatter fields, keeping each field's original text so that
+/// only the fields that are changed get re-serialized.
+#[derive(Debug, Clone)]
+pub struct Frontmatter {
+    entries: Vec<Entry>,
+    fields: Mapping,
+}
+
+impl Frontmatter {
+    /// Parse a frontmatter block (without its fences). Invalid YAML is read
+    /// field by field, with unparseable values taken as plain text, so one
+    /// bad field (e.g. an unquoted `: ` in a description) doesn't lose the rest.
+    pub fn parse(yaml: &str) -> Self {
+        let mut entries: Vec<Entry> = Vec::new();
+        for line in yaml.split_inclusive('\n') {
+            match top_level_key(line) {
+                Some(key) => entries.push(Entry {
+                    key: Some(key),
+                    text: line.to_string(),
+                }),
+                None => match entries.last_mut() {
+                    Some(entry) => entry.text.push_str(line),
+                    None => entries.push(Entry {
+                        key: None,
+                        text: line.to_string(),
+                    }),
+                },
+            }
+        }
+
+        let fields = parse_mapping(yaml).unwrap_or_else(|_| {
+            entries
+                .iter()
+                .filter_map(|e| Some((Value::from(e.key.clone()?), lenient_value(&e.text))))
+                .collect()
+        });
+        Frontmatter { entries, fields }
+    }
+
+    /// The parsed value of `key`
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.fields.get(key)
+    }
+
+    /// Whether the block has a `key:` field, whatever its value
+    pub fn contains(&self, key: &str) -> bool {
+        self.position(key).is_some()
+    }
+
+    /// The first of `keys` present, in field order
+    pub fn first_of<'k>(&self, keys: &[&'k str]) -> Option<&'k str> {
+        self.entries
+            .iter()
+            .find_map(|e| keys.iter().find(|k| e.key.as_deref() == Some(**k)).copied())
+    }
+
+    fn position(&self, key: &str) -> Option<usize> {
+        self.entries.iter().position(|e| e.key.as_deref() == Some(key))
+    }
+
+    /// Set `key` to `value`, given as inline YAML (e.g. a quoted string). An
+    /// existing field is replaced in place; a new one is added at the end.
+    pub fn set(&mut self, key: &str, value: &str) {
+        self.put(key, format!("{}: {}\n", key, value));
+    }
+
+    /// Set `key` to `value`, serialized by `serde_yaml`
+    pub fn set_value(&mut self, key: &str, value: Value) {
+        let mut field = Mapping::new();
+        field.insert(Value::from(key), value);
+        let text = serde_yaml::to_string(&field).unwrap_or_default();
+        self.put(key, text);
+    }
+
+    /// Add `key: value` (inline YAML) as the first field
+    pub fn prepend(&mut self, key: &str, value: &str) {
+        self.remove(key);
+        let text = format!("{}: {}\n", key, value);
+        self.fields.insert(Value::from(key), lenient_value(&text));
+        self.entries.insert(
+            0,
+            Entry {
+                key: Some(key.to_string()),
+                text,
+            },
+        );
+    }
+
+    /// Rename the field `from` to `to`, keeping its value as written
+    pub fn rename(&mut self, from: &str, to: &str) {
+        if from == to || !self.contains(from) {
+            return;
+        }
+        self.remove(to);
+        let Some(i) = self.position(from) else {
+            return;
+        };
+        let entry = &mut self.entries[i];
+        if let Some(rest) = entry.text.strip_prefix(from) {
+            entry.text = format!("{}{}", to, rest);
+        }
+        entry.key = Some(to.to_string());
+        if let Some(value) = self.fields.remove(from) {
+            self.fields.insert(Value::from(to), value);
+        }
+    }
+
+    /// Drop the field `key`
+    pub fn remove(&mut self, key: &str) {
+        if let Some(i) = self.position(key) {
+            self.entries.remove(i);
+        }
+        self.fields.remove(key);
+    }
+
+    fn put(&mut self, key: &str, mut text: String) {
+        if !text.ends_with('\n') {
+            text.push('\n');
+        }
+        self.fields.insert(Value::from(key), lenient_value(&text));
+        match self.position(key) {
+            Some(i) => self.entries[i].text = text,
+            None => {
+                // Keep the block's last line terminated before adding to it
+                if let Some(last) = self.entries.last_mut() {
+                    if !last.text.ends_with('\n') {
+                        last.text.push('\n');
+                    }
+                }
+                self.entries.push(Entry {
+                    key: Some(key.to_string()),
+                    text,
+                });
+            }
+        }
+    }
+
+    /// The block's text (without fences), ending in a newline
+    pub fn render(&self) -> String {
+        let mut text: String = self.entries.iter().map(|e| e.text.as_str()).collect();
+        if !text.is_empty() && !text.ends_with('\n') {
+            text.push('\n');
+        }
+        text
+    }
+}
+
+/// The key of a top-level `key: value` line, or `None` for indented, list,
+/// comment and blank lines, which continue the field above
+fn top_level_key(line: &str) -> Option<String> {
+    if line.starts_with(char::is_whitespace) || line.starts_with(['#', '-']) {
+        return None;
+    }
+    let (key, _) = line.split_once(':')?;
+    let key = key.trim();
+    let key = key
+        .strip_prefix('"')
+        .and_then(|k| k.strip_suffix('"'))
+        .or_else(|| key.strip_prefix('\'').and_then(|k| k.strip_suffix('\'')))
+        .unwrap_or(key);
+    Some(key.to_string())
+}
+
+/// The value of a single `key: value` field: valid YAML if possible,
+/// otherwise the raw text after the key with surrounding quotes removed
+fn lenient_value(field: &str) -> Value {
+    if let Ok(Value::Mapping(map)) = serde_yaml::from_str::<Value>(field) {
+        if let Some((_, value)) = map.into_iter().next() {
+            return value;
+        }
+    }
+    let raw = field.split_once(':').map_or("", |(_, v)| v).trim();
+    let unquoted = raw
+        .strip_prefix('"')
+        .and_then(|r| r.strip_suffix('"'))
+        .or_else(|| raw.strip_prefix('\'').and_then(|r| r.strip_suffix('\'')))
+        .unwrap_or(raw);
+    if unquoted.is_empty() {
+        Value::Null
+    } else {
+        Value::String(unquoted.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_needs_fence_lines() {
+        assert_eq!(split("# Title\n"), None);
+        assert_eq!(
+            split("---\nname: x\n---\nBody\n"),
+            Some(("name: x\n", Some("Body\n")))
+        );
+        assert_eq!(split("---\r\nname: x\r\n---\r\nBody"), Some(("name: x\r\n", Some("Body"))));
+        assert_eq!(split("---\nname: x\n"), Some(("name: x\n", None)));
+        assert_eq!(split("---\n---\n"), Some(("", Some(""))));
+    }
+
+    #[test]
+    fn test_description_containing_dashes() {
+        let content = "---\nname: dash\ndescription: \"before --- after\"\n---\n# Body\n\n---\n";
+        let doc = Document::parse(content);
+        let fm = doc.frontmatter.as_ref().unwrap();
+        assert_eq!(fm.get("description").unwrap(), "before --- after");
+        assert_eq!(doc.body, "# Body\n\n---\n");
+        assert_eq!(doc.render(), content);
+
+        // A plain value with dashes is still one field
+        let fm = Frontmatter::parse("description: a --- b\nname: x\n");
+        assert_eq!(fm.get("description").unwrap(), "a --- b");
+        assert_eq!(fm.get("name").unwrap(), "x");
+    }
+
+    #[test]
+    fn test_list_and_block_values() {
+        let fm = Frontmatter::parse(
+            "tools: [Read, Grep]\nskills:\n- one\n- two\ndescription: |\n  Line one\n  Line two\n",
+        );
+        let tools = fm.get("tools").unwrap().as_sequence().unwrap();
+        let tools: Vec<&str> = tools.iter().map(|t| t.as_str().unwrap()).collect();
+        assert_eq!(tools, ["Read", "Grep"]);
+        assert_eq!(fm.get("skills").unwrap().as_sequence().unwrap().len(), 2);
+        assert_eq!(fm.get("description").unwrap(), "Line one\nLine two\n");
+        assert!(!fm.contains("Line one"));
+    }
+
+    #[test]
+    fn test_invalid_yaml_is_read_field_by_field() {
+        let fm = Frontmatter::parse(
+            "name: helper\ndescription: Use this when: you need help\ntools: Read, Grep\n",
+        );
+        assert_eq!(fm.get("name").unwrap(), "helper");
+        assert_eq!(fm.get("description").unwrap(), "Use this when: you need help");
+        assert_eq!(fm.get("tools").unwrap(), "Read, Grep");
+    }
+
+    #[test]
+    fn test_edits_keep_other_fields_as_written() {
+        let mut doc = Document::parse("---\n# comment\nname:   spaced\ntools: Read\n---\nBody");
+        let fm = doc.frontmatter_mut();
+        fm.set("model", "sonnet");
+        fm.prepend("id", "1");
+        fm.rename("tools", "allowed-tools");
+        fm.set("name", "tidy");
+        assert_eq!(
+            doc.render(),
+            "---\nid: 1\n# comment\nname: tidy\nallowed-tools: Read\nmodel: sonnet\n---\nBody"
+        );
+
+        let mut doc = Document::parse("Body\n");
+        doc.frontmatter_mut().set("model", "sonnet");
+        assert_eq!(doc.render(), "---\nmodel: sonnet\n---\nBody\n");
+    }
+
+    #[test]
+    fn test_parse_strict_needs_a_mapping() {
+        assert!(parse_strict("---\n- a\n---\n").unwrap_err().contains("key: value"));
+        let fields = parse_strict("---\n---\n").unwrap().unwrap();
+        assert!(fields.is_empty());
+    }
+}
@@ -0,0 +1,202 @@
+use git2::{Cred, CredentialType, FetchOptions, RemoteCallbacks};
+use std::cell::RefCell;
+use std::path::PathBuf;
+
+/// Environment variables holding a token for HTTPS sources, in priority order
+const TOKEN_VARS: [&str; 2] = ["SKM_GIT_TOKEN", "GIT_TOKEN"];
+
+/// SSH keys tried when the agent has none that work, in `~/.ssh`
+const SSH_KEY_FILES: [&str; 2] = ["id_ed25519", "id_rsa"];
+
+/// Credentials for private git sources: the SSH agent, then keys in `~/.ssh`,
+/// then a token from the environment for HTTPS. libgit2 asks again after each
+/// rejected credential, so every method is offered once per clone or fetch.
+pub struct GitCredentials {
+    ssh_keys: Vec<PathBuf>,
+    token: Option<String>,
+    tried: RefCell<Tried>,
+}
+
+#[derive(Default)]
+struct Tried {
+    calls: usize,
+    username: bool,
+    agent: bool,
+    keys: usize,
+    token: bool,
+}
+
+impl GitCredentials {
+    pub fn new(ssh_keys: Vec<PathBuf>, token: Option<String>) -> Self {
+        GitCredentials {
+            ssh_keys,
+            token,
+            tried: RefCell::default(),
+        }
+    }
+
+    /// Keys that exist in `~/.ssh` and the first token variable that is set
+    pub fn from_env() -> Self {
+        let ssh_dir = std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".ssh"));
+        let ssh_keys = ssh_dir
+            .map(|dir| {
+                SSH_KEY_FILES
+                    .iter()
+                    .map(|file| dir.join(file))
+                    .filter(|key| key.is_file())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let token = TOKEN_VARS
+            .iter()
+            .find_map(|var| std::env::var(var).ok().filter(|t| !t.is_empty()));
+        Self::new(ssh_keys, token)
+    }
+
+    /// Fetch options whose credentials callback draws on these credentials
+    pub fn fetch_options(&self) -> FetchOptions<'_> {
+        let mut callbacks = RemoteCallbacks::new();
+        callbacks.credentials(|url, username, allowed| self.next(url, username, allowed));
+        let mut options = FetchOptions::new();
+        options.remote_callbacks(callbacks);
+        options
+    }
+
+    /// How often the remote asked for credentials
+    #[cfg(test)]
+    pub fn calls(&self) -> usize {
+        self.tried.borrow().calls
+    }
+
+    /// The next credential to offer, or an error once every method was tried
+    fn next(
+        &self,
+        url: &str,
+        username: Option<&str>,
+        allowed: CredentialType,
+    ) -> Result<Cred, git2::Error> {
+        let mut tried = self.tried.borrow_mut();
+        tried.calls += 1;
+
+        // SSH URLs without a user first ask for one
+        if allowed.contains(CredentialType::USERNAME) && !tried.username {
+            tried.username = true;
+            return Cred::username(username.unwrap_or("git"));
+        }
+
+        if allowed.contains(CredentialType::SSH_KEY) {
+            let user = username.unwrap_or("git");
+            if !tried.agent {
+                tried.agent = true;
+                if let Ok(cred) = Cred::ssh_key_from_agent(user) {
+                    return Ok(cred);
+                }
+            }
+            while let Some(key) = self.ssh_keys.get(tried.keys) {
+                tried.keys += 1;
+                if let Ok(cred) = Cred::ssh_key(user, None, key, None) {
+                    return Ok(cred);
+                }
+            }
+        }
+
+        if allowed.contains(CredentialType::USER_PASS_PLAINTEXT) && !tried.token {
+            tried.token = true;
+            if let Some(token) = &self.token {
+                // GitHub and GitLab take a token as the password for any user name
+                return Cred::userpass_plaintext(username.unwrap_or("x-access-token"), token);
+            }
+        }
+
+        Err(git2::Error::from_str(&self.exhausted(url, allowed)))
+    }
+
+    /// Error for when every credential was tried or none applies
+    fn exhausted(&self, url: &str, allowed: CredentialType) -> String {
+        let mut tried = Vec::new();
+        if allowed.contains(CredentialType::SSH_KEY) {
+            tried.push("the SSH agent".to_string());
+            tried.extend(self.ssh_keys.iter().map(|k| k.display().to_string()));
+        }
+        if allowed.contains(CredentialType::USER_PASS_PLAINTEXT) && self.token.is_some() {
+            tried.push("the token from SKM_GIT_TOKEN/GIT_TOKEN".to_string());
+        }
+        let tried = if tried.is_empty() {
+            "no credentials were available".to_string()
+        } else {
+            format!("tried {}", tried.join(", "))
+        };
+        format!(
+            "authentication failed for {} ({}); load a key into ssh-agent, \
+             or set SKM_GIT_TOKEN or GIT_TOKEN for HTTPS",
+            url, tried
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+
+    /// Serve `401 Unauthorized` to every request, counting the requests that
+    /// carried credentials
+    fn serve_unauthorized() -> (String, std::thread::JoinHandle<usize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/private/skills.git", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let mut with_auth = 0;
+            // One request without credentials, then one per offered token
+            for _ in 0..2 {
+                let Ok((mut stream, _)) = listener.accept() else {
+                    break;
+                };
+                let mut request = [0u8; 4096];
+                let read = stream.read(&mut request).unwrap_or(0);
+                let request = String::from_utf8_lossy(&request[..read]).to_lowercase();
+                if request.contains("authorization: basic") {
+                    with_auth += 1;
+                }
+                let _ = stream.write_all(
+                    b"HTTP/1.1 401 Unauthorized\r\n\
+                      WWW-Authenticate: Basic realm=\"skills\"\r\n\
+                      Content-Length: 0\r\nConnection: close\r\n\r\n",
+                );
+            }
+            with_auth
+        });
+        (url, server)
+    }
+
+    #[test]
+    fn test_credentials_callback_offers_token_once() {
+        let (url, server) = serve_unauthorized();
+        let dir = tempfile::tempdir().unwrap();
+        let creds = GitCredentials::new(vec![], Some("secret".to_string()));
+
+        let mut builder = git2::build::RepoBuilder::new();
+        builder.fetch_options(creds.fetch_options());
+        let Err(err) = builder.clone(&url, &dir.path().join("clone")) else {
+            panic!("clone without accepted credentials succeeded");
+        };
+
+        assert_eq!(creds.calls(), 2, "asked once for the token, once more after it failed");
+        assert_eq!(server.join().unwrap(), 1);
+        let message = err.message();
+        assert!(message.contains("authentication failed"), "{}", message);
+        assert!(message.contains("SKM_GIT_TOKEN"), "{}", message);
+    }
+
+    #[test]
+    fn test_no_credentials_names_what_to_set() {
+        let creds = GitCredentials::new(vec![], None);
+        let err = creds
+            .next("https://example.com/r.git", None, CredentialType::USER_PASS_PLAINTEXT)
+            .err()
+            .unwrap();
+        assert!(err.message().contains("no credentials were available"));
+        assert!(err.message().contains("GIT_TOKEN"));
+        assert_eq!(creds.calls(), 1);
+    }
+}
@@ -1,16 +1,183 @@
 use anyhow::Result;
 use colored::Colorize;
-use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
 
-use crate::bundle::SkillType;
+use crate::bundle::{Bundle, SkillType};
 use crate::config::Config;
-use crate::source::Source;
-use crate::target::Tool;
+use crate::install_manifest::InstallManifest;
+use crate::source::{LocalSource, Source};
+use crate::target::{
+    fnv1a_hash, DestTemplates, Output, Tool, WriteOptions, WriteStats, WrittenFiles,
+};
 
 /// Record of a bundle that was installed, for manifest tracking.
+#[derive(Debug)]
 pub struct InstallRecord {
     pub bundle_name: String,
     pub source_display: String,
+    /// Git commit the files came from, for git sources
+    pub commit: Option<String>,
+    /// The bundle's declared version
+    pub version: Option<String>,
+    /// Files written for the bundle
+    pub files: Vec<PathBuf>,
+}
+
+/// Options shared by all install entry points.
+#[derive(Debug, Clone, Default)]
+pub struct InstallOptions {
+    /// Install the single selected item under this name instead of `{bundle}-{name}`
+    pub as_name: Option<String>,
+    /// Overwrite existing files with different content, and bundles installed
+    /// from a different source
+    pub force: bool,
+    /// Install the bundle under this local name (set when installing through an alias)
+    pub bundle_alias: Option<String>,
+    /// Configured `transform_hook`, present only when `--allow-exec` was given
+    pub hook: Option<PathBuf>,
+    /// Install every selected bundle's files under this one bundle name
+    pub into_bundle: Option<String>,
+    /// Skip the install unless the tool's directory already exists in the target
+    pub if_tool_present: bool,
+    /// When installing a whole source, skip bundles the install manifest already lists
+    pub only_new: bool,
+    /// When installing a whole source, remove installed bundles it no longer has
+    pub prune: bool,
+    /// When installing a whole source, only install bundles changed since this
+    /// revision or date (see `Source::changed_since`)
+    pub since: Option<String>,
+    /// Don't ask before removing anything (`--yes`)
+    pub assume_yes: bool,
+    /// Keep installed files out of git via the tool directory's `.gitignore`
+    pub gitignore: bool,
+    /// Override the `model:` of installed agents
+    pub model: Option<String>,
+    /// Leave out documentation companions (README, CHANGELOG, ...)
+    pub no_docs: bool,
+    /// Only work out what would be written (see `InstallPlan::build`)
+    pub dry_run: bool,
+    /// Print the dry-run plan as JSON
+    pub plan_json: bool,
+    /// Collects the files written, for the dry-run plan
+    pub written: WrittenFiles,
+    /// Where files are written; buffered in memory for dry runs
+    pub output: Output,
+    /// Print a summary of what was written after the install
+    pub stats_after: bool,
+    /// Collects the files written, for `--stats-after`
+    pub stats: WriteStats,
+    /// Configured `[destinations]` templates
+    pub destinations: DestTemplates,
+    /// Configured `install_prefix`, put before the bundle name in install paths
+    pub install_prefix: Option<String>,
+}
+
+impl InstallOptions {
+    pub fn write_options(&self) -> WriteOptions {
+        WriteOptions {
+            name_override: self.as_name.clone(),
+            hook: self.hook.clone(),
+            gitignore: self.gitignore,
+            model: self.model.clone(),
+            no_docs: self.no_docs,
+            overwrite: self.force,
+            written: self.written.clone(),
+            stats: self.stats.clone(),
+            destinations: self.destinations.clone(),
+            install_prefix: self.install_prefix.clone(),
+            output: if self.dry_run && !self.output.is_dry_run() {
+                Output::dry_run()
+            } else {
+                self.output.clone()
+            },
+            ..Default::default()
+        }
+    }
+
+    /// Print an install progress line; silent in dry runs, which print the plan instead
+    fn say(&self, line: impl Display) {
+        if !self.dry_run {
+            println!("{}", line);
+        }
+    }
+
+    /// `--as` only makes sense when exactly one file is being installed
+    fn check_single_item(&self, bundle: &Bundle, types: &[SkillType]) -> Result<()> {
+        if let Some(as_name) = &self.as_name {
+            let count: usize = types.iter().map(|t| bundle.files_of_type(*t).len()).sum();
+            if count != 1 {
+                anyhow::bail!(
+                    "--as {} requires a single item, but bundle '{}' has {} matching files \
+                     (narrow it with --skills/--agents/--commands/--rules)",
+                    as_name,
+                    bundle.name,
+                    count
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Summarize the files written since `mark`, if any were kept because
+    /// they had been changed locally
+    fn report_skipped(&self, write_opts: &WriteOptions, mark: usize, target_dir: &Path) {
+        let skipped = write_opts.skipped.paths();
+        if skipped.is_empty() {
+            return;
+        }
+        for path in &skipped {
+            let path = path.strip_prefix(target_dir).unwrap_or(path);
+            self.say(format_args!("  {} {}", "Kept local changes:".yellow(), path.display()));
+        }
+        let total = write_opts.written.destinations_since(mark).len();
+        self.say(format_args!(
+            "{} written, {} skipped (use --force to overwrite)",
+            total.saturating_sub(skipped.len()),
+            skipped.len()
+        ));
+    }
+
+    /// With `--if-tool-present`, report and skip installs into projects
+    /// that don't use the tool yet
+    fn skip_absent_tool(&self, tool: &Tool, target_dir: &Path) -> bool {
+        if !self.if_tool_present || tool.is_present(target_dir) {
+            return false;
+        }
+        self.say(format_args!(
+            "{} {} not found in {}, skipping install (--if-tool-present)",
+            "Note:".yellow(),
+            tool.tool_dir_name(),
+            target_dir.display()
+        ));
+        true
+    }
+
+    /// Describe a source change if the bundle was previously installed from
+    /// somewhere else. Returns `None` when the sources match or `--force` is set.
+    fn source_conflict(
+        &self,
+        bundle_name: &str,
+        source_display: &str,
+        tool: &Tool,
+        target_dir: &Path,
+    ) -> Option<String> {
+        if self.force {
+            return None;
+        }
+        let manifest = InstallManifest::load(tool, target_dir);
+        let previous = manifest.source_of(bundle_name)?;
+        if previous == source_display {
+            return None;
+        }
+        Some(format!(
+            "bundle '{}' was installed from {} but is now coming from {}; \
+             re-run with --force to overwrite its files",
+            bundle_name, previous, source_display
+        ))
+    }
 }
 
 /// Install a bundle to the target directory
@@ -18,11 +185,15 @@ pub fn install_bundle(
     config: &Config,
     bundle_name: &str,
     tool: &Tool,
-    target_dir: &PathBuf,
+    target_dir: &Path,
     types: &[SkillType],
+    opts: &InstallOptions,
 ) -> Result<Vec<InstallRecord>> {
     // Find the bundle in configured sources
     let (source, bundle) = config.find_bundle(bundle_name)?.ok_or_else(|| {
+        if let Err(blocked) = config.check_bundle_allowed(bundle_name) {
+            return blocked;
+        }
         // Collect available bundle names for the error message
         let mut available = vec![];
         for src in config.sources() {
@@ -43,140 +214,618 @@ pub fn install_bundle(
         )
     })?;
 
-    let source_display = source.display_path();
-
-    println!(
-        "Importing from {} to {}...",
-        bundle_name.cyan(),
-        tool.name()
-    );
-
-    let mut total_count = 0;
-
-    for skill_type in types {
-        let files = bundle.files_of_type(*skill_type);
-
-        if files.is_empty() {
+    // Dependencies install under their own names, whatever --as/--into-bundle say
+    let dep_opts = InstallOptions {
+        as_name: None,
+        bundle_alias: None,
+        into_bundle: None,
+        ..opts.clone()
+    };
+    let installed = InstallManifest::load(tool, target_dir);
+    let mut records = Vec::new();
+    for (dep_source, dep) in resolve_requires(config, &bundle)? {
+        if installed.source_of(&dep.name).is_some() {
             continue;
         }
+        opts.say(format_args!(
+            "{} {} (required by {})",
+            "Requires".cyan(),
+            dep.name,
+            bundle.name
+        ));
+        records.extend(install_single_bundle(
+            dep,
+            dep_source.as_ref(),
+            tool,
+            target_dir,
+            types,
+            &dep_opts,
+        )?);
+    }
 
-        let mut count = 0;
+    records.extend(install_single_bundle(bundle, source.as_ref(), tool, target_dir, types, opts)?);
+    Ok(records)
+}
 
-        for file in files {
-            tool.write_file(target_dir, &bundle.name, file)?;
-            count += 1;
-        }
+/// Bundles `bundle` requires, directly or through other dependencies, in the
+/// order they need installing. Dependencies no source provides are warned about
+/// and left out; a cycle is an error.
+fn resolve_requires(
+    config: &Config,
+    bundle: &Bundle,
+) -> Result<Vec<(Box<dyn Source>, Bundle)>> {
+    let mut order = Vec::new();
+    let mut chain = vec![bundle.name.clone()];
+    visit_requires(config, bundle, &mut chain, &mut order)?;
+    Ok(order)
+}
 
-        if count > 0 {
-            let dest_info = tool.dest_info(*skill_type, &bundle.name);
-            println!(
-                "  {}: {} files -> {}",
-                skill_type.dir_name(),
-                count,
-                dest_info.dimmed()
-            );
-            total_count += count;
+fn visit_requires(
+    config: &Config,
+    bundle: &Bundle,
+    chain: &mut Vec<String>,
+    order: &mut Vec<(Box<dyn Source>, Bundle)>,
+) -> Result<()> {
+    for name in &bundle.meta.requires {
+        if let Some(start) = chain.iter().position(|n| n == name) {
+            let mut cycle = chain[start..].to_vec();
+            cycle.push(name.clone());
+            anyhow::bail!("Bundle dependency cycle: {}", cycle.join(" -> "));
         }
+        if order.iter().any(|(_, b)| &b.name == name) {
+            continue;
+        }
+        let Some((source, dep)) = config.find_bundle(name)? else {
+            eprintln!(
+                "  {}: bundle '{}' requires '{}', which no configured source provides",
+                "Warning".yellow(),
+                bundle.name,
+                name
+            );
+            continue;
+        };
+        chain.push(name.clone());
+        visit_requires(config, &dep, chain, order)?;
+        chain.pop();
+        order.push((source, dep));
     }
-
-    if total_count == 0 {
-        println!("{}", "No files to import.".yellow());
-    } else {
-        println!("{}", "Done!".green());
-    }
-
-    Ok(vec![InstallRecord {
-        bundle_name: bundle.name,
-        source_display,
-    }])
+    Ok(())
 }
 
 /// Install all bundles from a named source
 pub fn install_from_source(
     source: &dyn Source,
     tool: &Tool,
-    target_dir: &PathBuf,
+    target_dir: &Path,
     types: &[SkillType],
+    opts: &InstallOptions,
 ) -> Result<Vec<InstallRecord>> {
-    let bundles = source.list_bundles()?;
+    if opts.as_name.is_some() {
+        anyhow::bail!("--as cannot be used when installing every bundle from a source");
+    }
+    if let Some(alias) = &opts.bundle_alias {
+        anyhow::bail!(
+            "Alias '{}' points at a whole source; aliases must name a single bundle (source/bundle)",
+            alias
+        );
+    }
+    if opts.skip_absent_tool(tool, target_dir) {
+        return Ok(vec![]);
+    }
+
+    let mut bundles = source.list_bundles()?;
 
     if bundles.is_empty() {
-        println!("{}", "No bundles found in source.".yellow());
+        opts.say("No bundles found in source.".yellow());
         return Ok(vec![]);
     }
 
+    if let Some(since) = &opts.since {
+        let changed = source.changed_since(since)?.ok_or_else(|| {
+            anyhow::anyhow!(
+                "--since needs a git source; {} has no history",
+                source.display_path()
+            )
+        })?;
+        bundles.retain(|b| changed.contains(&b.name));
+        if bundles.is_empty() {
+            opts.say(format!("No bundles changed since {}.", since).yellow());
+            return Ok(vec![]);
+        }
+    }
+
     let source_display = source.display_path();
 
-    println!(
+    opts.say(format_args!(
         "Installing {} bundle(s) from {} to {}...",
         bundles.len(),
         source_display.cyan(),
         tool.name()
-    );
-    println!();
+    ));
+    opts.say("");
 
     let mut total_files = 0;
-    let mut records = Vec::new();
+    let mut records: Vec<InstallRecord> = Vec::new();
+    let commit = source.revision();
+    let write_opts = opts.write_options();
+    let first_mark = write_opts.written.mark();
+    let installed = InstallManifest::load(tool, target_dir);
+    let mut skipped_existing = 0;
+    let available: Vec<SkillType> = SkillType::ALL
+        .into_iter()
+        .filter(|t| bundles.iter().any(|b| !b.files_of_type(*t).is_empty()))
+        .collect();
 
-    for bundle in bundles {
-        let mut bundle_files = 0;
-
-        for skill_type in types {
-            let files = bundle.files_of_type(*skill_type);
-
-            for file in files {
-                tool.write_file(target_dir, &bundle.name, file)?;
-                bundle_files += 1;
-            }
+    for mut bundle in bundles {
+        if let Some(into) = &opts.into_bundle {
+            bundle.name = into.clone();
+        }
+        if opts.only_new && installed.source_of(&bundle.name).is_some() {
+            skipped_existing += 1;
+            continue;
         }
+        if let Some(conflict) =
+            opts.source_conflict(&bundle.name, &source_display, tool, target_dir)
+        {
+            opts.say(format_args!("  {} {}", "Skipping:".yellow(), conflict));
+            continue;
+        }
+
+        let mark = write_opts.written.mark();
+        let bundle_files =
+            write_bundle_files(&bundle, tool, target_dir, types, &write_opts, |_, _| {})?;
 
         if bundle_files > 0 {
-            println!("  {} {} file(s)", bundle.name.cyan(), bundle_files);
+            opts.say(format_args!("  {} {} file(s)", bundle.name.cyan(), bundle_files));
             total_files += bundle_files;
+            let files = write_opts.written.destinations_since(mark);
+            if let Some(record) = records.iter_mut().find(|r| r.bundle_name == bundle.name) {
+                record.files.extend(files);
+                continue;
+            }
             records.push(InstallRecord {
                 bundle_name: bundle.name,
                 source_display: source_display.clone(),
+                commit: commit.clone(),
+                version: bundle.meta.version,
+                files,
             });
         }
     }
+    record_installs(tool, target_dir, &records, opts);
 
     if total_files == 0 {
-        println!("{}", "No files to import.".yellow());
+        let what = format!("source {}", source_display);
+        opts.say(no_files_message(&what, &available, types).yellow());
     } else {
-        println!();
-        println!("{} {} file(s) installed.", "Done!".green(), total_files);
+        opts.say("");
+        opts.say(format_args!("{} {} file(s) installed.", "Done!".green(), total_files));
+    }
+    opts.report_skipped(&write_opts, first_mark, target_dir);
+    if skipped_existing > 0 {
+        opts.say(format_args!(
+            "{} {} already-installed bundle(s) (--only-new)",
+            "Skipped".dimmed(),
+            skipped_existing
+        ));
     }
+    write_opts.unknown_tools.report();
+    write_opts.placeholder_descriptions.report();
 
     Ok(records)
 }
 
+/// Install ad hoc from a directory that isn't a configured source: the
+/// directory itself when it is a bundle, otherwise every bundle inside it
+pub fn install_from_path(
+    config: &Config,
+    path: &Path,
+    tool: &Tool,
+    target_dir: &Path,
+    types: &[SkillType],
+    opts: &InstallOptions,
+) -> Result<Vec<InstallRecord>> {
+    let path = std::fs::canonicalize(path)?;
+    let bundle = Bundle::from_path(path.clone())?;
+    if bundle.is_empty() {
+        let source = config.filtered(Box::new(LocalSource::new(path)));
+        return install_from_source(source.as_ref(), tool, target_dir, types, opts);
+    }
+    config.check_bundle_allowed(&bundle.name)?;
+    let parent = path.parent().unwrap_or(&path).to_path_buf();
+    install_single_bundle(bundle, &LocalSource::new(parent), tool, target_dir, types, opts)
+}
+
 /// Install a specific bundle from a specific source
 pub fn install_bundle_from_source(
     source: &dyn Source,
     bundle_name: &str,
     tool: &Tool,
-    target_dir: &PathBuf,
+    target_dir: &Path,
     types: &[SkillType],
+    opts: &InstallOptions,
 ) -> Result<Vec<InstallRecord>> {
     let bundles = source.list_bundles()?;
 
     let source_display = source.display_path();
 
-    let bundle = bundles.into_iter().find(|b| b.name == bundle_name).ok_or_else(|| {
-        anyhow::anyhow!(
-            "Bundle '{}' not found in source '{}'",
-            bundle_name,
-            source_display
-        )
-    })?;
+    let bundle = bundles
+        .into_iter()
+        .find(|b| b.name == bundle_name)
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "Bundle '{}' not found in source '{}'",
+                bundle_name,
+                source_display
+            )
+        })?;
+
+    install_single_bundle(bundle, source, tool, target_dir, types, opts)
+}
+
+/// Install one resolved bundle, printing a per-type summary
+fn install_single_bundle(
+    mut bundle: Bundle,
+    source: &dyn Source,
+    tool: &Tool,
+    target_dir: &Path,
+    types: &[SkillType],
+    opts: &InstallOptions,
+) -> Result<Vec<InstallRecord>> {
+    let source_display = source.display_path();
 
-    println!(
+    opts.check_single_item(&bundle, types)?;
+    if opts.skip_absent_tool(tool, target_dir) {
+        return Ok(vec![]);
+    }
+    if let Some(name) = opts.bundle_alias.as_ref().or(opts.into_bundle.as_ref()) {
+        bundle.name = name.clone();
+    }
+    if let Some(conflict) = opts.source_conflict(&bundle.name, &source_display, tool, target_dir) {
+        anyhow::bail!("Source changed: {}", conflict);
+    }
+
+    opts.say(format_args!(
         "Importing from {} to {}...",
-        bundle_name.cyan(),
+        bundle.name.cyan(),
         tool.name()
-    );
+    ));
 
-    let mut total_count = 0;
+    let write_opts = opts.write_options();
+    let mark = write_opts.written.mark();
+    let total_count = write_bundle_files(
+        &bundle,
+        tool,
+        target_dir,
+        types,
+        &write_opts,
+        |skill_type, count| {
+            let install_bundle = write_opts.install_bundle_name(&bundle.name);
+            let dest_info = tool.dest_info(skill_type, &install_bundle, &write_opts.destinations);
+            opts.say(format_args!(
+                "  {}: {} files -> {}",
+                skill_type.dir_name(),
+                count,
+                dest_info.dimmed()
+            ));
+        },
+    )?;
+
+    if total_count == 0 {
+        opts.say(no_files_message(&bundle.name, &bundle.types(), types).yellow());
+    } else {
+        opts.say("Done!".green());
+    }
+    opts.report_skipped(&write_opts, mark, target_dir);
+    write_opts.unknown_tools.report();
+    write_opts.placeholder_descriptions.report();
+
+    let records = vec![InstallRecord {
+        bundle_name: bundle.name,
+        source_display,
+        commit: source.revision(),
+        version: bundle.meta.version,
+        files: write_opts.written.destinations_since(mark),
+    }];
+    record_installs(tool, target_dir, &records, opts);
+
+    Ok(records)
+}
+
+/// Say why an install wrote nothing: `what` (a bundle, or a source's bundles)
+/// only has `available` types, none of which are among the selected `types`
+fn no_files_message(what: &str, available: &[SkillType], types: &[SkillType]) -> String {
+    let names = |types: &[SkillType]| {
+        types.iter().map(|t| t.dir_name()).collect::<Vec<_>>().join(", ")
+    };
+    if available.is_empty() {
+        return format!("No files to import: {} has no skills, agents, commands or rules.", what);
+    }
+    if available.iter().any(|t| types.contains(t)) {
+        // Files of the selected types were there but skipped for another reason
+        return "No files to import.".to_string();
+    }
+    let flags: Vec<String> = available.iter().map(|t| format!("--{}", t.dir_name())).collect();
+    format!(
+        "No files to import: {} has {}, but only {} were selected (use {} to install them).",
+        what,
+        names(available),
+        names(types),
+        flags.join(" or ")
+    )
+}
+
+/// Record installed bundles and their files in the tool's install manifest
+fn record_installs(
+    tool: &Tool,
+    target_dir: &Path,
+    records: &[InstallRecord],
+    opts: &InstallOptions,
+) {
+    if records.is_empty() || opts.dry_run {
+        return;
+    }
+    let mut manifest = InstallManifest::load(tool, target_dir);
+    for rec in records {
+        manifest.record_install(&rec.bundle_name, &rec.source_display, rec.commit.as_deref());
+        manifest.record_version(&rec.bundle_name, rec.version.as_deref());
+        let files: Vec<PathBuf> = rec
+            .files
+            .iter()
+            .map(|f| f.strip_prefix(target_dir).unwrap_or(f).to_path_buf())
+            .collect();
+        manifest.record_files(&rec.bundle_name, &files);
+    }
+    if let Err(e) = manifest.save(tool, target_dir) {
+        eprintln!("Warning: could not save install manifest: {}", e);
+    }
+}
+
+/// Run `install` with every write buffered in memory and return the content
+/// each destination would get. Locally edited files are rendered too, as with
+/// `--force`, so the result is what the source would install.
+pub fn render_install(
+    opts: &InstallOptions,
+    install: impl FnOnce(&InstallOptions) -> Result<Vec<InstallRecord>>,
+) -> Result<Vec<(PathBuf, Vec<u8>)>> {
+    let rendered_opts = InstallOptions {
+        dry_run: true,
+        force: true,
+        written: WrittenFiles::default(),
+        output: Output::dry_run(),
+        ..opts.clone()
+    };
+    install(&rendered_opts)?;
+
+    let mut files: Vec<(PathBuf, Vec<u8>)> = Vec::new();
+    for (_, dest) in rendered_opts.written.pairs() {
+        if let Some(content) = rendered_opts.output.buffered(&dest) {
+            // A later write to the same destination replaces the earlier one
+            files.retain(|(d, _)| *d != dest);
+            files.push((dest, content));
+        }
+    }
+    Ok(files)
+}
+
+/// Files an install would write, worked out by `--dry-run`
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct InstallPlan {
+    /// Tool id (claude, opencode, cursor, codex, windsurf)
+    pub tool: String,
+    pub target: PathBuf,
+    pub bundles: Vec<PlannedBundle>,
+    pub files: Vec<PlannedFile>,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct PlannedBundle {
+    pub bundle: String,
+    pub source: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct PlannedFile {
+    pub src: PathBuf,
+    pub dest: PathBuf,
+    pub action: PlanAction,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum PlanAction {
+    Create,
+    Overwrite,
+    /// The destination already has exactly this content
+    Unchanged,
+    /// The destination was changed locally and is kept (no `--force`)
+    Skip,
+}
+
+impl PlanAction {
+    fn as_str(&self) -> &'static str {
+        match self {
+            PlanAction::Create => "create",
+            PlanAction::Overwrite => "overwrite",
+            PlanAction::Unchanged => "unchanged",
+            PlanAction::Skip => "skip",
+        }
+    }
+}
+
+impl InstallPlan {
+    /// Run `install` with every write buffered in memory and describe what it
+    /// would have written, leaving `target_dir` untouched
+    pub fn build(
+        tool: &Tool,
+        target_dir: &Path,
+        opts: &InstallOptions,
+        install: impl FnOnce(&InstallOptions) -> Result<Vec<InstallRecord>>,
+    ) -> Result<Self> {
+        let planned_opts = InstallOptions {
+            dry_run: true,
+            written: WrittenFiles::default(),
+            output: Output::dry_run(),
+            ..opts.clone()
+        };
+        let records = install(&planned_opts)?;
+
+        let mut files: Vec<PlannedFile> = Vec::new();
+        for (src, dest) in planned_opts.written.pairs() {
+            let buffered = planned_opts.output.buffered(&dest);
+            let action = if !dest.exists() {
+                PlanAction::Create
+            } else if buffered.is_none() {
+                PlanAction::Skip
+            } else if std::fs::read(&dest).ok() == buffered {
+                PlanAction::Unchanged
+            } else {
+                PlanAction::Overwrite
+            };
+            // A later write to the same destination replaces the earlier one
+            files.retain(|f| f.dest != dest);
+            files.push(PlannedFile { src, dest, action });
+        }
+
+        Ok(InstallPlan {
+            tool: tool.id().to_string(),
+            target: target_dir.to_path_buf(),
+            bundles: records
+                .into_iter()
+                .map(|r| PlannedBundle {
+                    bundle: r.bundle_name,
+                    source: r.source_display,
+                })
+                .collect(),
+            files,
+        })
+    }
+
+    /// Print the plan for a person to read
+    pub fn print(&self) {
+        println!(
+            "{} {} file(s) for {} in {}",
+            "Dry run:".yellow(),
+            self.files.len(),
+            self.tool,
+            self.target.display()
+        );
+        for bundle in &self.bundles {
+            println!("  {} {}", bundle.bundle.cyan(), format!("from {}", bundle.source).dimmed());
+        }
+        for file in &self.files {
+            let dest = file.dest.strip_prefix(&self.target).unwrap_or(&file.dest);
+            let action = format!("{:<9}", file.action.as_str());
+            let action = match file.action {
+                PlanAction::Create => action.green(),
+                PlanAction::Overwrite => action.yellow(),
+                PlanAction::Unchanged | PlanAction::Skip => action.dimmed(),
+            };
+            println!("  {} {}", action, dest.display());
+        }
+        println!("{}", "Nothing was written.".dimmed());
+    }
+}
+
+/// Content hashes of the files under a tool's directory, taken before a
+/// refresh so rewritten-but-identical files can be told apart from changed ones
+pub struct ContentSnapshot(HashMap<PathBuf, u64>);
+
+impl ContentSnapshot {
+    pub fn take(tool: &Tool, target_dir: &Path) -> Self {
+        let hashes = walkdir::WalkDir::new(target_dir.join(tool.tool_dir_name()))
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+            .filter_map(|e| {
+                let hash = hash_file(e.path())?;
+                Some((e.into_path(), hash))
+            })
+            .collect();
+        ContentSnapshot(hashes)
+    }
+
+    /// Whether `path` now differs from (or didn't exist in) the snapshot
+    pub fn changed(&self, path: &Path) -> bool {
+        hash_file(path) != self.0.get(path).copied()
+    }
+}
+
+fn hash_file(path: &Path) -> Option<u64> {
+    std::fs::read(path).ok().map(|bytes| fnv1a_hash(&bytes))
+}
+
+/// Result of rewriting one installed bundle
+#[derive(Debug, Default)]
+pub struct RefreshOutcome {
+    pub written: usize,
+    pub errors: Vec<anyhow::Error>,
+    /// At least one written file differs from the snapshot
+    pub changed: bool,
+    /// Every file written, companions included
+    pub files: Vec<PathBuf>,
+}
+
+/// Rewrite an installed bundle's files, noting whether any content changed
+pub fn refresh_bundle(
+    bundle: &Bundle,
+    tool: &Tool,
+    target_dir: &Path,
+    types: &[SkillType],
+    write_opts: &WriteOptions,
+    snapshot: &ContentSnapshot,
+) -> RefreshOutcome {
+    // Refreshing takes the sources' current files, local edits or not
+    let write_opts = WriteOptions {
+        overwrite: true,
+        ..write_opts.clone()
+    };
+    let mut outcome = RefreshOutcome::default();
+    let mark = write_opts.written.mark();
+    for skill_type in types {
+        for file in bundle.files_of_type(*skill_type) {
+            match tool.write_file_with(target_dir, &bundle.name, file, &write_opts) {
+                Ok(dest) => {
+                    outcome.written += 1;
+                    outcome.changed |= snapshot.changed(&dest);
+                }
+                Err(e) => outcome.errors.push(e),
+            }
+        }
+    }
+    outcome.files = write_opts.written.destinations_since(mark);
+    outcome
+}
+
+/// Write every file of the selected types, calling `on_type` with each
+/// non-empty type's count. Returns the total number of files written.
+fn write_bundle_files(
+    bundle: &Bundle,
+    tool: &Tool,
+    target_dir: &Path,
+    types: &[SkillType],
+    write_opts: &WriteOptions,
+    mut on_type: impl FnMut(SkillType, usize),
+) -> Result<usize> {
+    let mut total = 0;
+
+    let all_files: Vec<_> = types.iter().flat_map(|t| bundle.files_of_type(*t)).collect();
+    for (dest, first, second) in
+        tool.dest_collisions(target_dir, &bundle.name, &all_files, write_opts)
+    {
+        eprintln!(
+            "{} {} `{}` and {} `{}` in {} both install to {} for {}; only the {} is kept",
+            "Warning:".yellow(),
+            first.skill_type.as_str(),
+            first.name,
+            second.skill_type.as_str(),
+            second.name,
+            bundle.name,
+            dest.strip_prefix(target_dir).unwrap_or(&dest).display(),
+            tool.name(),
+            second.skill_type.as_str(),
+        );
+    }
 
     for skill_type in types {
         let files = bundle.files_of_type(*skill_type);
@@ -185,35 +834,15 @@ pub fn install_bundle_from_source(
             continue;
         }
 
-        let mut count = 0;
-
         for file in files {
-            tool.write_file(target_dir, &bundle.name, file)?;
-            count += 1;
+            tool.write_file_with(target_dir, &bundle.name, file, write_opts)?;
         }
 
-        if count > 0 {
-            let dest_info = tool.dest_info(*skill_type, &bundle.name);
-            println!(
-                "  {}: {} files -> {}",
-                skill_type.dir_name(),
-                count,
-                dest_info.dimmed()
-            );
-            total_count += count;
-        }
+        on_type(*skill_type, files.len());
+        total += files.len();
     }
 
-    if total_count == 0 {
-        println!("{}", "No files to import.".yellow());
-    } else {
-        println!("{}", "Done!".green());
-    }
-
-    Ok(vec![InstallRecord {
-        bundle_name: bundle.name,
-        source_display,
-    }])
+    Ok(total)
 }
 
 #[cfg(test)]
@@ -246,6 +875,15 @@ mod tests {
         (dir, source_path)
     }
 
+    fn setup_single_skill_source() -> (tempfile::TempDir, PathBuf) {
+        let dir = tempdir().unwrap();
+        let source_path = dir.path().to_path_buf();
+        let skills_dir = source_path.join("solo/skills");
+        fs::create_dir_all(&skills_dir).unwrap();
+        fs::write(skills_dir.join("helper.md"), "# Helper skill").unwrap();
+        (dir, source_path)
+    }
+
     #[test]
     fn test_install_to_claude() {
         let (_source_dir, source_path) = setup_test_source();
@@ -258,7 +896,7 @@ mod tests {
 
         for cmd in &bundle.commands {
             Tool::Claude
-                .write_file(&target_dir.path().to_path_buf(), "test-bundle", cmd)
+                .write_file(target_dir.path(), "test-bundle", cmd)
                 .unwrap();
         }
 
@@ -283,7 +921,7 @@ mod tests {
         // Test skill (should create directory structure)
         for skill in &bundle.skills {
             Tool::OpenCode
-                .write_file(&target_dir.path().to_path_buf(), "test-bundle", skill)
+                .write_file(target_dir.path(), "test-bundle", skill)
                 .unwrap();
         }
 
@@ -296,7 +934,7 @@ mod tests {
         // Test command
         for cmd in &bundle.commands {
             Tool::OpenCode
-                .write_file(&target_dir.path().to_path_buf(), "test-bundle", cmd)
+                .write_file(target_dir.path(), "test-bundle", cmd)
                 .unwrap();
         }
 
@@ -316,7 +954,7 @@ mod tests {
         // Test skill (should go to skills directory)
         for skill in &bundle.skills {
             Tool::Cursor
-                .write_file(&target_dir.path().to_path_buf(), "test-bundle", skill)
+                .write_file(target_dir.path(), "test-bundle", skill)
                 .unwrap();
         }
 
@@ -329,7 +967,7 @@ mod tests {
         // Test agent (should go to agents directory as flat file)
         for agent in &bundle.agents {
             Tool::Cursor
-                .write_file(&target_dir.path().to_path_buf(), "test-bundle", agent)
+                .write_file(target_dir.path(), "test-bundle", agent)
                 .unwrap();
         }
 
@@ -342,7 +980,7 @@ mod tests {
         // Test command (should go to commands directory as flat file)
         for command in &bundle.commands {
             Tool::Cursor
-                .write_file(&target_dir.path().to_path_buf(), "test-bundle", command)
+                .write_file(target_dir.path(), "test-bundle", command)
                 .unwrap();
         }
 
@@ -352,4 +990,584 @@ mod tests {
             .join(".cursor/commands/test-bundle-commit.md")
             .exists());
     }
+
+    #[test]
+    fn test_install_as_overrides_combined_name() {
+        let (_source_dir, source_path) = setup_single_skill_source();
+        let target_dir = tempdir().unwrap();
+        let source = crate::source::LocalSource::new(source_path);
+
+        let opts = InstallOptions {
+            as_name: Some("myskill".to_string()),
+            ..Default::default()
+        };
+        install_bundle_from_source(
+            &source,
+            "solo",
+            &Tool::Claude,
+            target_dir.path(),
+            &[SkillType::Skill],
+            &opts,
+        )
+        .unwrap();
+
+        let skill_md = target_dir.path().join(".claude/skills/myskill/SKILL.md");
+        assert!(skill_md.exists());
+        assert!(fs::read_to_string(&skill_md)
+            .unwrap()
+            .contains("name: myskill"));
+        assert!(!target_dir
+            .path()
+            .join(".claude/skills/solo-helper")
+            .exists());
+    }
+
+    #[test]
+    fn test_install_as_rejects_multiple_items() {
+        let (_source_dir, source_path) = setup_test_source();
+        let target_dir = tempdir().unwrap();
+        let source = crate::source::LocalSource::new(source_path);
+
+        let opts = InstallOptions {
+            as_name: Some("myskill".to_string()),
+            ..Default::default()
+        };
+        let types = [
+            SkillType::Skill,
+            SkillType::Agent,
+            SkillType::Command,
+            SkillType::Rule,
+        ];
+        let err = install_bundle_from_source(
+            &source,
+            "test-bundle",
+            &Tool::Claude,
+            target_dir.path(),
+            &types,
+            &opts,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("requires a single item"));
+
+        // Nothing should have been written
+        assert!(!target_dir.path().join(".claude").exists());
+
+        let err = install_from_source(&source, &Tool::Claude, target_dir.path(), &types, &opts)
+            .unwrap_err();
+        assert!(err.to_string().contains("--as"));
+    }
+
+    #[test]
+    fn test_agents_filter_on_skills_only_bundle_explains_no_files() {
+        let source_dir = tempdir().unwrap();
+        let skill = source_dir.path().join("pdf/skills/fill");
+        fs::create_dir_all(&skill).unwrap();
+        fs::write(skill.join("SKILL.md"), "---\ndescription: Fill\n---\n").unwrap();
+        let source = crate::source::LocalSource::new(source_dir.path().to_path_buf());
+        let target_dir = tempdir().unwrap();
+
+        let types = [SkillType::Agent];
+        let opts = InstallOptions::default();
+        let records = install_bundle_from_source(
+            &source,
+            "pdf",
+            &Tool::Claude,
+            target_dir.path(),
+            &types,
+            &opts,
+        )
+        .unwrap();
+        assert!(records[0].files.is_empty());
+        assert!(!target_dir.path().join(".claude/skills").exists());
+
+        let bundle = source.list_bundles().unwrap().remove(0);
+        assert_eq!(
+            no_files_message(&bundle.name, &bundle.types(), &types),
+            "No files to import: pdf has skills, but only agents were selected \
+             (use --skills to install them)."
+        );
+        assert_eq!(
+            no_files_message("pdf", &[], &SkillType::ALL),
+            "No files to import: pdf has no skills, agents, commands or rules."
+        );
+        assert_eq!(
+            no_files_message("pdf", &[SkillType::Skill], &SkillType::ALL),
+            "No files to import."
+        );
+    }
+
+    #[test]
+    fn test_reinstall_from_different_source_warns() {
+        let (_source_dir, source_path) = setup_single_skill_source();
+        let target_dir = tempdir().unwrap();
+        let source = crate::source::LocalSource::new(source_path);
+
+        let mut manifest = InstallManifest::default();
+        manifest.record_install("solo", "~/old-skills", None);
+        manifest.save(&Tool::Claude, target_dir.path()).unwrap();
+
+        let install = |opts: &InstallOptions| {
+            install_bundle_from_source(
+                &source,
+                "solo",
+                &Tool::Claude,
+                target_dir.path(),
+                &[SkillType::Skill],
+                opts,
+            )
+        };
+
+        let err = install(&InstallOptions::default()).unwrap_err().to_string();
+        assert!(err.contains("was installed from ~/old-skills"));
+        assert!(err.contains("--force"));
+        let skill_md = target_dir
+            .path()
+            .join(".claude/skills/solo-helper/SKILL.md");
+        assert!(!skill_md.exists());
+
+        let forced = InstallOptions {
+            force: true,
+            ..Default::default()
+        };
+        let records = install(&forced).unwrap();
+        assert_eq!(records.len(), 1);
+        assert!(skill_md.exists());
+    }
+
+    /// Local source where each `(bundle, requires)` is a command bundle with a meta.yaml
+    fn source_with_requires(bundles: &[(&str, &[&str])]) -> (tempfile::TempDir, Config) {
+        let dir = tempdir().unwrap();
+        for (name, requires) in bundles {
+            let commands = dir.path().join(name).join("commands");
+            fs::create_dir_all(&commands).unwrap();
+            fs::write(commands.join("run.md"), "# Run").unwrap();
+            let meta = format!("requires: [{}]\n", requires.join(", "));
+            fs::write(dir.path().join(name).join("meta.yaml"), meta).unwrap();
+        }
+        let config = Config::new(vec![crate::config::SourceConfig::local(
+            dir.path().to_string_lossy().to_string(),
+            None,
+        )]);
+        (dir, config)
+    }
+
+    #[test]
+    fn test_install_pulls_in_required_bundles_first() {
+        let (_source, config) = source_with_requires(&[
+            ("report", &["pdf"]),
+            ("pdf", &["base", "gone"]),
+            ("base", &[]),
+        ]);
+        let target_dir = tempdir().unwrap();
+
+        let records = install_bundle(
+            &config,
+            "report",
+            &Tool::OpenCode,
+            target_dir.path(),
+            &[SkillType::Command],
+            &InstallOptions::default(),
+        )
+        .unwrap();
+
+        let order: Vec<&str> = records.iter().map(|r| r.bundle_name.as_str()).collect();
+        assert_eq!(order, vec!["base", "pdf", "report"]);
+        let commands = target_dir.path().join(".opencode/commands");
+        for bundle in ["base", "pdf", "report"] {
+            assert!(commands.join(format!("{}-run.md", bundle)).exists());
+        }
+    }
+
+    #[test]
+    fn test_install_rejects_dependency_cycles() {
+        let (_source, config) =
+            source_with_requires(&[("a", &["b"]), ("b", &["c"]), ("c", &["a"])]);
+        let target_dir = tempdir().unwrap();
+
+        let err = install_bundle(
+            &config,
+            "a",
+            &Tool::OpenCode,
+            target_dir.path(),
+            &[SkillType::Command],
+            &InstallOptions::default(),
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("a -> b -> c -> a"), "{}", err);
+        assert!(!target_dir.path().join(".opencode").exists());
+    }
+
+    #[test]
+    fn test_into_bundle_groups_skills_under_one_name() {
+        let source_dir = tempdir().unwrap();
+        for name in ["pdf", "xlsx"] {
+            let skill_dir = source_dir.path().join("skills").join(name);
+            fs::create_dir_all(&skill_dir).unwrap();
+            fs::write(
+                skill_dir.join("SKILL.md"),
+                format!("---\nname: {}\ndescription: {} tools\n---\n", name, name),
+            )
+            .unwrap();
+        }
+        let source = crate::source::LocalSource::new(source_dir.path().to_path_buf());
+        let target_dir = tempdir().unwrap();
+
+        let opts = InstallOptions {
+            into_bundle: Some("docs".to_string()),
+            ..Default::default()
+        };
+        let records = install_from_source(
+            &source,
+            &Tool::Claude,
+            target_dir.path(),
+            &[SkillType::Skill],
+            &opts,
+        )
+        .unwrap();
+
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].bundle_name, "docs");
+        let skills = target_dir.path().join(".claude/skills");
+        assert!(skills.join("docs-pdf/SKILL.md").exists());
+        assert!(skills.join("docs-xlsx/SKILL.md").exists());
+    }
+
+    #[test]
+    fn test_if_tool_present_skips_when_tool_dir_missing() {
+        let (_source_dir, source_path) = setup_single_skill_source();
+        let target_dir = tempdir().unwrap();
+        let source = crate::source::LocalSource::new(source_path);
+        let opts = InstallOptions {
+            if_tool_present: true,
+            ..Default::default()
+        };
+        let install = || {
+            install_bundle_from_source(
+                &source,
+                "solo",
+                &Tool::Cursor,
+                target_dir.path(),
+                &[SkillType::Skill],
+                &opts,
+            )
+            .unwrap()
+        };
+
+        assert!(install().is_empty());
+        assert!(!target_dir.path().join(".cursor").exists());
+
+        fs::create_dir(target_dir.path().join(".cursor")).unwrap();
+        assert_eq!(install().len(), 1);
+        assert!(target_dir
+            .path()
+            .join(".cursor/skills/solo-helper/SKILL.md")
+            .exists());
+    }
+
+    #[test]
+    fn test_install_flat_folder_of_markdown_as_commands() {
+        let source_dir = tempdir().unwrap();
+        let folder = source_dir.path().join("my-commands");
+        fs::create_dir_all(&folder).unwrap();
+        fs::write(folder.join("commit.md"), "# Commit").unwrap();
+        fs::write(folder.join("review.md"), "# Review").unwrap();
+        fs::write(folder.join("README.md"), "# About these commands").unwrap();
+        let source = crate::source::LocalSource::new(source_dir.path().to_path_buf());
+        let target_dir = tempdir().unwrap();
+
+        install_bundle_from_source(
+            &source,
+            "my-commands",
+            &Tool::Claude,
+            target_dir.path(),
+            &[SkillType::Command],
+            &InstallOptions::default(),
+        )
+        .unwrap();
+
+        let commands = target_dir.path().join(".claude/commands/my-commands");
+        assert!(commands.join("commit.md").exists());
+        assert!(commands.join("review.md").exists());
+        assert!(!commands.join("README.md").exists());
+
+        let as_agents = crate::source::LocalSource::new(source_dir.path().to_path_buf())
+            .with_loose_type(SkillType::Agent);
+        let bundles = as_agents.list_bundles().unwrap();
+        assert_eq!(bundles[0].agents.len(), 2);
+        assert!(bundles[0].commands.is_empty());
+    }
+
+    #[test]
+    fn test_refresh_reports_only_changed_bundles() {
+        let target_dir = tempdir().unwrap();
+        let mut sources = vec![];
+        for name in ["alpha", "beta"] {
+            let dir = tempdir().unwrap();
+            let commands = dir.path().join(name).join("commands");
+            fs::create_dir_all(&commands).unwrap();
+            fs::write(commands.join("run.md"), "# Run v1").unwrap();
+            let source = crate::source::LocalSource::new(dir.path().to_path_buf());
+            install_bundle_from_source(
+                &source,
+                name,
+                &Tool::Claude,
+                target_dir.path(),
+                &[SkillType::Command],
+                &InstallOptions::default(),
+            )
+            .unwrap();
+            sources.push((dir, source));
+        }
+
+        // Only alpha's source changes upstream
+        fs::write(sources[0].0.path().join("alpha/commands/run.md"), "# Run v2").unwrap();
+
+        let snapshot = ContentSnapshot::take(&Tool::Claude, target_dir.path());
+        let changed: Vec<bool> = ["alpha", "beta"]
+            .iter()
+            .zip(&sources)
+            .map(|(name, (_, source))| {
+                let bundle = source
+                    .list_bundles()
+                    .unwrap()
+                    .into_iter()
+                    .find(|b| b.name == *name)
+                    .unwrap();
+                let outcome = refresh_bundle(
+                    &bundle,
+                    &Tool::Claude,
+                    target_dir.path(),
+                    &[SkillType::Command],
+                    &WriteOptions::default(),
+                    &snapshot,
+                );
+                assert_eq!(outcome.written, 1);
+                outcome.changed
+            })
+            .collect();
+
+        assert_eq!(changed, vec![true, false]);
+    }
+
+    #[test]
+    fn test_install_records_bundle_and_files_in_manifest() {
+        let (_source_dir, source_path) = setup_test_source();
+        let target_dir = tempdir().unwrap();
+        let source = crate::source::LocalSource::new(source_path);
+
+        install_bundle_from_source(
+            &source,
+            "test-bundle",
+            &Tool::Claude,
+            target_dir.path(),
+            &[SkillType::Command],
+            &InstallOptions::default(),
+        )
+        .unwrap();
+
+        let manifest = InstallManifest::load(&Tool::Claude, target_dir.path());
+        assert_eq!(manifest.source_of("test-bundle"), Some(source.display_path().as_str()));
+        let mut files = manifest.files_of("test-bundle").to_vec();
+        files.sort();
+        assert_eq!(
+            files,
+            vec![
+                PathBuf::from(".claude/commands/test-bundle/commit.md"),
+                PathBuf::from(".claude/commands/test-bundle/debug.md"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_only_new_skips_installed_bundles() {
+        let (_source_dir, source_path) = setup_test_source();
+        let target_dir = tempdir().unwrap();
+        let source = crate::source::LocalSource::new(source_path);
+        let opts = InstallOptions {
+            only_new: true,
+            ..Default::default()
+        };
+        let install = || {
+            install_from_source(
+                &source,
+                &Tool::Claude,
+                target_dir.path(),
+                &[SkillType::Command],
+                &opts,
+            )
+            .unwrap()
+        };
+
+        assert_eq!(install().len(), 1);
+        assert!(install().is_empty());
+    }
+
+    #[test]
+    fn test_denied_bundle_is_reported_as_blocked() {
+        let (_source_dir, source_path) = setup_test_source();
+        let mut config = Config::new(vec![crate::config::SourceConfig::local(
+            source_path.to_string_lossy().to_string(),
+            None,
+        )]);
+        config.deny = vec!["test-bundle".to_string()];
+        let target_dir = tempdir().unwrap();
+
+        let err = install_bundle(
+            &config,
+            "test-bundle",
+            &Tool::Claude,
+            target_dir.path(),
+            &[SkillType::Command],
+            &InstallOptions::default(),
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("blocked by the `allow`/`deny` lists"), "{}", err);
+    }
+
+    #[test]
+    fn test_since_needs_a_source_with_history() {
+        let (_source_dir, source_path) = setup_test_source();
+        let target_dir = tempdir().unwrap();
+        let source = crate::source::LocalSource::new(source_path);
+        let opts = InstallOptions {
+            since: Some("HEAD~1".to_string()),
+            ..Default::default()
+        };
+
+        let err = install_from_source(
+            &source,
+            &Tool::Claude,
+            target_dir.path(),
+            &[SkillType::Command],
+            &opts,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("has no history"), "{}", err);
+        assert!(!target_dir.path().join(".claude").exists());
+    }
+
+    #[test]
+    fn test_plan_json_lists_every_file_without_writing() {
+        let (_source_dir, source_path) = setup_test_source();
+        let target_dir = tempdir().unwrap();
+        let existing = target_dir.path().join(".claude/commands/test-bundle/commit.md");
+        fs::create_dir_all(existing.parent().unwrap()).unwrap();
+        fs::write(&existing, "# Local edit").unwrap();
+
+        let source = crate::source::LocalSource::new(source_path);
+        let types = [SkillType::Skill, SkillType::Agent, SkillType::Command];
+        let target = target_dir.path();
+        let plan = InstallPlan::build(&Tool::Claude, target, &InstallOptions::default(), |opts| {
+            install_bundle_from_source(&source, "test-bundle", &Tool::Claude, target, &types, opts)
+        })
+        .unwrap();
+
+        let json = serde_json::to_string(&plan).unwrap();
+        let parsed: InstallPlan = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, plan);
+        assert_eq!(parsed.tool, "claude");
+        assert_eq!(parsed.bundles[0].bundle, "test-bundle");
+        // helper skill, analyzer agent, commit and debug commands
+        assert_eq!(parsed.files.len(), 4);
+        let commit = parsed.files.iter().find(|f| f.dest == existing).unwrap();
+        assert_eq!(commit.action, PlanAction::Skip);
+        assert!(commit.src.ends_with("commands/commit.md"));
+
+        let forced = InstallOptions {
+            force: true,
+            ..Default::default()
+        };
+        let plan = InstallPlan::build(&Tool::Claude, target, &forced, |opts| {
+            install_bundle_from_source(&source, "test-bundle", &Tool::Claude, target, &types, opts)
+        })
+        .unwrap();
+        let commit = plan.files.iter().find(|f| f.dest == existing).unwrap();
+        assert_eq!(commit.action, PlanAction::Overwrite);
+
+        // Only the pre-existing file is in the target
+        let files: Vec<_> = walkdir::WalkDir::new(target_dir.path())
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_type().is_file())
+            .collect();
+        assert_eq!(files.len(), 1);
+        assert_eq!(fs::read_to_string(&existing).unwrap(), "# Local edit");
+    }
+
+    #[test]
+    fn test_dry_run_leaves_target_and_manifest_untouched() {
+        let (_source_dir, source_path) = setup_test_source();
+        let target_dir = tempdir().unwrap();
+        let target = target_dir.path();
+
+        let source = crate::source::LocalSource::new(source_path);
+        let types = [SkillType::Skill, SkillType::Agent, SkillType::Command];
+        let opts = InstallOptions {
+            dry_run: true,
+            ..Default::default()
+        };
+        let records =
+            install_bundle_from_source(&source, "test-bundle", &Tool::Claude, target, &types, &opts)
+                .unwrap();
+
+        assert_eq!(records[0].files.len(), 4);
+        assert!(records.iter().flat_map(|r| &r.files).all(|f| f.starts_with(target)));
+        assert_eq!(fs::read_dir(target).unwrap().count(), 0);
+        assert!(InstallManifest::load(&Tool::Claude, target).is_empty());
+    }
+
+    #[test]
+    fn test_reinstall_keeps_local_edits_unless_forced() {
+        let source_dir = tempdir().unwrap();
+        let skill_dir = source_dir.path().join("kit/skills/pdf");
+        fs::create_dir_all(&skill_dir).unwrap();
+        fs::write(skill_dir.join("SKILL.md"), "---\nname: pdf\ndescription: PDFs\n---\n").unwrap();
+        fs::write(skill_dir.join("forms.md"), "# Forms").unwrap();
+        fs::write(skill_dir.join("merge.md"), "# Merge").unwrap();
+        let commands = source_dir.path().join("kit/commands");
+        fs::create_dir_all(&commands).unwrap();
+        fs::write(commands.join("run.md"), "# Run").unwrap();
+
+        let target_dir = tempdir().unwrap();
+        let source = crate::source::LocalSource::new(source_dir.path().to_path_buf());
+        let types = [SkillType::Skill, SkillType::Command];
+        let target = target_dir.path();
+        let install = |opts: &InstallOptions| {
+            install_bundle_from_source(&source, "kit", &Tool::Claude, target, &types, opts).unwrap()
+        };
+        install(&InstallOptions::default());
+
+        // Edit one companion and the command; leave SKILL.md and merge.md alone
+        let installed = target_dir.path().join(".claude/skills/kit-pdf");
+        let forms = installed.join("forms.md");
+        let run = target_dir.path().join(".claude/commands/kit/run.md");
+        fs::write(&forms, "# My forms").unwrap();
+        fs::write(&run, "# My run").unwrap();
+        fs::write(skill_dir.join("merge.md"), "# Merge v2").unwrap();
+
+        // Each file is compared on its own; SKILL.md is identical and rewritten
+        let write_opts = InstallOptions::default().write_options();
+        let kit = crate::bundle::Bundle::from_path(source_dir.path().join("kit")).unwrap();
+        for item in kit.skills.iter().chain(&kit.commands) {
+            Tool::Claude.write_file_with(target_dir.path(), "kit", item, &write_opts).unwrap();
+        }
+        let mut skipped = write_opts.skipped.paths();
+        skipped.sort();
+        assert_eq!(skipped, vec![run.clone(), forms.clone(), installed.join("merge.md")]);
+
+        install(&InstallOptions::default());
+        assert_eq!(fs::read_to_string(&forms).unwrap(), "# My forms");
+        assert_eq!(fs::read_to_string(&run).unwrap(), "# My run");
+        assert_eq!(fs::read_to_string(installed.join("merge.md")).unwrap(), "# Merge");
+
+        let forced = InstallOptions {
+            force: true,
+            ..Default::default()
+        };
+        install(&forced);
+        assert_eq!(fs::read_to_string(&forms).unwrap(), "# Forms");
+        assert_eq!(fs::read_to_string(&run).unwrap(), "# Run");
+        assert_eq!(fs::read_to_string(installed.join("merge.md")).unwrap(), "# Merge v2");
+    }
 }
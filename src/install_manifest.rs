@@ -16,6 +16,16 @@ pub struct InstallManifest {
 pub struct ManifestEntry {
     pub name: String,
     pub source: String,
+    /// Git commit the bundle was installed from (git sources only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    /// Version of the bundle when it was installed, if it declares one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Files written for the bundle, relative to the target directory.
+    /// Empty for entries recorded before file lists were kept.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<PathBuf>,
 }
 
 impl InstallManifest {
@@ -55,18 +65,59 @@ impl InstallManifest {
         Ok(())
     }
 
-    /// Record a bundle install (upsert: update source if exists, append if new).
-    pub fn record_install(&mut self, name: &str, source: &str) {
+    /// Record a bundle install (upsert: update source if exists, append if new),
+    /// along with the git commit it came from, if any.
+    pub fn record_install(&mut self, name: &str, source: &str, commit: Option<&str>) {
+        let commit = commit.map(str::to_string);
         if let Some(entry) = self.bundles.iter_mut().find(|e| e.name == name) {
             entry.source = source.to_string();
+            entry.commit = commit;
         } else {
             self.bundles.push(ManifestEntry {
                 name: name.to_string(),
                 source: source.to_string(),
+                commit,
+                version: None,
+                files: Vec::new(),
             });
         }
     }
 
+    /// Add the files written for an installed bundle to its entry. Installs of
+    /// different item types add up, so earlier files are kept.
+    pub fn record_files(&mut self, name: &str, files: &[PathBuf]) {
+        if let Some(entry) = self.bundles.iter_mut().find(|e| e.name == name) {
+            for file in files {
+                if !entry.files.contains(file) {
+                    entry.files.push(file.clone());
+                }
+            }
+        }
+    }
+
+    /// Record the version a bundle was installed at (`None` if it has none)
+    pub fn record_version(&mut self, name: &str, version: Option<&str>) {
+        if let Some(entry) = self.bundles.iter_mut().find(|e| e.name == name) {
+            entry.version = version.map(str::to_string);
+        }
+    }
+
+    /// The version a bundle was installed at, if recorded
+    pub fn version_of(&self, name: &str) -> Option<&str> {
+        self.bundles
+            .iter()
+            .find(|e| e.name == name)
+            .and_then(|e| e.version.as_deref())
+    }
+
+    /// The recorded files of a bundle (empty if unknown)
+    pub fn files_of(&self, name: &str) -> &[PathBuf] {
+        self.bundles
+            .iter()
+            .find(|e| e.name == name)
+            .map_or(&[], |e| e.files.as_slice())
+    }
+
     /// Remove a bundle entry by name. Returns true if an entry was removed.
     pub fn remove_bundle(&mut self, name: &str) -> bool {
         let len_before = self.bundles.len();
@@ -74,6 +125,14 @@ impl InstallManifest {
         self.bundles.len() < len_before
     }
 
+    /// Get the recorded source of a bundle, if it is installed.
+    pub fn source_of(&self, name: &str) -> Option<&str> {
+        self.bundles
+            .iter()
+            .find(|e| e.name == name)
+            .map(|e| e.source.as_str())
+    }
+
     /// Get all recorded bundle names.
     pub fn bundle_names(&self) -> Vec<&str> {
         self.bundles.iter().map(|e| e.name.as_str()).collect()
@@ -117,8 +176,8 @@ mod tests {
         let target = dir.path();
 
         let mut manifest = InstallManifest::default();
-        manifest.record_install("ralph", "~/claude_skills");
-        manifest.record_install("cl", "https://github.com/example/repo");
+        manifest.record_install("ralph", "~/claude_skills", None);
+        manifest.record_install("cl", "https://github.com/example/repo", None);
 
         manifest.save(&Tool::Claude, target).unwrap();
 
@@ -127,17 +186,14 @@ mod tests {
         assert_eq!(loaded.bundles[0].name, "ralph");
         assert_eq!(loaded.bundles[0].source, "~/claude_skills");
         assert_eq!(loaded.bundles[1].name, "cl");
-        assert_eq!(
-            loaded.bundles[1].source,
-            "https://github.com/example/repo"
-        );
+        assert_eq!(loaded.bundles[1].source, "https://github.com/example/repo");
     }
 
     #[test]
     fn test_upsert_idempotency() {
         let mut manifest = InstallManifest::default();
-        manifest.record_install("ralph", "~/old_path");
-        manifest.record_install("ralph", "~/new_path");
+        manifest.record_install("ralph", "~/old_path", None);
+        manifest.record_install("ralph", "~/new_path", None);
 
         assert_eq!(manifest.bundles.len(), 1);
         assert_eq!(manifest.bundles[0].source, "~/new_path");
@@ -146,8 +202,8 @@ mod tests {
     #[test]
     fn test_remove_bundle() {
         let mut manifest = InstallManifest::default();
-        manifest.record_install("ralph", "~/skills");
-        manifest.record_install("cl", "https://example.com");
+        manifest.record_install("ralph", "~/skills", None);
+        manifest.record_install("cl", "https://example.com", None);
 
         assert!(manifest.remove_bundle("ralph"));
         assert_eq!(manifest.bundles.len(), 1);
@@ -160,13 +216,40 @@ mod tests {
     #[test]
     fn test_bundle_names() {
         let mut manifest = InstallManifest::default();
-        manifest.record_install("ralph", "~/skills");
-        manifest.record_install("cl", "https://example.com");
+        manifest.record_install("ralph", "~/skills", None);
+        manifest.record_install("cl", "https://example.com", None);
 
         let names = manifest.bundle_names();
         assert_eq!(names, vec!["ralph", "cl"]);
     }
 
+    #[test]
+    fn test_record_files_accumulates_and_roundtrips() {
+        let dir = tempdir().unwrap();
+        let mut manifest = InstallManifest::default();
+        manifest.record_install("pdf", "~/skills", None);
+        manifest.record_files("pdf", &[PathBuf::from(".claude/commands/pdf/fill.md")]);
+        manifest.record_install("pdf", "~/skills", None);
+        manifest.record_files(
+            "pdf",
+            &[
+                PathBuf::from(".claude/commands/pdf/fill.md"),
+                PathBuf::from(".claude/skills/pdf-forms/SKILL.md"),
+            ],
+        );
+        manifest.save(&Tool::Claude, dir.path()).unwrap();
+
+        let loaded = InstallManifest::load(&Tool::Claude, dir.path());
+        assert_eq!(
+            loaded.files_of("pdf"),
+            [
+                PathBuf::from(".claude/commands/pdf/fill.md"),
+                PathBuf::from(".claude/skills/pdf-forms/SKILL.md"),
+            ]
+        );
+        assert!(loaded.files_of("other").is_empty());
+    }
+
     #[test]
     fn test_load_missing_file() {
         let dir = tempdir().unwrap();
@@ -191,7 +274,7 @@ mod tests {
         assert!(manifest.is_empty());
 
         let mut manifest = InstallManifest::default();
-        manifest.record_install("test", "source");
+        manifest.record_install("test", "source", None);
         assert!(!manifest.is_empty());
     }
 }
@@ -0,0 +1,194 @@
+use anyhow::Result;
+use colored::Colorize;
+use std::path::{Path, PathBuf};
+
+use crate::bundle::{Bundle, SkillType};
+use crate::frontmatter::parse_strict;
+use crate::target::{Output, Tool, WriteOptions};
+use crate::transform::PLACEHOLDER_DESCRIPTION;
+
+/// Outcome of linting one item of a bundle (`skm lint`)
+#[derive(Debug)]
+pub struct LintResult {
+    pub skill_type: SkillType,
+    pub name: String,
+    /// Where the item would be installed, relative to the target directory
+    pub dest: PathBuf,
+    /// Required fields the source lacks that the install fills in
+    pub filled: Vec<String>,
+    /// Problems with the frontmatter the install would produce
+    pub problems: Vec<String>,
+}
+
+impl LintResult {
+    pub fn is_ok(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+/// Frontmatter fields `tool` needs on an installed item of `skill_type`
+fn required_fields(tool: &Tool, skill_type: SkillType) -> &'static [&'static str] {
+    match (skill_type, tool) {
+        (SkillType::Skill, _) => &["name", "description"],
+        (SkillType::Agent, Tool::OpenCode) => &["description"],
+        (SkillType::Agent, _) => &["name", "description"],
+        (SkillType::Rule, Tool::Claude | Tool::OpenCode) => &["name", "description"],
+        (SkillType::Rule, _) => &["description"],
+        (SkillType::Command, _) => &[],
+    }
+}
+
+/// Whether `field` is set to something non-empty
+fn has_field(fields: &serde_yaml::Mapping, field: &str) -> bool {
+    match fields.get(field) {
+        None | Some(serde_yaml::Value::Null) => false,
+        Some(serde_yaml::Value::String(s)) => !s.trim().is_empty(),
+        Some(_) => true,
+    }
+}
+
+/// Dry-run an install of `types` of `bundle` for `tool` and check the
+/// frontmatter of every item it would write. Nothing is written to disk.
+pub fn lint_bundle(bundle: &Bundle, tool: &Tool, types: &[SkillType]) -> Result<Vec<LintResult>> {
+    let mut results = Vec::new();
+    for (skill_type, files) in [
+        (SkillType::Skill, &bundle.skills),
+        (SkillType::Agent, &bundle.agents),
+        (SkillType::Command, &bundle.commands),
+        (SkillType::Rule, &bundle.rules),
+    ] {
+        if !types.contains(&skill_type) {
+            continue;
+        }
+        for skill in files {
+            let opts = WriteOptions {
+                output: Output::dry_run(),
+                overwrite: true,
+                ..Default::default()
+            };
+            // Destinations come out relative to the project
+            let dest = tool.write_file_with(Path::new(""), &bundle.name, skill, &opts)?;
+            let installed = opts.output.buffered(&dest).unwrap_or_default();
+            let installed = String::from_utf8_lossy(&installed);
+            let source = std::fs::read_to_string(&skill.path).unwrap_or_default();
+            let source_fields = parse_strict(&source).ok().flatten().unwrap_or_default();
+
+            let mut filled = Vec::new();
+            let mut problems = Vec::new();
+            match parse_strict(&installed) {
+                Err(e) => problems.push(e),
+                Ok(fields) => {
+                    let fields = fields.unwrap_or_default();
+                    for field in required_fields(tool, skill_type) {
+                        if !has_field(&fields, field) {
+                            problems.push(format!("missing `{}`", field));
+                        } else if !has_field(&source_fields, field) {
+                            filled.push(field.to_string());
+                        }
+                    }
+                }
+            }
+            if !opts.placeholder_descriptions.names().is_empty() {
+                problems.push(format!(
+                    "description is the placeholder \"{}\"; add one or a heading",
+                    PLACEHOLDER_DESCRIPTION
+                ));
+            }
+
+            results.push(LintResult {
+                skill_type,
+                name: skill.name.clone(),
+                dest,
+                filled,
+                problems,
+            });
+        }
+    }
+    Ok(results)
+}
+
+/// Print one line per item, with what was filled in and what is wrong
+pub fn print_results(bundle_name: &str, tool: &Tool, results: &[LintResult]) {
+    println!(
+        "{} {} for {}",
+        "Linting".bold(),
+        bundle_name.cyan(),
+        tool.name()
+    );
+    println!();
+    for result in results {
+        let item = format!("{}/{}", result.skill_type.dir_name(), result.name);
+        let mark = if result.is_ok() { "✓".green() } else { "✗".red() };
+        println!("  {} {} {}", mark, item, format!("({})", result.dest.display()).dimmed());
+        if !result.filled.is_empty() {
+            println!(
+                "      {}",
+                format!("added on install: {}", result.filled.join(", ")).dimmed()
+            );
+        }
+        for problem in &result.problems {
+            println!("      {}", problem.yellow());
+        }
+    }
+    println!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bundle::SkillFile;
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn skill(dir: &Path, name: &str, content: &str) -> SkillFile {
+        let skill_dir = dir.join(name);
+        fs::create_dir_all(&skill_dir).unwrap();
+        fs::write(skill_dir.join("SKILL.md"), content).unwrap();
+        SkillFile {
+            name: name.to_string(),
+            path: skill_dir.join("SKILL.md"),
+            skill_type: SkillType::Skill,
+            source_dir: Some(skill_dir),
+        }
+    }
+
+    #[test]
+    fn test_lint_flags_skill_without_description() {
+        let dir = tempdir().unwrap();
+        let bundle = Bundle {
+            name: "pdf".to_string(),
+            path: dir.path().to_path_buf(),
+            skills: vec![
+                skill(dir.path(), "fill", "---\nname: fill\ndescription: Fill forms\n---\nBody\n"),
+                skill(dir.path(), "titled", "---\nname: titled\n---\n# Merge PDFs\n"),
+                skill(dir.path(), "empty", "\n"),
+            ],
+            agents: vec![],
+            commands: vec![],
+            rules: vec![],
+            meta: Default::default(),
+        };
+
+        let results = lint_bundle(&bundle, &Tool::Claude, &[SkillType::Skill]).unwrap();
+
+        assert!(results[0].is_ok());
+        assert!(results[0].filled.is_empty());
+        assert_eq!(results[0].dest, PathBuf::from(".claude/skills/pdf-fill/SKILL.md"));
+        // A missing description is filled in from the first heading
+        assert!(results[1].is_ok());
+        assert_eq!(results[1].filled, vec!["description"]);
+        // ...but with nothing to take it from, it's only the placeholder
+        assert!(!results[2].is_ok());
+        assert!(results[2].problems[0].contains("placeholder"));
+    }
+
+    #[test]
+    fn test_parse_frontmatter_errors() {
+        assert_eq!(parse_strict("# No frontmatter"), Ok(None));
+        assert!(parse_strict("---\nname: x\n").unwrap_err().contains("closing"));
+        assert!(parse_strict("---\nname: [x\n---\n").unwrap_err().contains("YAML"));
+        let fields = parse_strict("---\nname: x\ndescription: \"\"\n---\n").unwrap().unwrap();
+        assert!(has_field(&fields, "name"));
+        assert!(!has_field(&fields, "description"));
+    }
+}
@@ -0,0 +1,304 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::bundle::SkillType;
+use crate::config::{self, Config};
+use crate::install::{install_bundle_from_source, InstallOptions, InstallRecord};
+use crate::install_manifest::InstallManifest;
+use crate::source::{ArchiveSource, GitSource, LocalSource, Source};
+use crate::target::Tool;
+
+/// Lock file name, written to the target directory
+pub const LOCK_FILE: &str = "skm.lock";
+
+/// Exact record of installed bundles, for reproducible installs with `skm sync`.
+#[derive(Debug, Serialize, Deserialize, Default, PartialEq)]
+pub struct LockFile {
+    #[serde(default)]
+    pub bundles: Vec<LockEntry>,
+}
+
+/// One installed bundle for one tool.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct LockEntry {
+    /// Tool id (claude, opencode, cursor, codex, windsurf)
+    pub tool: String,
+    /// Name the bundle is installed under
+    pub name: String,
+    /// Bundle name in the source, when installed under an alias
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bundle: Option<String>,
+    /// Source path or git URL
+    pub source: String,
+    /// Git commit to install from (git sources only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+}
+
+impl LockFile {
+    pub fn path_for(target_dir: &Path) -> PathBuf {
+        target_dir.join(LOCK_FILE)
+    }
+
+    /// Build a lock from the install manifests of every tool in `target_dir`
+    pub fn from_manifests(config: &Config, target_dir: &Path) -> Self {
+        let mut bundles = Vec::new();
+
+        for tool in Tool::ALL {
+            let manifest = InstallManifest::load(&tool, target_dir);
+            let mut entries = manifest.bundles.clone();
+            entries.sort_by(|a, b| a.name.cmp(&b.name));
+
+            for entry in entries {
+                // Aliased installs record the alias; the lock also needs the real bundle
+                let bundle = config
+                    .resolve_alias(&entry.name)
+                    .map(|target| {
+                        target
+                            .split_once('/')
+                            .map_or(target, |(_, bundle)| bundle)
+                            .to_string()
+                    })
+                    .filter(|bundle| *bundle != entry.name);
+
+                // Installs made before commits were tracked: use the cache's commit
+                let commit = entry.commit.clone().or_else(|| {
+                    if config::is_git_url(&entry.source) {
+                        GitSource::new(entry.source.clone())
+                            .map(|s| s.with_mirror(config.git_mirror().as_deref()))
+                            .and_then(|s| s.head_commit())
+                            .ok()
+                    } else {
+                        None
+                    }
+                });
+
+                bundles.push(LockEntry {
+                    tool: tool.id().to_string(),
+                    name: entry.name,
+                    bundle,
+                    source: entry.source,
+                    commit,
+                });
+            }
+        }
+
+        LockFile { bundles }
+    }
+
+    pub fn load(target_dir: &Path) -> Result<Self> {
+        let path = Self::path_for(target_dir);
+        let content = std::fs::read_to_string(&path).with_context(|| {
+            format!(
+                "Could not read {} (create one with `skm lock`)",
+                path.display()
+            )
+        })?;
+        toml::from_str(&content).with_context(|| format!("Invalid lock file {}", path.display()))
+    }
+
+    pub fn save(&self, target_dir: &Path) -> Result<()> {
+        let content = toml::to_string_pretty(self)?;
+        std::fs::write(Self::path_for(target_dir), content)?;
+        Ok(())
+    }
+}
+
+impl LockEntry {
+    /// The source this entry installs from, pinned to its commit if it has one
+    fn source(&self, git_mirror: Option<&str>) -> Result<Box<dyn Source>> {
+        if let Some(commit) = &self.commit {
+            let source = GitSource::new(self.source.clone())?
+                .with_mirror(git_mirror)
+                .pinned(commit.clone());
+            Ok(Box::new(source))
+        } else if config::is_git_url(&self.source) {
+            Ok(Box::new(GitSource::new(self.source.clone())?.with_mirror(git_mirror)))
+        } else if ArchiveSource::is_archive_url(&self.source) {
+            Ok(Box::new(ArchiveSource::new(self.source.clone())?))
+        } else {
+            let path = config::expand_tilde(&self.source);
+            Ok(Box::new(LocalSource::new(path)))
+        }
+    }
+}
+
+/// Install exactly what the lock specifies, skipping tools left out of
+/// `enabled_tools`. Returns the install records per tool.
+pub fn sync(
+    lock: &LockFile,
+    target_dir: &Path,
+    types: &[SkillType],
+    opts: &InstallOptions,
+    config: &Config,
+) -> Result<Vec<(Tool, Vec<InstallRecord>)>> {
+    let mut installed = Vec::new();
+
+    for entry in &lock.bundles {
+        let tool = Tool::from_id(&entry.tool)
+            .ok_or_else(|| anyhow::anyhow!("Unknown tool `{}` in {}", entry.tool, LOCK_FILE))?;
+        if !config.tool_enabled(&tool) {
+            eprintln!(
+                "Warning: skipping `{}` for {}; it is not in `enabled_tools`",
+                entry.name, entry.tool
+            );
+            continue;
+        }
+
+        // The lock is authoritative, so a recorded source change is expected
+        let entry_opts = InstallOptions {
+            force: true,
+            bundle_alias: entry.bundle.as_ref().map(|_| entry.name.clone()),
+            ..opts.clone()
+        };
+        let bundle_name = entry.bundle.as_deref().unwrap_or(&entry.name);
+        config.check_bundle_allowed(bundle_name)?;
+
+        let source = entry.source(config.git_mirror().as_deref())?;
+        let records = install_bundle_from_source(
+            source.as_ref(),
+            bundle_name,
+            &tool,
+            target_dir,
+            types,
+            &entry_opts,
+        )?;
+        installed.push((tool, records));
+    }
+
+    Ok(installed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    const ALL_TYPES: [SkillType; 4] = [
+        SkillType::Skill,
+        SkillType::Agent,
+        SkillType::Command,
+        SkillType::Rule,
+    ];
+
+    #[test]
+    fn test_lock_and_sync_roundtrip() {
+        let source_dir = tempdir().unwrap();
+        let commands = source_dir.path().join("commit/commands");
+        fs::create_dir_all(&commands).unwrap();
+        fs::write(commands.join("commit.md"), "# Commit").unwrap();
+        let skills = source_dir.path().join("docs/skills/writer");
+        fs::create_dir_all(&skills).unwrap();
+        fs::write(skills.join("SKILL.md"), "# Writer").unwrap();
+
+        let source = LocalSource::new(source_dir.path().to_path_buf());
+        let project = tempdir().unwrap();
+        for (tool, bundle) in [(Tool::Claude, "commit"), (Tool::Cursor, "docs")] {
+            install_bundle_from_source(
+                &source,
+                bundle,
+                &tool,
+                project.path(),
+                &ALL_TYPES,
+                &InstallOptions::default(),
+            )
+            .unwrap();
+        }
+
+        let config = Config::default();
+        let lock = LockFile::from_manifests(&config, project.path());
+        assert_eq!(lock.bundles.len(), 2);
+        assert_eq!(lock.bundles[0].tool, "claude");
+        assert_eq!(lock.bundles[0].name, "commit");
+        assert_eq!(lock.bundles[0].commit, None);
+        assert_eq!(lock.bundles[1].tool, "cursor");
+        lock.save(project.path()).unwrap();
+        assert_eq!(LockFile::load(project.path()).unwrap(), lock);
+
+        // Syncing into a fresh directory reproduces the same files and lock
+        let fresh = tempdir().unwrap();
+        sync(&lock, fresh.path(), &ALL_TYPES, &InstallOptions::default(), &config).unwrap();
+        assert!(fresh
+            .path()
+            .join(".claude/commands/commit/commit.md")
+            .exists());
+        assert!(fresh
+            .path()
+            .join(".cursor/skills/docs-writer/SKILL.md")
+            .exists());
+        assert_eq!(LockFile::from_manifests(&config, fresh.path()), lock);
+    }
+
+    #[test]
+    fn test_sync_rejects_unknown_tool() {
+        let lock = LockFile {
+            bundles: vec![LockEntry {
+                tool: "emacs".to_string(),
+                name: "x".to_string(),
+                bundle: None,
+                source: "/nonexistent".to_string(),
+                commit: None,
+            }],
+        };
+        let dir = tempdir().unwrap();
+        let config = Config::default();
+        let err =
+            sync(&lock, dir.path(), &ALL_TYPES, &InstallOptions::default(), &config).unwrap_err();
+        assert!(err.to_string().contains("Unknown tool `emacs`"));
+    }
+
+    #[test]
+    fn test_sync_rejects_denied_bundle() {
+        let source_dir = tempdir().unwrap();
+        let commands = source_dir.path().join("commit/commands");
+        fs::create_dir_all(&commands).unwrap();
+        fs::write(commands.join("commit.md"), "# Commit").unwrap();
+        let lock = LockFile {
+            bundles: vec![LockEntry {
+                tool: "claude".to_string(),
+                name: "commit".to_string(),
+                bundle: None,
+                source: source_dir.path().to_string_lossy().to_string(),
+                commit: None,
+            }],
+        };
+        let mut config = Config::default();
+        config.deny = vec!["commit".to_string()];
+
+        let dir = tempdir().unwrap();
+        let err =
+            sync(&lock, dir.path(), &ALL_TYPES, &InstallOptions::default(), &config).unwrap_err();
+        assert!(err.to_string().contains("blocked by the `allow`/`deny` lists"), "{}", err);
+        assert!(!dir.path().join(".claude/commands/commit/commit.md").exists());
+    }
+
+    #[test]
+    fn test_sync_skips_disabled_tools() {
+        let source_dir = tempdir().unwrap();
+        let commands = source_dir.path().join("commit/commands");
+        fs::create_dir_all(&commands).unwrap();
+        fs::write(commands.join("commit.md"), "# Commit").unwrap();
+        let entry = |tool: &str| LockEntry {
+            tool: tool.to_string(),
+            name: "commit".to_string(),
+            bundle: None,
+            source: source_dir.path().to_string_lossy().to_string(),
+            commit: None,
+        };
+        let lock = LockFile {
+            bundles: vec![entry("claude"), entry("cursor")],
+        };
+        let mut config = Config::default();
+        config.enabled_tools = vec!["cursor".to_string()];
+
+        let dir = tempdir().unwrap();
+        let installed =
+            sync(&lock, dir.path(), &ALL_TYPES, &InstallOptions::default(), &config).unwrap();
+        assert_eq!(installed.len(), 1);
+        assert_eq!(installed[0].0, Tool::Cursor);
+        assert!(!dir.path().join(".claude").exists());
+        assert!(dir.path().join(".cursor").exists());
+    }
+}
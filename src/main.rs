@@ -1,29 +1,46 @@
 mod bundle;
+mod cache;
+mod catalog;
 mod config;
+mod diff;
 mod discover;
+mod frontmatter;
+mod git_auth;
 mod install;
 mod install_manifest;
+mod lint;
+mod lock;
 mod manifest;
+mod parallel;
+mod render;
+mod scaffold;
 mod setup;
 mod source;
+mod stats;
 mod target;
+mod transform;
+mod validate;
 
 use anyhow::Result;
 use clap::{CommandFactory, Parser, Subcommand};
 use clap_complete::{generate, Shell};
 use colored::Colorize;
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use crate::bundle::SkillType;
+use crate::bundle::{version_needs_refresh, SkillType};
 use crate::config::{Config, SourceConfig};
-use crate::install::{install_bundle, install_bundle_from_source, install_from_source};
-use crate::setup::run_setup_wizard;
-use crate::target::Tool;
+use crate::install::{
+    install_bundle, install_bundle_from_source, install_from_path, install_from_source,
+    refresh_bundle,
+    ContentSnapshot, InstallOptions, InstallPlan, InstallRecord,
+};
+use crate::setup::{run_setup_edit, run_setup_wizard};
+use crate::target::{ellipsize, prefixed_bundle_name, Tool};
 
 #[derive(Parser)]
 #[command(name = "skm")]
-#[command(about = "Manage AI coding tool skills for Claude, OpenCode, Cursor, and Codex")]
+#[command(about = "Manage AI coding tool skills for Claude, OpenCode, Cursor, Codex, and Windsurf")]
 #[command(version)]
 #[command(args_conflicts_with_subcommands = true)]
 struct Cli {
@@ -46,6 +63,10 @@ struct Cli {
     #[arg(short = 'x', long = "codex", global = true)]
     codex: bool,
 
+    /// Install to Windsurf instead of Claude
+    #[arg(short = 'w', long = "windsurf", global = true)]
+    windsurf: bool,
+
     /// Install globally (tool-specific location)
     #[arg(short = 'g', long = "global", global = true)]
     global: bool,
@@ -69,6 +90,73 @@ struct Cli {
     /// Filter: only install rules
     #[arg(long = "rules")]
     rules_only: bool,
+
+    /// Install a single skill/agent/command/rule under this name instead of `{bundle}-{name}`
+    #[arg(long = "as", value_name = "NAME", global = true)]
+    as_name: Option<String>,
+
+    /// Install all selected bundles under this single bundle name
+    #[arg(long = "into-bundle", value_name = "NAME", global = true)]
+    into_bundle: Option<String>,
+
+    /// When installing a whole source, skip bundles that are already installed
+    #[arg(long = "only-new", global = true)]
+    only_new: bool,
+
+    /// When installing a whole configured source, remove installed bundles it no
+    /// longer has (`--dry-run` lists them instead)
+    #[arg(long, global = true, conflicts_with = "into_bundle")]
+    prune: bool,
+
+    /// Only list, update, or install (from a whole source) bundles of git sources
+    /// changed since this revision (e.g. HEAD~5) or date (YYYY-MM-DD)
+    #[arg(long, value_name = "REV|DATE", global = true)]
+    since: Option<String>,
+
+    /// Set the `model:` of installed agents (Claude, OpenCode and Cursor)
+    #[arg(long, value_name = "NAME", global = true)]
+    model: Option<String>,
+
+    /// List installed files in the tool directory's .gitignore (e.g. .claude/.gitignore)
+    #[arg(long, global = true)]
+    gitignore: bool,
+
+    /// Read up to N sources in parallel (default: `jobs` from config, else the CPU count)
+    #[arg(short = 'j', long, value_name = "N", global = true,
+          value_parser = clap::value_parser!(u16).range(1..))]
+    jobs: Option<u16>,
+
+    /// Show the files an install would write, without writing anything
+    #[arg(long = "dry-run", global = true)]
+    dry_run: bool,
+
+    /// With --dry-run, print the plan as JSON (tool, bundles, and src/dest/action per file)
+    #[arg(long = "plan-json", global = true, requires = "dry_run")]
+    plan_json: bool,
+
+    /// After installing, print the files and bytes written (per type) and the time taken
+    #[arg(long = "stats-after", global = true)]
+    stats_after: bool,
+
+    /// Don't copy README/CHANGELOG/CONTRIBUTING files that sit next to a skill
+    #[arg(long = "no-docs", global = true)]
+    no_docs: bool,
+
+    /// Only install if the tool's directory (e.g. `.cursor/`) already exists in the target
+    #[arg(long = "if-tool-present", global = true)]
+    if_tool_present: bool,
+
+    /// Overwrite existing files with different content, and bundles installed from another source
+    #[arg(long, global = true)]
+    force: bool,
+
+    /// Allow running the `transform_hook` executable from config on installed files
+    #[arg(long = "allow-exec", global = true)]
+    allow_exec: bool,
+
+    /// Answer yes to every confirmation prompt
+    #[arg(short = 'y', long, global = true)]
+    yes: bool,
 }
 
 #[derive(Subcommand)]
@@ -78,8 +166,24 @@ enum Commands {
         /// Bundle name to install
         bundle: String,
     },
-    /// Browse available bundles interactively
-    List,
+    /// Browse available bundles interactively, or search them with a query
+    List {
+        /// Print bundles matching this text, best matches first
+        query: Option<String>,
+
+        /// Print every source's bundles as JSON
+        #[arg(long, conflicts_with = "query")]
+        json: bool,
+    },
+    /// Print the installable items of a bundle, one `type name` per line
+    Items {
+        /// Bundle name (or source/bundle)
+        bundle: String,
+
+        /// Print JSON instead of plain lines
+        #[arg(long)]
+        json: bool,
+    },
     /// Manage skill sources (interactive if no subcommand)
     Sources {
         #[command(subcommand)]
@@ -87,7 +191,7 @@ enum Commands {
     },
     /// Show installed skills in current directory
     Here {
-        /// Filter by tool (claude, opencode, cursor)
+        /// Filter by tool (claude, opencode, cursor, codex, windsurf, or all)
         #[arg(long)]
         tool: Option<String>,
 
@@ -99,12 +203,22 @@ enum Commands {
         #[arg(long)]
         clean: bool,
 
-        /// Skip confirmation prompts
-        #[arg(short = 'y', long)]
-        yes: bool,
+        /// Show file sizes and line counts, with per-bundle totals
+        #[arg(short = 'v', long)]
+        verbose: bool,
+
+        /// Also list installs in subdirectories (e.g. monorepo packages)
+        #[arg(short = 'r', long, conflicts_with_all = ["remove", "clean"])]
+        recursive: bool,
+
+        /// Print the installs as a JSON array instead of a listing
+        #[arg(long, conflicts_with_all = ["remove", "clean", "verbose"])]
+        json: bool,
     },
     /// Update git sources and refresh installed skills
     Update {
+        /// Only update this source (by name) or bundle; default: everything
+        name: Option<String>,
         /// Only update git sources, don't refresh skills
         #[arg(long)]
         sources_only: bool,
@@ -123,17 +237,145 @@ enum Commands {
         #[arg(long)]
         to_rule: bool,
         /// Output file (default: stdout)
-        #[arg(long)]
+        #[arg(long, conflicts_with = "in_place")]
         output: Option<PathBuf>,
+        /// Overwrite the source file, keeping the original as `<file>.bak`
+        #[arg(long = "in-place")]
+        in_place: bool,
+        /// With --in-place, don't keep a `.bak` copy of the original
+        #[arg(long = "no-backup", requires = "in_place")]
+        no_backup: bool,
     },
     /// Remove an installed bundle
     Rm {
         /// Bundle name to remove
+        #[arg(required_unless_present = "source")]
+        bundle: Option<String>,
+
+        /// Remove every bundle recorded as installed from this source (name, path or URL)
+        #[arg(long, conflicts_with = "bundle")]
+        source: Option<String>,
+    },
+    /// Inspect the skm config file
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+    /// Record installed bundles, sources and git commits in skm.lock
+    Lock,
+    /// Install exactly what skm.lock specifies (git sources pinned to the recorded commit)
+    Sync,
+    /// Edit the config: review sources, set the default tool, add a source
+    Setup {
+        /// Set the default tool without prompting (claude, opencode, cursor, codex, windsurf)
+        #[arg(long = "default-tool", value_name = "TOOL")]
+        default_tool: Option<String>,
+    },
+    /// Check the frontmatter installing a bundle would produce, without installing it
+    Lint {
+        /// Bundle name (or source/bundle)
         bundle: String,
 
-        /// Skip confirmation prompt
-        #[arg(short = 'y', long)]
-        yes: bool,
+        /// Tool to check for (claude, opencode, cursor, codex, windsurf;
+        /// default: the selected tool)
+        #[arg(long)]
+        tool: Option<String>,
+    },
+    /// Check a source's skill files for problems before publishing it; fails if any are found
+    Validate {
+        /// Source name or directory (default: the first local source)
+        source: Option<String>,
+    },
+    /// Show how a bundle's installed files differ from what its source would install
+    Diff {
+        /// Bundle name
+        bundle: String,
+    },
+    /// Check that a bundle's installed files match what its source would install
+    /// (OK/MODIFIED/MISSING); fails on any mismatch
+    Verify {
+        /// Bundle name
+        bundle: String,
+    },
+    /// Write a bundle's transformed files flat into a directory, as `{bundle}-{type}-{name}.md`
+    Render {
+        /// Bundle name (or source/bundle)
+        bundle: String,
+
+        /// Directory to write the files to
+        #[arg(long, value_name = "DIR")]
+        out: PathBuf,
+    },
+    /// Summarize sources, available bundles, installs per tool and git cache size
+    Stats {
+        /// Print JSON instead of a summary
+        #[arg(long)]
+        json: bool,
+    },
+    /// Manage local bundle aliases (lists aliases if no subcommand)
+    Alias {
+        #[command(subcommand)]
+        action: Option<AliasAction>,
+    },
+    /// Create a new skill, agent, command or rule in the first local source
+    /// (prompts for anything not given)
+    New {
+        /// Item type: skill, agent, command or rule
+        #[arg(value_name = "TYPE")]
+        item_type: Option<String>,
+
+        /// Name of the new item
+        name: Option<String>,
+
+        /// One-line description for the frontmatter
+        #[arg(short = 'd', long)]
+        description: Option<String>,
+
+        /// Bundle folder to create it in (default: one named after the item)
+        #[arg(long)]
+        bundle: Option<String>,
+    },
+    /// Inspect or clear the git source caches
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+}
+
+#[derive(Subcommand)]
+enum CacheAction {
+    /// List each git source's cache path and size
+    List,
+    /// Remove one source's cache, or all caches, so they are cloned again on next use
+    Clear {
+        /// Source name or URL (default: every cache)
+        name: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigAction {
+    /// Validate config.toml without running anything
+    Check,
+}
+
+#[derive(Subcommand)]
+enum AliasAction {
+    /// List configured aliases
+    List,
+    /// Add an alias for a bundle (e.g. `skm alias add my-commit fg/commit`)
+    Add {
+        /// Local name to install the bundle under
+        name: String,
+        /// Bundle reference: `source/bundle` or a bundle name
+        #[arg(value_name = "TARGET")]
+        bundle_ref: String,
+    },
+    /// Remove an alias
+    #[command(alias = "remove")]
+    Rm {
+        /// Alias to remove
+        name: String,
     },
 }
 
@@ -159,14 +401,24 @@ enum SourcesAction {
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
+    // `skm config check` has to work even when the config itself fails to load
+    if let Some(Commands::Config { action }) = &cli.command {
+        return match action {
+            ConfigAction::Check => config_check(),
+        };
+    }
+
     // Check if this is first run (no config file) and we're not doing a specific subcommand
-    let config = if !Config::exists()? && cli.command.is_none() && cli.bundle.is_none() {
+    let mut config = if !Config::exists()? && cli.command.is_none() && cli.bundle.is_none() {
         // First run - show setup wizard
         run_setup_wizard()?
     } else {
         // Load existing config or use defaults
         Config::load_or_default()?
     };
+    if let Some(jobs) = cli.jobs {
+        config.jobs = Some(jobs.into());
+    }
 
     // Determine target tool
     let tool = if cli.cursor {
@@ -175,17 +427,32 @@ fn main() -> Result<()> {
         Tool::OpenCode
     } else if cli.codex {
         Tool::Codex
+    } else if cli.windsurf {
+        Tool::Windsurf
     } else {
-        Tool::Claude
+        let default = Tool::from_id(&config.default_tool).unwrap_or(Tool::Claude);
+        // A default left out of `enabled_tools` gives way to the first enabled tool
+        match config.tools().first() {
+            Some(enabled) if !config.tool_enabled(&default) => *enabled,
+            _ => default,
+        }
     };
+    if !config.tool_enabled(&tool) {
+        anyhow::bail!(
+            "{} is not in `enabled_tools` in the config; add `{}` to use it",
+            tool.name(),
+            tool.id()
+        );
+    }
 
     // Determine target directory
     let target_dir = if cli.global {
-        tool.global_target()
+        config.global_target(&tool)
     } else if let Some(t) = cli.target {
+        check_target_dir(&t)?;
         t
     } else {
-        std::env::current_dir()?
+        config::working_dir("cd into an existing directory or pass --to <dir>")?
     };
 
     // Determine which types to install
@@ -213,15 +480,56 @@ fn main() -> Result<()> {
         ]
     };
 
+    let install_opts = InstallOptions {
+        as_name: cli.as_name,
+        force: cli.force,
+        into_bundle: cli.into_bundle,
+        if_tool_present: cli.if_tool_present,
+        only_new: cli.only_new,
+        prune: cli.prune,
+        since: cli.since.clone(),
+        assume_yes: cli.yes,
+        gitignore: cli.gitignore,
+        model: cli.model,
+        no_docs: cli.no_docs,
+        dry_run: cli.dry_run,
+        plan_json: cli.plan_json,
+        stats_after: cli.stats_after,
+        destinations: config.dest_templates()?,
+        install_prefix: config.install_prefix.clone(),
+        hook: if cli.allow_exec {
+            config.transform_hook_path()
+        } else {
+            None
+        },
+        ..Default::default()
+    };
+
     match cli.command {
         Some(Commands::Add {
             bundle: bundle_name,
         }) => {
             // `skm add <bundle>` is an alias for `skm <bundle>`
-            do_install(&config, &bundle_name, &tool, &target_dir, &types)?;
+            do_install(&config, &bundle_name, &tool, &target_dir, &types, &install_opts)?;
+        }
+        Some(Commands::List { json: true, .. }) => {
+            let catalog = catalog::Catalog::collect(&config, cli.since.as_deref());
+            println!("{}", serde_json::to_string_pretty(&catalog)?);
         }
-        Some(Commands::List) => {
-            browse_bundles(&config)?;
+        Some(Commands::List { query, .. }) => match (query, &cli.since) {
+            (None, None) => {
+                let install = BrowseInstall {
+                    tool: &tool,
+                    target_dir: &target_dir,
+                    types: &types,
+                    opts: &install_opts,
+                };
+                browse_bundles(&config, &install)?
+            }
+            (query, since) => search_bundles(&config, query.as_deref(), since.as_deref())?,
+        },
+        Some(Commands::Items { bundle, json }) => {
+            list_items(&config, &bundle, json)?;
         }
         Some(Commands::Sources { action }) => match action {
             Some(SourcesAction::List) => {
@@ -242,20 +550,38 @@ fn main() -> Result<()> {
             tool: filter_tool,
             remove,
             clean,
-            yes,
+            verbose,
+            recursive,
+            json,
         }) => {
-            if remove {
-                interactive_remove(&target_dir, filter_tool.as_deref())?;
+            let filter_tool = here_tool_filter(filter_tool.as_deref())?;
+            let filter_tool = filter_tool.as_deref();
+            let tools = config.tools();
+            if json {
+                let skills = installed_skills(&config, &target_dir, filter_tool, recursive)?;
+                println!("{}", serde_json::to_string_pretty(&skills)?);
+            } else if recursive {
+                show_nested_installs(&target_dir, &tools, filter_tool, verbose)?;
+            } else if remove {
+                interactive_remove(&target_dir, &tools, filter_tool, cli.yes)?;
             } else if clean {
-                clean_all_skills(&target_dir, filter_tool.as_deref(), yes)?;
+                clean_all_skills(&target_dir, &tools, filter_tool, cli.yes)?;
             } else {
-                show_installed_skills(&target_dir, filter_tool.as_deref())?;
+                show_installed_skills(&config, &target_dir, filter_tool, verbose)?;
             }
         }
-        Some(Commands::Update { sources_only }) => {
-            update_sources(&config)?;
+        Some(Commands::Update { name, sources_only }) => {
+            let target = UpdateTarget::resolve(&config, name.as_deref())?;
+            update_sources(&config, target.source())?;
             if !sources_only {
-                refresh_installed_skills(&config, &tool, &target_dir, &types)?;
+                refresh_installed_skills(
+                    &config,
+                    &tool,
+                    &target_dir,
+                    &types,
+                    &install_opts,
+                    &target,
+                )?;
             }
         }
         Some(Commands::Completions { shell }) => {
@@ -265,24 +591,119 @@ fn main() -> Result<()> {
             source,
             to_rule,
             output,
+            in_place,
+            no_backup,
         }) => {
-            convert_format(&source, to_rule, output.as_ref())?;
+            if in_place {
+                convert_in_place(&source, to_rule, !no_backup)?;
+            } else {
+                convert_format(&source, to_rule, output.as_deref())?;
+            }
         }
-        Some(Commands::Rm { bundle, yes }) => {
+        Some(Commands::Rm { bundle, source }) => {
             let filter_tool = if cli.cursor {
                 Some("cursor")
             } else if cli.opencode {
                 Some("opencode")
+            } else if cli.windsurf {
+                Some("windsurf")
             } else {
                 None
             };
-            remove_bundle(&bundle, &target_dir, filter_tool, yes)?;
+            let prefix = config.install_prefix.as_deref();
+            match (bundle, source) {
+                (_, Some(source)) => {
+                    remove_source_bundles(&config, &source, &target_dir, filter_tool, cli.yes)?
+                }
+                (Some(bundle), None) => {
+                    let tools = config.tools();
+                    remove_bundle(&bundle, &target_dir, &tools, filter_tool, prefix, cli.yes)?
+                }
+                (None, None) => unreachable!("clap requires a bundle or --source"),
+            }
+        }
+        Some(Commands::Config { .. }) => {
+            // Handled before config loading
+        }
+        Some(Commands::Lock) => {
+            write_lock(&config, &target_dir)?;
+        }
+        Some(Commands::Sync) => {
+            sync_from_lock(&config, &target_dir, &types, &install_opts)?;
+        }
+        Some(Commands::Setup { default_tool }) => {
+            let tool = match default_tool {
+                Some(id) => Some(parse_tool_id(&id)?),
+                None => None,
+            };
+            run_setup_edit(config, tool)?;
+        }
+        Some(Commands::Lint {
+            bundle,
+            tool: lint_tool,
+        }) => {
+            let lint_tool = match lint_tool {
+                Some(id) => parse_tool_id(&id)?,
+                None => tool,
+            };
+            lint_bundle(&config, &bundle, &lint_tool, &types)?;
+        }
+        Some(Commands::Validate { source }) => {
+            validate_source(&config, source.as_deref())?;
+        }
+        Some(Commands::Diff { bundle }) => {
+            print_bundle_diff(&config, &bundle, &tool, &target_dir, &types, &install_opts)?;
+        }
+        Some(Commands::Verify { bundle }) => {
+            verify_bundle(&config, &bundle, &tool, &target_dir, &types, &install_opts)?;
+        }
+        Some(Commands::Render { bundle, out }) => {
+            render_bundle(&config, &bundle, &tool, &types, &out, &install_opts)?;
+        }
+        Some(Commands::Stats { json }) => {
+            let cache_root = crate::source::GitSource::cache_root().ok();
+            let stats = stats::Stats::collect(&config, &target_dir, cache_root.as_deref())?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&stats)?);
+            } else {
+                stats.print();
+            }
+        }
+        Some(Commands::Alias { action }) => match action {
+            Some(AliasAction::Add { name, bundle_ref }) => alias_add(&name, &bundle_ref)?,
+            Some(AliasAction::Rm { name }) => alias_remove(&name)?,
+            Some(AliasAction::List) | None => alias_list(&config),
+        },
+        Some(Commands::New {
+            item_type,
+            name,
+            description,
+            bundle,
+        }) => {
+            let (item, path) = new_item(&config, item_type, name, description, bundle)?;
+            scaffold::print_created(&item, &path);
+        }
+        Some(Commands::Cache { action }) => {
+            let cache_root = crate::source::GitSource::cache_root()?;
+            let entries = cache::CacheEntry::from_config(&config);
+            match action {
+                CacheAction::List => cache::list(&entries, &cache_root),
+                CacheAction::Clear { name } => {
+                    // Clearing every cache means recloning every source, so ask first
+                    let prompt = format!("Remove every git cache in {}?", cache_root.display());
+                    if name.is_none() && cache_root.exists() && !confirm(&prompt, cli.yes)? {
+                        println!("{}", "Cancelled.".yellow());
+                    } else {
+                        cache::clear(&entries, name.as_deref(), &cache_root)?
+                    }
+                }
+            }
         }
         None => {
             // No subcommand - either list bundles or install a bundle
             if let Some(bundle_name) = cli.bundle {
                 // Install the specified bundle
-                do_install(&config, &bundle_name, &tool, &target_dir, &types)?;
+                do_install(&config, &bundle_name, &tool, &target_dir, &types, &install_opts)?;
             } else {
                 // List available bundles
                 list_bundles(&config)?;
@@ -293,27 +714,36 @@ fn main() -> Result<()> {
     Ok(())
 }
 
-fn browse_bundles(config: &Config) -> Result<()> {
-    use crate::bundle::Bundle;
-    use dialoguer::{theme::ColorfulTheme, FuzzySelect};
+/// Where `skm list` installs the bundle picked in the browser: the tool, target,
+/// types and options given on the command line
+struct BrowseInstall<'a> {
+    tool: &'a Tool,
+    target_dir: &'a Path,
+    types: &'a [SkillType],
+    opts: &'a InstallOptions,
+}
 
-    let sources = config.sources();
+fn browse_bundles(config: &Config, install: &BrowseInstall) -> Result<()> {
+    use crate::bundle::Bundle;
+    use dialoguer::{theme::ColorfulTheme, FuzzySelect, Input};
 
-    if sources.is_empty() {
+    if config.source_configs().is_empty() {
         println!("{}", "No sources configured.".yellow());
         println!("Add a source with: skm sources add <path>");
         return Ok(());
     }
 
-    // Collect all bundles with their source info
-    let mut all_bundles: Vec<(String, Bundle)> = Vec::new();
+    // Collect all bundles with the index of their source
+    let mut sources: Vec<Box<dyn crate::source::Source>> = Vec::new();
+    let mut all_bundles: Vec<(usize, Bundle)> = Vec::new();
 
-    for source in &sources {
-        match source.list_bundles() {
+    for (source, listed) in config.list_all_bundles() {
+        match listed {
             Ok(bundles) => {
                 for bundle in bundles {
-                    all_bundles.push((source.display_path(), bundle));
+                    all_bundles.push((sources.len(), bundle));
                 }
+                sources.push(source);
             }
             Err(e) => {
                 eprintln!(
@@ -331,66 +761,79 @@ fn browse_bundles(config: &Config) -> Result<()> {
         return Ok(());
     }
 
+    // `author:` and `tag:` filters match only their field, which fuzzy search can't do
+    let mut filter = String::new();
+
     loop {
         println!();
-        println!("{}", "Available Bundles (type to search)".bold());
+        if filter.is_empty() {
+            println!("{}", "Available Bundles (type to search)".bold());
+        } else {
+            println!("{} {}", "Available Bundles matching".bold(), filter.cyan());
+        }
         println!();
 
-        // Build display items with searchable content
-        // Format: "name | description | author | counts | source"
-        let items: Vec<String> = all_bundles
+        let shown: Vec<&(usize, Bundle)> = all_bundles
             .iter()
-            .map(|(source, bundle)| {
-                let desc = bundle
-                    .meta
-                    .description
-                    .as_ref()
-                    .map(|d| {
-                        // Truncate long descriptions
-                        if d.len() > 40 {
-                            format!("{}...", &d[..37])
-                        } else {
-                            d.clone()
-                        }
-                    })
-                    .unwrap_or_default();
-                let author = bundle
-                    .meta
-                    .author
-                    .as_ref()
-                    .map(|a| format!("by {}", a))
-                    .unwrap_or_default();
-                let counts = format!(
-                    "{}s {}a {}c",
-                    bundle.skills.len(),
-                    bundle.agents.len(),
-                    bundle.commands.len()
-                );
-                // Include searchable content (name, author, description, skill names)
-                let search_hint = bundle.search_string();
-                if desc.is_empty() {
-                    format!(
-                        "{:<20} {:<15} {} {} [{}]",
-                        bundle.name,
-                        author.dimmed(),
-                        counts.dimmed(),
-                        format!("({})", source).dimmed(),
-                        search_hint.dimmed()
-                    )
-                } else {
-                    format!(
-                        "{:<20} {} {:<15} {} {} [{}]",
-                        bundle.name,
-                        desc.dimmed(),
-                        author.dimmed(),
-                        counts.dimmed(),
-                        format!("({})", source).dimmed(),
-                        search_hint.dimmed()
-                    )
-                }
-            })
+            .filter(|(_, bundle)| filter.is_empty() || bundle.relevance(&filter).is_some())
             .collect();
 
+        // Build display items with searchable content
+        // Format: "name | description | author | counts | source"
+        let mut items: Vec<String> = vec![if filter.is_empty() {
+            "🔎 Filter by author:name or tag:name...".to_string()
+        } else {
+            format!("🔎 Change filter ({} of {} bundles shown)", shown.len(), all_bundles.len())
+        }];
+        items.extend(shown.iter().map(|(source_idx, bundle)| {
+            let source = sources[*source_idx].display_path();
+            let name = match &bundle.meta.version {
+                Some(version) => format!("{} {}", bundle.name, version),
+                None => bundle.name.clone(),
+            };
+            let desc = bundle
+                .meta
+                .description
+                .as_ref()
+                // Truncate long descriptions
+                .map(|d| ellipsize(d, 40))
+                .unwrap_or_default();
+            let author = bundle
+                .meta
+                .author
+                .as_ref()
+                .map(|a| format!("by {}", a))
+                .unwrap_or_default();
+            let counts = format!(
+                "{}s {}a {}c",
+                bundle.skills.len(),
+                bundle.agents.len(),
+                bundle.commands.len()
+            );
+            // Include searchable content (name, author, description, skill names)
+            let search_hint = bundle.search_string();
+            if desc.is_empty() {
+                format!(
+                    "{:<20} {:<15} {} {} [{}]",
+                    name,
+                    author.dimmed(),
+                    counts.dimmed(),
+                    format!("({})", source).dimmed(),
+                    search_hint.dimmed()
+                )
+            } else {
+                format!(
+                    "{:<20} {} {:<15} {} {} [{}]",
+                    name,
+                    desc.dimmed(),
+                    author.dimmed(),
+                    counts.dimmed(),
+                    format!("({})", source).dimmed(),
+                    search_hint.dimmed()
+                )
+            }
+        }));
+
         let sel = FuzzySelect::with_theme(&ColorfulTheme::default())
             .with_prompt("Select a bundle (type to filter, Esc to quit)")
             .items(&items)
@@ -399,9 +842,18 @@ fn browse_bundles(config: &Config) -> Result<()> {
             .interact_opt()?;
 
         match sel {
-            Some(idx) if idx < all_bundles.len() => {
-                let (_, bundle) = &all_bundles[idx];
-                show_bundle_details(bundle)?;
+            Some(0) => {
+                filter = Input::<String>::with_theme(&ColorfulTheme::default())
+                    .with_prompt("Filter (e.g. author:alice tag:git; empty to clear)")
+                    .with_initial_text(filter.clone())
+                    .allow_empty(true)
+                    .interact_text()?
+                    .trim()
+                    .to_string();
+            }
+            Some(idx) if idx < items.len() => {
+                let (source_idx, bundle) = shown[idx - 1];
+                show_bundle_details(config, sources[*source_idx].as_ref(), bundle, install)?;
             }
             _ => break,
         }
@@ -410,12 +862,99 @@ fn browse_bundles(config: &Config) -> Result<()> {
     Ok(())
 }
 
-fn show_bundle_details(bundle: &crate::bundle::Bundle) -> Result<()> {
-    use dialoguer::{theme::ColorfulTheme, Select};
+fn list_items(config: &Config, bundle_ref: &str, json: bool) -> Result<()> {
+    let reference = config.resolve_alias(bundle_ref).unwrap_or(bundle_ref);
+    let (_, bundle) = config
+        .find_bundle_ref(reference)?
+        .ok_or_else(|| anyhow::anyhow!("Bundle not found: {}", bundle_ref))?;
+    print!("{}", format_items(&bundle.items(), json)?);
+    Ok(())
+}
+
+/// Render bundle items as `type name` lines, or as a JSON array
+fn format_items(items: &[crate::bundle::BundleItem], json: bool) -> Result<String> {
+    if json {
+        return Ok(format!("{}\n", serde_json::to_string_pretty(items)?));
+    }
+    Ok(items
+        .iter()
+        .map(|item| format!("{} {}\n", item.skill_type.as_str(), item.name))
+        .collect())
+}
+
+/// Print the bundles matching `query`, best matches first, limited to those
+/// changed since `since` when given
+fn search_bundles(config: &Config, query: Option<&str>, since: Option<&str>) -> Result<()> {
+    let mut matches: Vec<(String, crate::bundle::Bundle)> = Vec::new();
+    for (source, listed) in config.list_all_bundles() {
+        let listed = match since {
+            Some(since) => listed.and_then(|mut bundles| {
+                let changed = source.changed_since(since)?.unwrap_or_default();
+                bundles.retain(|b| changed.contains(&b.name));
+                Ok(bundles)
+            }),
+            None => listed,
+        };
+        match listed {
+            Ok(bundles) => {
+                matches.extend(bundles.into_iter().map(|b| (source.display_path(), b)));
+            }
+            Err(e) => {
+                eprintln!(
+                    "  {} {} - {}",
+                    "Warning:".yellow(),
+                    source.display_path(),
+                    e
+                );
+            }
+        }
+    }
+
+    crate::bundle::sort_by_relevance(&mut matches, query);
+
+    let since = since.map(|s| format!(" changed since {}", s)).unwrap_or_default();
+    if matches.is_empty() {
+        match query {
+            Some(query) => println!("{} {}{}", "No bundles match".yellow(), query, since),
+            None => println!("{}", format!("No bundles{}", since).yellow()),
+        }
+        return Ok(());
+    }
+
+    match query {
+        Some(query) => println!("{} {}{}", "Bundles matching".bold(), query.cyan(), since.bold()),
+        None => println!("{}", format!("Bundles{}", since).bold()),
+    }
+    println!();
+    for (source, bundle) in &matches {
+        match &bundle.meta.description {
+            Some(desc) => println!("  {} - {}", bundle.name.cyan(), desc.dimmed()),
+            None => println!("  {}", bundle.name.cyan()),
+        }
+        println!("    {}", format!("({})", source).dimmed());
+    }
+    println!();
+
+    Ok(())
+}
+
+fn show_bundle_details(
+    config: &Config,
+    source: &dyn crate::source::Source,
+    bundle: &crate::bundle::Bundle,
+    install: &BrowseInstall,
+) -> Result<()> {
+    use dialoguer::{theme::ColorfulTheme, MultiSelect, Select};
 
     loop {
         println!();
         println!("{} {}", "Bundle:".bold(), bundle.name.cyan());
+        if let Some(desc) = &bundle.meta.description {
+            println!("  {}", desc.dimmed());
+        }
+        for (label, value) in bundle.meta.details() {
+            println!("  {:<11} {}", format!("{}:", label).dimmed(), value);
+        }
         println!();
 
         let mut items: Vec<String> = Vec::new();
@@ -435,13 +974,16 @@ fn show_bundle_details(bundle: &crate::bundle::Bundle) -> Result<()> {
                 file_paths.push(None); // section header
 
                 for file in files {
-                    let preview = get_file_preview(&file.path);
+                    let preview = get_file_preview(source, &file.path);
                     items.push(format!("  {} {}", file.name, preview.dimmed()));
                     file_paths.push(Some(file.path.clone()));
                 }
             }
         }
 
+        let install_item = items.len();
+        items.push(format!("⤓ Install to {}", install.tool.name()));
+        file_paths.push(None);
         items.push("← Back".to_string());
         file_paths.push(None);
 
@@ -455,6 +997,33 @@ fn show_bundle_details(bundle: &crate::bundle::Bundle) -> Result<()> {
             break;
         }
 
+        if sel == install_item {
+            // Offer the bundle's types, checked when the command line selected them
+            let available = bundle.types();
+            let labels: Vec<String> = available
+                .iter()
+                .map(|t| format!("{} ({})", t.dir_name(), bundle.files_of_type(*t).len()))
+                .collect();
+            let checked: Vec<bool> = available.iter().map(|t| install.types.contains(t)).collect();
+            let chosen = MultiSelect::with_theme(&ColorfulTheme::default())
+                .with_prompt("Types to install (space to toggle, enter to confirm)")
+                .items(&labels)
+                .defaults(&checked)
+                .interact()?;
+            let chosen: Vec<SkillType> = chosen.into_iter().map(|i| available[i]).collect();
+            if chosen.is_empty() {
+                println!("{}", "Nothing selected.".yellow());
+                continue;
+            }
+
+            println!();
+            let bundle_ref = browsed_bundle_ref(config, source, &bundle.name);
+            if let Err(e) = install_browsed_bundle(config, &bundle_ref, &chosen, install) {
+                println!("{} {:#}", "Error:".red(), e);
+            }
+            continue;
+        }
+
         let path = match &file_paths[sel] {
             Some(p) => p,
             None => continue, // section header
@@ -463,7 +1032,7 @@ fn show_bundle_details(bundle: &crate::bundle::Bundle) -> Result<()> {
         // Show file contents
         println!();
         println!("{}", "─".repeat(60).dimmed());
-        if let Ok(content) = std::fs::read_to_string(path) {
+        if let Ok(content) = source.read_file(path) {
             for line in content.lines().take(40) {
                 println!("{}", line);
             }
@@ -482,8 +1051,37 @@ fn show_bundle_details(bundle: &crate::bundle::Bundle) -> Result<()> {
     Ok(())
 }
 
-fn get_file_preview(path: &std::path::PathBuf) -> String {
-    if let Ok(content) = std::fs::read_to_string(path) {
+/// How to name `bundle_name` of the browsed `source` for an install: as
+/// `source/bundle` when the source has a name, so the browsed copy is installed
+/// even if an earlier source has a bundle of the same name
+fn browsed_bundle_ref(
+    config: &Config,
+    source: &dyn crate::source::Source,
+    bundle_name: &str,
+) -> String {
+    let display = source.display_path();
+    config
+        .source_configs()
+        .iter()
+        .filter_map(|s| Some((s.name()?, config.source_for(s).ok()?)))
+        .find(|(_, s)| s.display_path() == display)
+        .map(|(name, _)| format!("{}/{}", name, bundle_name))
+        .unwrap_or_else(|| bundle_name.to_string())
+}
+
+/// Install a bundle picked in the `skm list` browser, limited to the `chosen` types
+fn install_browsed_bundle(
+    config: &Config,
+    bundle_ref: &str,
+    chosen: &[SkillType],
+    install: &BrowseInstall,
+) -> Result<()> {
+    do_install(config, bundle_ref, install.tool, install.target_dir, chosen, install.opts)
+}
+
+/// First meaningful line of a bundle file, read through its source
+fn get_file_preview(source: &dyn crate::source::Source, path: &Path) -> String {
+    if let Ok(content) = source.read_file(path) {
         content
             .lines()
             .filter(|line| !line.trim().is_empty())
@@ -492,11 +1090,7 @@ fn get_file_preview(path: &std::path::PathBuf) -> String {
             .take(1)
             .map(|line| {
                 let trimmed = line.trim_start_matches('#').trim();
-                if trimmed.len() > 50 {
-                    format!("- {}...", &trimmed[..47])
-                } else {
-                    format!("- {}", trimmed)
-                }
+                format!("- {}", ellipsize(trimmed, 50))
             })
             .next()
             .unwrap_or_default()
@@ -505,6 +1099,83 @@ fn get_file_preview(path: &std::path::PathBuf) -> String {
     }
 }
 
+fn lint_bundle(config: &Config, bundle_ref: &str, tool: &Tool, types: &[SkillType]) -> Result<()> {
+    let (_, bundle) = config
+        .find_bundle_ref(bundle_ref)?
+        .ok_or_else(|| anyhow::anyhow!("Bundle not found: {}", bundle_ref))?;
+
+    let results = lint::lint_bundle(&bundle, tool, types)?;
+    lint::print_results(&bundle.name, tool, &results);
+
+    let failed = results.iter().filter(|r| !r.is_ok()).count();
+    if failed > 0 {
+        anyhow::bail!("{} of {} file(s) failed lint", failed, results.len());
+    }
+    println!("{} {} file(s) passed", "✓".green(), results.len());
+    Ok(())
+}
+
+/// `skm validate`: check a configured source (by name), a directory, or the
+/// first local source, and fail if anything is wrong
+fn validate_source(config: &Config, source: Option<&str>) -> Result<()> {
+    let (source, root) = match source {
+        None => {
+            let root = first_local_source(config)?;
+            let source: Box<dyn crate::source::Source> =
+                Box::new(crate::source::LocalSource::new(root.clone()));
+            (source, Some(root))
+        }
+        Some(name) => match config.source_configs().iter().find(|s| s.name() == Some(name)) {
+            Some(source_config) => {
+                let root = match source_config {
+                    SourceConfig::Local { path, .. } => Some(config::expand_tilde(path)),
+                    SourceConfig::Git { .. } | SourceConfig::Archive { .. } => None,
+                };
+                (source_config.to_source(config.git_mirror().as_deref())?, root)
+            }
+            None if Path::new(name).is_dir() => {
+                let root = PathBuf::from(name);
+                let source: Box<dyn crate::source::Source> =
+                    Box::new(crate::source::LocalSource::new(root.clone()));
+                (source, Some(root))
+            }
+            None => anyhow::bail!("No source named `{}` and no directory at that path", name),
+        },
+    };
+
+    let problems = validate::validate_source(source.as_ref())?;
+    validate::print_problems(source.as_ref(), root.as_deref(), &problems);
+    if !problems.is_empty() {
+        anyhow::bail!("{} problem(s) found in {}", problems.len(), source.display_path());
+    }
+    println!("{} {} is valid", "✓".green(), source.display_path());
+    Ok(())
+}
+
+fn render_bundle(
+    config: &Config,
+    bundle_ref: &str,
+    tool: &Tool,
+    types: &[SkillType],
+    out: &Path,
+    opts: &InstallOptions,
+) -> Result<()> {
+    let (_, bundle) = config
+        .find_bundle_ref(bundle_ref)?
+        .ok_or_else(|| anyhow::anyhow!("Bundle not found: {}", bundle_ref))?;
+
+    let written = render::render_bundle(&bundle, tool, types, out, opts)?;
+    println!(
+        "{} {} file(s) of {} for {} to {}",
+        "Rendered".green(),
+        written.len(),
+        bundle.name.cyan(),
+        tool.name(),
+        out.display()
+    );
+    Ok(())
+}
+
 fn sources_interactive() -> Result<()> {
     use dialoguer::{theme::ColorfulTheme, Input, Select};
 
@@ -523,6 +1194,7 @@ fn sources_interactive() -> Result<()> {
                 let type_label = match source {
                     SourceConfig::Local { .. } => "local",
                     SourceConfig::Git { .. } => "git",
+                    SourceConfig::Archive { .. } => "archive",
                 };
                 let priority = format!("[{}]", i + 1).dimmed();
                 let name_display = source
@@ -612,8 +1284,9 @@ fn sources_interactive() -> Result<()> {
     if !git_sources.is_empty() {
         println!();
         println!("{}", "Updating git sources...".dimmed());
-        for source in git_sources {
-            match source.pull() {
+        let results = crate::source::GitSource::pull_all(&git_sources, config.jobs());
+        for (source, result) in git_sources.iter().zip(results) {
+            match result {
                 Ok(true) => {
                     println!("  {} {}", "Updated:".green(), source.url());
                 }
@@ -639,9 +1312,13 @@ fn sources_list(config: &Config) -> Result<()> {
         println!("Add a source with: skm sources add <path>");
     } else {
         for (i, source) in sources.iter().enumerate() {
-            let type_label = match source {
-                SourceConfig::Local { .. } => "local",
-                SourceConfig::Git { .. } => "git",
+            let type_label = match (source, config.is_dropin(source)) {
+                (SourceConfig::Local { .. }, false) => "local",
+                (SourceConfig::Git { .. }, false) => "git",
+                (SourceConfig::Archive { .. }, false) => "archive",
+                (SourceConfig::Local { .. }, true) => "local, from sources.d",
+                (SourceConfig::Git { .. }, true) => "git, from sources.d",
+                (SourceConfig::Archive { .. }, true) => "archive, from sources.d",
             };
             let name_display = source
                 .name()
@@ -655,6 +1332,7 @@ fn sources_list(config: &Config) -> Result<()> {
                 format!("({})", type_label).dimmed()
             );
         }
+        warn_overlapping_sources(config);
     }
     println!();
 
@@ -664,33 +1342,27 @@ fn sources_list(config: &Config) -> Result<()> {
 fn sources_add(name: Option<String>, path: String) -> Result<()> {
     let mut config = Config::load_or_default()?;
 
-    // Determine if this is a git URL or local path
-    let source =
-        if path.starts_with("https://") || path.starts_with("git@") || path.ends_with(".git") {
-            SourceConfig::Git {
-                url: path.clone(),
-                name,
-            }
-        } else {
+    // Determine if this is an archive URL, git URL or local path
+    let source = match SourceConfig::from_location(path.clone(), name) {
+        SourceConfig::Local { path, name, .. } => {
             // Normalize local path
             let normalized = if path.starts_with("~/") || path.starts_with('/') {
-                path.clone()
+                path
             } else {
                 // Make relative path absolute
-                let cwd = std::env::current_dir()?;
+                let cwd = config::working_dir("Pass the source as an absolute path instead")?;
                 cwd.join(&path).to_string_lossy().to_string()
             };
-            SourceConfig::Local {
-                path: normalized,
-                name,
-            }
-        };
+            SourceConfig::local(normalized, name)
+        }
+        remote => remote,
+    };
 
     // Check if path exists for local sources
     if let SourceConfig::Local { ref path, .. } = source {
-        let expanded = if path.starts_with("~/") {
+        let expanded = if let Some(rest) = path.strip_prefix("~/") {
             let home = std::env::var("HOME")?;
-            PathBuf::from(format!("{}/{}", home, &path[2..]))
+            PathBuf::from(format!("{}/{}", home, rest))
         } else {
             PathBuf::from(path)
         };
@@ -700,14 +1372,51 @@ fn sources_add(name: Option<String>, path: String) -> Result<()> {
         }
     }
 
-    config.add_source(source);
+    config.add_source(source)?;
     config.save()?;
+    warn_overlapping_sources(&config);
 
     println!("{} {}", "Added source:".green(), path);
 
     Ok(())
 }
 
+fn config_check() -> Result<()> {
+    if let Some(inline) = Config::inline_env() {
+        return report_config_issues("$SKM_CONFIG", &Config::check(&inline));
+    }
+
+    let config_path = Config::config_path()?;
+
+    if !config_path.exists() {
+        println!(
+            "{} No config file at {} (built-in defaults are used)",
+            "Note:".yellow(),
+            config_path.display()
+        );
+        return Ok(());
+    }
+
+    let content = std::fs::read_to_string(&config_path)?;
+    report_config_issues(&config_path.display().to_string(), &Config::check(&content))
+}
+
+fn report_config_issues(origin: &str, issues: &[String]) -> Result<()> {
+    if issues.is_empty() {
+        println!("{} {}", "Config OK:".green(), origin);
+        return Ok(());
+    }
+
+    println!("{} {}", "Config problems in".bold(), origin);
+    println!();
+    for issue in issues {
+        println!("  {} {}", "✗".red(), issue);
+    }
+    println!();
+
+    anyhow::bail!("{} problem(s) found in config", issues.len())
+}
+
 fn sources_remove(path: String) -> Result<()> {
     let mut config = Config::load_or_default()?;
 
@@ -721,10 +1430,292 @@ fn sources_remove(path: String) -> Result<()> {
     Ok(())
 }
 
-fn update_sources(config: &Config) -> Result<()> {
-    let git_sources = config.git_sources();
+fn write_lock(config: &Config, target_dir: &Path) -> Result<()> {
+    let lock = lock::LockFile::from_manifests(config, target_dir);
 
-    if git_sources.is_empty() {
+    if lock.bundles.is_empty() {
+        println!("{}", "No installed bundles recorded; nothing to lock.".yellow());
+        println!("Install bundles with: skm <bundle>");
+        return Ok(());
+    }
+
+    lock.save(target_dir)?;
+    for entry in &lock.bundles {
+        let pin = entry
+            .commit
+            .as_deref()
+            .map(|c| format!(" @ {}", &c[..c.len().min(12)]))
+            .unwrap_or_default();
+        println!(
+            "  {} {} {}",
+            entry.name.cyan(),
+            format!("({})", entry.tool).dimmed(),
+            format!("{}{}", entry.source, pin).dimmed()
+        );
+    }
+    println!();
+    println!(
+        "{} Locked {} bundle(s) in {}",
+        "Done!".green(),
+        lock.bundles.len(),
+        lock::LockFile::path_for(target_dir).display()
+    );
+
+    Ok(())
+}
+
+fn sync_from_lock(
+    config: &Config,
+    target_dir: &Path,
+    types: &[SkillType],
+    opts: &InstallOptions,
+) -> Result<()> {
+    let lock = lock::LockFile::load(target_dir)?;
+
+    if lock.bundles.is_empty() {
+        println!("{}", "skm.lock is empty; nothing to sync.".yellow());
+        return Ok(());
+    }
+
+    let installed = lock::sync(&lock, target_dir, types, opts, config)?;
+
+    println!();
+    println!(
+        "{} Synced {} bundle(s) from {}",
+        "Done!".green(),
+        installed.len(),
+        lock::LOCK_FILE
+    );
+
+    Ok(())
+}
+
+/// Warn about git sources sharing a cache and local sources nested in each other
+fn warn_overlapping_sources(config: &Config) {
+    for problem in config.cache_collisions().into_iter().chain(config.nested_sources()) {
+        println!("{} {}", "Warning:".yellow(), problem);
+    }
+}
+
+fn alias_list(config: &Config) {
+    println!("{}", "Aliases:".bold());
+    println!();
+
+    if config.alias.is_empty() {
+        println!("  {}", "(none)".dimmed());
+        println!();
+        println!("Add an alias with: skm alias add <name> <source/bundle>");
+    } else {
+        for (name, target) in &config.alias {
+            println!("  {} -> {}", name.cyan(), target);
+        }
+    }
+    println!();
+}
+
+fn alias_add(name: &str, target: &str) -> Result<()> {
+    let mut config = Config::load_or_default()?;
+
+    let previous = config.add_alias(name, target)?;
+    config.save()?;
+
+    match previous {
+        Some(old) if old != target => println!(
+            "{} {} -> {} (was {})",
+            "Updated alias:".green(),
+            name,
+            target,
+            old
+        ),
+        _ => println!("{} {} -> {}", "Added alias:".green(), name, target),
+    }
+
+    Ok(())
+}
+
+fn alias_remove(name: &str) -> Result<()> {
+    let mut config = Config::load_or_default()?;
+
+    if config.remove_alias(name) {
+        config.save()?;
+        println!("{} {}", "Removed alias:".green(), name);
+    } else {
+        println!("{} Alias not found: {}", "Error:".red(), name);
+    }
+
+    Ok(())
+}
+
+/// `skm diff`: print how the installed files of `bundle` differ from its source
+fn print_bundle_diff(
+    config: &Config,
+    bundle: &str,
+    tool: &Tool,
+    target_dir: &Path,
+    types: &[SkillType],
+    opts: &InstallOptions,
+) -> Result<()> {
+    let diff = diff::bundle_diff(config, bundle, tool, target_dir, types, opts)?;
+    if diff.is_empty() {
+        println!("{} {} matches its source for {}", "✓".green(), bundle.cyan(), tool.name());
+        return Ok(());
+    }
+
+    for (_, text) in &diff.changed {
+        for line in text.lines() {
+            if line.starts_with("---") || line.starts_with("+++") {
+                println!("{}", line.bold());
+            } else if line.starts_with("@@") {
+                println!("{}", line.cyan());
+            } else if line.starts_with('-') {
+                println!("{}", line.red());
+            } else if line.starts_with('+') {
+                println!("{}", line.green());
+            } else {
+                println!("{}", line);
+            }
+        }
+        println!();
+    }
+    for path in &diff.only_in_source {
+        println!("{} {}", "Only in source:".yellow(), path.display());
+    }
+    for path in &diff.only_installed {
+        println!("{} {}", "Only installed:".yellow(), path.display());
+    }
+    if !diff.only_in_source.is_empty() || !diff.only_installed.is_empty() {
+        println!();
+    }
+    println!(
+        "{} file(s) differ, {} only in source, {} only installed",
+        diff.changed.len(),
+        diff.only_in_source.len(),
+        diff.only_installed.len()
+    );
+    Ok(())
+}
+
+/// `skm verify`: one line per file the bundle installs, failing unless all are OK
+fn verify_bundle(
+    config: &Config,
+    bundle: &str,
+    tool: &Tool,
+    target_dir: &Path,
+    types: &[SkillType],
+    opts: &InstallOptions,
+) -> Result<()> {
+    use crate::diff::FileStatus;
+
+    let statuses = diff::verify_bundle(config, bundle, tool, target_dir, types, opts)?;
+    println!("{} {} for {}", "Verifying".bold(), bundle.cyan(), tool.name());
+    println!();
+    for (path, status) in &statuses {
+        let label = format!("{:<9}", status.label());
+        let label = match status {
+            FileStatus::Ok => label.green(),
+            FileStatus::Modified => label.yellow(),
+            FileStatus::Missing => label.red(),
+        };
+        println!("  {} {}", label, path.display());
+    }
+    println!();
+
+    let bad = statuses.iter().filter(|(_, s)| *s != FileStatus::Ok).count();
+    if bad > 0 {
+        anyhow::bail!(
+            "{} of {} file(s) don't match the source; run `skm diff {}` for details",
+            bad,
+            statuses.len(),
+            bundle
+        );
+    }
+    println!("{} {} file(s) match the source", "✓".green(), statuses.len());
+    Ok(())
+}
+
+/// Parse a `--tool`-style argument, naming the valid ids on a typo
+fn parse_tool_id(id: &str) -> Result<Tool> {
+    Tool::from_id(id).ok_or_else(|| {
+        let ids: Vec<&str> = Tool::ALL.iter().map(|t| t.id()).collect();
+        anyhow::anyhow!("Unknown tool `{}` (expected one of: {})", id, ids.join(", "))
+    })
+}
+
+/// `skm here --tool`: `all` means no filter, anything else must be a known tool
+fn here_tool_filter(tool: Option<&str>) -> Result<Option<String>> {
+    match tool.map(str::to_lowercase).as_deref() {
+        None | Some("all") => Ok(None),
+        Some(id) => parse_tool_id(id).map(|t| Some(t.id().to_string())),
+    }
+}
+
+/// What `skm update` works on
+#[derive(Debug, PartialEq)]
+enum UpdateTarget {
+    /// Every git source and installed bundle
+    All,
+    /// A source by name: pulled if it's a git source, then its installed bundles refreshed
+    Source { name: String, display_path: String },
+    /// A bundle: the git source it comes from is pulled, then the bundle refreshed
+    Bundle { name: String, display_path: String },
+}
+
+impl UpdateTarget {
+    /// Resolve `skm update [name]`, trying source names before bundle names
+    fn resolve(config: &Config, name: Option<&str>) -> Result<Self> {
+        let Some(name) = name else {
+            return Ok(UpdateTarget::All);
+        };
+        if let Some((source, _)) = config.find_source_by_name(name) {
+            return Ok(UpdateTarget::Source {
+                name: name.to_string(),
+                display_path: source.display_path(),
+            });
+        }
+        if let Some((source, bundle)) = config.find_bundle(name)? {
+            return Ok(UpdateTarget::Bundle {
+                name: bundle.name,
+                display_path: source.display_path(),
+            });
+        }
+
+        let sources: Vec<&str> = config.source_configs().iter().filter_map(|s| s.name()).collect();
+        let sources = if sources.is_empty() {
+            "(none named)".to_string()
+        } else {
+            sources.join(", ")
+        };
+        anyhow::bail!(
+            "No source or bundle named `{}`\n  Sources: {}\n  Bundles: see `skm list`",
+            name,
+            sources
+        )
+    }
+
+    /// Display path of the one source to pull, or `None` to pull every git source
+    fn source(&self) -> Option<&str> {
+        match self {
+            UpdateTarget::All => None,
+            UpdateTarget::Source { display_path, .. }
+            | UpdateTarget::Bundle { display_path, .. } => Some(display_path),
+        }
+    }
+}
+
+/// Pull every git source, or only the one shown as `only` (see `UpdateTarget::source`)
+fn update_sources(config: &Config, only: Option<&str>) -> Result<()> {
+    warn_overlapping_sources(config);
+
+    let mut git_sources = config.git_sources();
+    if let Some(only) = only {
+        git_sources.retain(|s| s.url() == only);
+        if git_sources.is_empty() {
+            println!("{} {} is not a git source; nothing to pull", "·".dimmed(), only);
+            return Ok(());
+        }
+    }
+
+    if git_sources.is_empty() {
         println!("{}", "No git sources configured.".yellow());
         println!("Add a git source with: skm sources add <git-url>");
         return Ok(());
@@ -737,10 +1728,12 @@ fn update_sources(config: &Config) -> Result<()> {
     let mut already_current = 0;
     let mut errors = 0;
 
-    for source in git_sources {
+    // Pulls run in parallel; results are printed afterwards so they stay in config order
+    let results = crate::source::GitSource::pull_all(&git_sources, config.jobs());
+    for (source, result) in git_sources.iter().zip(results) {
         print!("  {} {}... ", "Updating".cyan(), source.url());
 
-        match source.pull() {
+        match result {
             Ok(true) => {
                 println!("{}", "updated".green());
                 updated += 1;
@@ -777,8 +1770,10 @@ fn update_sources(config: &Config) -> Result<()> {
 fn refresh_installed_skills(
     config: &Config,
     tool: &Tool,
-    target_dir: &PathBuf,
+    target_dir: &Path,
     types: &[SkillType],
+    opts: &InstallOptions,
+    target: &UpdateTarget,
 ) -> Result<()> {
     use crate::discover::{discover_installed, filter_by_tool};
     use std::collections::HashSet;
@@ -792,13 +1787,7 @@ fn refresh_installed_skills(
         manifest.bundle_names().into_iter().map(|s| s.to_string()).collect()
     } else {
         // Legacy fallback: discover from filesystem
-        let tool_name = match tool {
-            Tool::Claude => "claude",
-            Tool::OpenCode => "opencode",
-            Tool::Cursor => "cursor",
-            Tool::Codex => "codex",
-        };
-        let skills = filter_by_tool(discover_installed(target_dir)?, tool_name);
+        let skills = filter_by_tool(discover_installed(target_dir)?, tool.id());
 
         if skills.is_empty() {
             println!();
@@ -808,26 +1797,72 @@ fn refresh_installed_skills(
 
         let mut names: HashSet<String> = HashSet::new();
         for skill in &skills {
-            if let Some(ref bundle) = skill.bundle {
-                names.insert(bundle.clone());
-            } else {
-                names.insert(skill.name.clone());
-            }
+            let name = skill.bundle.as_ref().unwrap_or(&skill.name);
+            // Installed names start with the configured prefix; sources don't
+            let name = match &opts.install_prefix {
+                Some(prefix) => name.strip_prefix(&format!("{}-", prefix)).unwrap_or(name),
+                None => name,
+            };
+            names.insert(name.to_string());
         }
         names
     };
 
+    // `skm update <name>` refreshes only what's installed from that source or bundle
+    let bundles_to_refresh: HashSet<String> = match target {
+        UpdateTarget::All => bundles_to_refresh,
+        UpdateTarget::Source { name, .. } => {
+            // Without a manifest, fall back to what the source currently offers
+            let from_source: Vec<String> = if use_manifest {
+                bundles_from_source(config, name, target_dir, Some(tool.id()))
+            } else {
+                config
+                    .find_source_by_name(name)
+                    .and_then(|(source, _)| source.list_bundles().ok())
+                    .map(|bundles| bundles.into_iter().map(|b| b.name).collect())
+                    .unwrap_or_default()
+            };
+            bundles_to_refresh.into_iter().filter(|b| from_source.contains(b)).collect()
+        }
+        UpdateTarget::Bundle { name, .. } => {
+            bundles_to_refresh.into_iter().filter(|b| b == name).collect()
+        }
+    };
+
+    // `--since` leaves out bundles whose source hasn't touched them
+    let bundles_to_refresh: HashSet<String> = match &opts.since {
+        Some(since) => {
+            let changed = bundles_changed_since(config, since);
+            bundles_to_refresh
+                .into_iter()
+                .filter(|name| {
+                    // Aliases are installed under their own name
+                    let bundle = config.resolve_alias(name).map_or(name.as_str(), |target| {
+                        target.split_once('/').map_or(target, |(_, bundle)| bundle)
+                    });
+                    changed.contains(bundle)
+                })
+                .collect()
+        }
+        None => bundles_to_refresh,
+    };
+
     if bundles_to_refresh.is_empty() {
         println!();
         println!("{}", "No bundles to refresh.".yellow());
         return Ok(());
     }
 
+    warn_if_hook_blocked(config, opts);
+    let write_opts = opts.write_options();
+    let snapshot = ContentSnapshot::take(tool, target_dir);
+
     println!();
     println!("{}", "Refreshing installed skills...".bold());
     println!();
 
     let mut refreshed = 0;
+    let mut unchanged = 0;
     let mut not_found = 0;
     let mut errors = 0;
     // Track which actual bundle names have been refreshed to avoid duplicates
@@ -837,9 +1872,22 @@ fn refresh_installed_skills(
     for bundle_name in &bundles_to_refresh {
         print!("  {} {}... ", "Refreshing".cyan(), bundle_name);
 
+        // Aliased bundles are looked up by their target but keep the alias name
+        let lookup = match config.resolve_alias(bundle_name) {
+            Some(target) => config.find_bundle_ref(target).map(|found| {
+                found.map(|(source, mut bundle)| {
+                    bundle.name = bundle_name.clone();
+                    (source, bundle)
+                })
+            }),
+            None => config.find_bundle(bundle_name),
+        };
+
         // Try to find this bundle in sources (exact match first, then prefix match)
-        let found = match config.find_bundle(bundle_name) {
-            Ok(Some((source, bundle))) => Some((source.display_path(), bundle)),
+        let found = match lookup {
+            Ok(Some((source, bundle))) => {
+                Some((source.display_path(), source.revision(), bundle))
+            }
             Ok(None) => {
                 // Legacy fallback: skills/rules use {bundle}-{name} folder format,
                 // so the discovered "bundle name" may actually be a combined name.
@@ -847,13 +1895,13 @@ fn refresh_installed_skills(
                     Ok(Some(bundle)) => {
                         // We don't have the source display path from prefix match,
                         // but we can look it up
-                        let source_display = config
+                        let (source_display, commit) = config
                             .find_bundle(&bundle.name)
                             .ok()
                             .flatten()
-                            .map(|(s, _)| s.display_path())
+                            .map(|(s, _)| (s.display_path(), s.revision()))
                             .unwrap_or_default();
-                        Some((source_display, bundle))
+                        Some((source_display, commit, bundle))
                     }
                     Ok(None) => None,
                     Err(_) => None,
@@ -867,7 +1915,7 @@ fn refresh_installed_skills(
         };
 
         match found {
-            Some((source_display, bundle)) => {
+            Some((source_display, commit, bundle)) => {
                 // Skip if we already refreshed this actual bundle
                 if already_refreshed.contains(&bundle.name) {
                     println!("{} (via {})", "already refreshed".dimmed(), bundle.name);
@@ -875,25 +1923,45 @@ fn refresh_installed_skills(
                 }
                 already_refreshed.insert(bundle.name.clone());
 
+                // Versioned bundles are only refreshed when their version goes up
+                let recorded = manifest.version_of(&bundle.name).map(str::to_string);
+                let current = bundle.meta.version.as_deref();
+                if !opts.force && !version_needs_refresh(recorded.as_deref(), current) {
+                    println!("{} ({})", "unchanged".dimmed(), current.unwrap_or_default());
+                    unchanged += 1;
+                    continue;
+                }
+
                 // Re-install this bundle
-                let mut count = 0;
-                for skill_type in types {
-                    let files = bundle.files_of_type(*skill_type);
-                    for file in files {
-                        match tool.write_file(target_dir, &bundle.name, file) {
-                            Ok(_) => count += 1,
-                            Err(e) => {
-                                println!("{}: {}", "error".red(), e);
-                                errors += 1;
-                            }
-                        }
-                    }
+                let outcome =
+                    refresh_bundle(&bundle, tool, target_dir, types, &write_opts, &snapshot);
+                for e in &outcome.errors {
+                    println!("{}: {}", "error".red(), e);
                 }
+                errors += outcome.errors.len();
+                let count = outcome.written;
                 if count > 0 {
-                    println!("{} ({} files)", "done".green(), count);
-                    refreshed += 1;
+                    if outcome.changed {
+                        println!("{} ({} files)", "updated".green(), count);
+                        refreshed += 1;
+                    } else {
+                        println!("{}", "unchanged".dimmed());
+                        unchanged += 1;
+                    }
+                    if let (Some(old), Some(new)) = (recorded.as_deref(), current) {
+                        if old != new {
+                            println!("    bundle {}: {} → {}", bundle.name, old, new);
+                        }
+                    }
                     // Record in manifest (migrates legacy installs)
-                    manifest.record_install(&bundle.name, &source_display);
+                    manifest.record_install(&bundle.name, &source_display, commit.as_deref());
+                    manifest.record_version(&bundle.name, current);
+                    let files: Vec<PathBuf> = outcome
+                        .files
+                        .iter()
+                        .map(|f| f.strip_prefix(target_dir).unwrap_or(f).to_path_buf())
+                        .collect();
+                    manifest.record_files(&bundle.name, &files);
                 } else {
                     println!("{}", "no files".dimmed());
                 }
@@ -912,7 +1980,10 @@ fn refresh_installed_skills(
 
     println!();
     if refreshed > 0 {
-        println!("  {} {} bundle(s) refreshed", "✓".green(), refreshed);
+        println!("  {} {} bundle(s) updated", "✓".green(), refreshed);
+    }
+    if unchanged > 0 {
+        println!("  {} {} bundle(s) already up to date", "·".dimmed(), unchanged);
     }
     if not_found > 0 {
         println!(
@@ -924,14 +1995,32 @@ fn refresh_installed_skills(
     if errors > 0 {
         println!("  {} {} error(s)", "✗".red(), errors);
     }
+    write_opts.unknown_tools.report();
+    write_opts.placeholder_descriptions.report();
 
     Ok(())
 }
 
-fn list_bundles(config: &Config) -> Result<()> {
-    let sources = config.sources();
+/// Names of the bundles any source changed since `since`, warning about
+/// sources whose history can't be read
+fn bundles_changed_since(config: &Config, since: &str) -> std::collections::HashSet<String> {
+    let mut changed = std::collections::HashSet::new();
+    for source in config.sources() {
+        match source.changed_since(since) {
+            Ok(names) => changed.extend(names.unwrap_or_default()),
+            Err(e) => eprintln!(
+                "  {} {} - {:#}",
+                "Warning:".yellow(),
+                source.display_path(),
+                e
+            ),
+        }
+    }
+    changed
+}
 
-    if sources.is_empty() {
+fn list_bundles(config: &Config) -> Result<()> {
+    if config.source_configs().is_empty() {
         println!("{}", "No sources configured.".yellow());
         println!("Add a source with: skm sources add <path>");
         return Ok(());
@@ -943,9 +2032,9 @@ fn list_bundles(config: &Config) -> Result<()> {
     let mut found_any = false;
     let mut had_errors = false;
 
-    for source in sources {
+    for (source, listed) in config.list_all_bundles() {
         // Handle source errors gracefully - warn and continue
-        let bundles = match source.list_bundles() {
+        let bundles = match listed {
             Ok(b) => b,
             Err(e) => {
                 eprintln!(
@@ -967,11 +2056,17 @@ fn list_bundles(config: &Config) -> Result<()> {
         println!("  {} {}", "Source:".dimmed(), source.display_path());
 
         for bundle in bundles {
-            // Show description on same line if available
+            // Show version and description on same line if available
+            let version = bundle
+                .meta
+                .version
+                .as_ref()
+                .map(|v| format!(" {}", v))
+                .unwrap_or_default();
             if let Some(desc) = &bundle.meta.description {
-                println!("    {}/ - {}", bundle.name.cyan(), desc.dimmed());
+                println!("    {}/{} - {}", bundle.name.cyan(), version, desc.dimmed());
             } else {
-                println!("    {}/", bundle.name.cyan());
+                println!("    {}/{}", bundle.name.cyan(), version);
             }
 
             let skill_count = bundle.skills.len();
@@ -1007,12 +2102,101 @@ fn list_bundles(config: &Config) -> Result<()> {
     Ok(())
 }
 
-fn show_installed_skills(base: &PathBuf, filter_tool: Option<&str>) -> Result<()> {
+/// Installs of the enabled tools for `skm here --json`: in `base` and the
+/// tools' global locations, or in `base` and its subdirectories when `recursive`
+fn installed_skills(
+    config: &Config,
+    base: &Path,
+    filter_tool: Option<&str>,
+    recursive: bool,
+) -> Result<Vec<crate::discover::InstalledSkill>> {
+    use crate::discover::{
+        discover_global, discover_installed_for, discover_nested, filter_by_tool, NESTED_MAX_DEPTH,
+    };
+
+    let tools = config.tools();
+    let mut skills = if recursive {
+        let nested = discover_nested(base, NESTED_MAX_DEPTH, &tools)?;
+        nested.into_iter().flat_map(|(_, skills)| skills).collect()
+    } else {
+        let mut skills = discover_installed_for(base, &tools)?;
+        skills.extend(discover_global(base, &config.global_targets())?);
+        skills
+    };
+    if let Some(tool_filter) = filter_tool {
+        skills = filter_by_tool(skills, tool_filter);
+    }
+    Ok(skills)
+}
+
+/// `skm here --recursive`: installs of `tools` in the target directory and
+/// below, by directory
+fn show_nested_installs(
+    root: &Path,
+    tools: &[Tool],
+    filter_tool: Option<&str>,
+    verbose: bool,
+) -> Result<()> {
+    use crate::discover::{discover_nested, filter_by_tool, FileStats, NESTED_MAX_DEPTH};
+
+    let mut total = 0;
+    let mut dirs = 0;
+    for (base, mut skills) in discover_nested(root, NESTED_MAX_DEPTH, tools)? {
+        if let Some(tool_filter) = filter_tool {
+            skills = filter_by_tool(skills, tool_filter);
+        }
+        if skills.is_empty() {
+            continue;
+        }
+        let relative = base.strip_prefix(root).unwrap_or(&base);
+        let label = if relative.as_os_str().is_empty() {
+            ".".to_string()
+        } else {
+            relative.display().to_string()
+        };
+        println!("{}", format!("{}/", label).bold());
+        for skill in &skills {
+            let line = format!(
+                "  {} {}/{}",
+                format!("{:<12}", skill.tool.display_name()).cyan(),
+                skill.skill_type.plural().dimmed(),
+                skill.unique_id()
+            );
+            if verbose {
+                let stats = FileStats::read(&skill.path)?;
+                println!("{}  {}", line, format_stats(stats).dimmed());
+            } else {
+                println!("{}", line);
+            }
+        }
+        println!();
+        total += skills.len();
+        dirs += 1;
+    }
+
+    if dirs == 0 {
+        println!("{}", "No installed skills found.".yellow());
+    } else {
+        println!("  {} total in {} director{}", total, dirs, if dirs == 1 { "y" } else { "ies" });
+    }
+    println!();
+    Ok(())
+}
+
+fn show_installed_skills(
+    config: &Config,
+    base: &Path,
+    filter_tool: Option<&str>,
+    verbose: bool,
+) -> Result<()> {
     use crate::discover::{
-        discover_installed, filter_by_tool, group_by_tool, InstalledTool, SkillType,
+        bundle_totals, discover_global, discover_installed_for, filter_by_tool, group_by_tool,
+        FileStats, InstalledTool, Scope, SkillType,
     };
 
-    let mut skills = discover_installed(base)?;
+    let install_prefix = config.install_prefix.as_deref();
+    let mut skills = discover_installed_for(base, &config.tools())?;
+    skills.extend(discover_global(base, &config.global_targets())?);
 
     // Apply filter if provided
     if let Some(tool_filter) = filter_tool {
@@ -1033,43 +2217,81 @@ fn show_installed_skills(base: &PathBuf, filter_tool: Option<&str>) -> Result<()
         return Ok(());
     }
 
-    println!("{}", "Installed skills:".bold());
-    println!();
-
-    let grouped = group_by_tool(&skills);
-
     // Define tool order
     let tool_order = [
         InstalledTool::Claude,
         InstalledTool::OpenCode,
         InstalledTool::Cursor,
         InstalledTool::Codex,
+        InstalledTool::Windsurf,
     ];
 
-    for tool in &tool_order {
-        if let Some(type_map) = grouped.get(tool) {
-            println!("  {}", tool.display_name().cyan().bold());
+    // Project installs first, then global ones in their own section
+    let sections = [
+        (Scope::Local, "Installed skills:"),
+        (Scope::Global, "Global skills:"),
+    ];
+    for (scope, heading) in sections {
+        let scoped: Vec<_> = skills.iter().filter(|s| s.scope == scope).cloned().collect();
+        if scoped.is_empty() {
+            continue;
+        }
+        println!("{}", heading.bold());
+        println!();
 
-            // Define type order
-            let type_order = [SkillType::Skill, SkillType::Agent, SkillType::Command];
+        let grouped = group_by_tool(&scoped);
 
-            for skill_type in &type_order {
-                if let Some(skill_list) = type_map.get(skill_type) {
-                    if !skill_list.is_empty() {
-                        println!("    {}/", skill_type.plural().dimmed());
-
-                        for skill in skill_list {
-                            let display_name = if let Some(ref bundle) = skill.bundle {
-                                format!("{}/{}", bundle, skill.name)
-                            } else {
-                                skill.name.clone()
-                            };
-                            println!("      {}", display_name);
+        for tool in &tool_order {
+            if let Some(type_map) = grouped.get(tool) {
+                println!("  {}", tool.display_name().cyan().bold());
+
+                let manifest = Tool::from_id(tool.as_str())
+                    .map(|t| {
+                        let manifest_base = match scope {
+                            Scope::Local => base.to_path_buf(),
+                            Scope::Global => config.global_target(&t),
+                        };
+                        install_manifest::InstallManifest::load(&t, &manifest_base)
+                    })
+                    .unwrap_or_default();
+                // Installed names carry the prefix, so split them on prefixed bundles
+                let bundles: Vec<String> = manifest
+                    .bundle_names()
+                    .iter()
+                    .map(|b| prefixed_bundle_name(install_prefix, b))
+                    .collect();
+                let bundles: Vec<&str> = bundles.iter().map(String::as_str).collect();
+
+                // Define type order
+                let type_order = [SkillType::Skill, SkillType::Agent, SkillType::Command];
+
+                for skill_type in &type_order {
+                    if let Some(skill_list) = type_map.get(skill_type) {
+                        if !skill_list.is_empty() {
+                            println!("    {}/", skill_type.plural().dimmed());
+
+                            for skill in skill_list {
+                                let display_name = format!(
+                                    "{}{}",
+                                    skill.display_name(&bundles),
+                                    skill.scope.tag().dimmed()
+                                );
+                                if verbose {
+                                    let stats = FileStats::read(&skill.path)?;
+                                    println!(
+                                        "      {}  {}",
+                                        display_name,
+                                        format_stats(stats).dimmed()
+                                    );
+                                } else {
+                                    println!("      {}", display_name);
+                                }
+                            }
                         }
                     }
                 }
+                println!();
             }
-            println!();
         }
     }
 
@@ -1092,27 +2314,54 @@ fn show_installed_skills(base: &PathBuf, filter_tool: Option<&str>) -> Result<()
         })
         .collect();
 
+    let global = skills.iter().filter(|s| s.scope == Scope::Global).count();
+    let global_note = if global > 0 {
+        format!(", {} global", global)
+    } else {
+        String::new()
+    };
     println!(
-        "  {} {} total ({})",
+        "  {} {} total ({}){}",
         "".dimmed(),
         total,
-        summary_parts.join(", ")
+        summary_parts.join(", "),
+        global_note
     );
     println!();
 
+    if verbose {
+        println!("{}", "Bundle totals:".bold());
+        let mut grand_total = FileStats::default();
+        for (bundle, stats) in bundle_totals(&skills)? {
+            println!("  {}  {}", bundle, format_stats(stats).dimmed());
+            grand_total.add(stats);
+        }
+        println!("  {}  {}", "total".bold(), format_stats(grand_total));
+        println!();
+    }
+
     Ok(())
 }
 
+fn format_stats(stats: crate::discover::FileStats) -> String {
+    format!("{}, {} lines", crate::stats::format_size(stats.bytes), stats.lines)
+}
+
 fn generate_completions(shell: Shell) {
     let mut cmd = Cli::command();
     generate(shell, &mut cmd, "skm", &mut io::stdout());
 }
 
-fn interactive_remove(base: &PathBuf, filter_tool: Option<&str>) -> Result<()> {
-    use crate::discover::{discover_installed, filter_by_tool, group_same_skills, remove_skill};
-    use dialoguer::{theme::ColorfulTheme, Confirm, MultiSelect};
+fn interactive_remove(
+    base: &Path,
+    tools: &[Tool],
+    filter_tool: Option<&str>,
+    skip_confirm: bool,
+) -> Result<()> {
+    use crate::discover::{discover_installed_for, filter_by_tool, group_same_skills, remove_skill};
+    use dialoguer::{theme::ColorfulTheme, MultiSelect};
 
-    let mut skills = discover_installed(base)?;
+    let mut skills = discover_installed_for(base, tools)?;
 
     if let Some(tool_filter) = filter_tool {
         skills = filter_by_tool(skills, tool_filter);
@@ -1179,13 +2428,10 @@ fn interactive_remove(base: &PathBuf, filter_tool: Option<&str>) -> Result<()> {
     }
     println!();
 
-    // Confirm
-    let confirm = Confirm::with_theme(&ColorfulTheme::default())
-        .with_prompt(format!("Remove {} skill(s)?", to_remove.len()))
-        .default(false)
-        .interact()?;
+    // Confirm unless --yes
+    let confirmed = confirm(&format!("Remove {} skill(s)?", to_remove.len()), skip_confirm)?;
 
-    if !confirm {
+    if !confirmed {
         println!("{}", "Cancelled.".yellow());
         return Ok(());
     }
@@ -1222,11 +2468,28 @@ fn interactive_remove(base: &PathBuf, filter_tool: Option<&str>) -> Result<()> {
     Ok(())
 }
 
-fn clean_all_skills(base: &PathBuf, filter_tool: Option<&str>, skip_confirm: bool) -> Result<()> {
-    use crate::discover::{discover_installed, filter_by_tool, remove_skill};
+/// Ask a yes/no question (default no), or answer yes without asking under `--yes`
+fn confirm(prompt: &str, assume_yes: bool) -> Result<bool> {
     use dialoguer::{theme::ColorfulTheme, Confirm};
 
-    let mut skills = discover_installed(base)?;
+    if assume_yes {
+        return Ok(true);
+    }
+    Ok(Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt(prompt)
+        .default(false)
+        .interact()?)
+}
+
+fn clean_all_skills(
+    base: &Path,
+    tools: &[Tool],
+    filter_tool: Option<&str>,
+    skip_confirm: bool,
+) -> Result<()> {
+    use crate::discover::{discover_installed_for, filter_by_tool, remove_skill};
+
+    let mut skills = discover_installed_for(base, tools)?;
 
     if let Some(tool_filter) = filter_tool {
         skills = filter_by_tool(skills, tool_filter);
@@ -1246,14 +2509,7 @@ fn clean_all_skills(base: &PathBuf, filter_tool: Option<&str>, skip_confirm: boo
     println!();
 
     // Confirm unless --yes flag
-    let confirmed = if skip_confirm {
-        true
-    } else {
-        Confirm::with_theme(&ColorfulTheme::default())
-            .with_prompt(format!("Remove all {} skill(s)?", count))
-            .default(false)
-            .interact()?
-    };
+    let confirmed = confirm(&format!("Remove all {} skill(s)?", count), skip_confirm)?;
 
     if !confirmed {
         println!("{}", "Cancelled.".yellow());
@@ -1286,8 +2542,8 @@ fn clean_all_skills(base: &PathBuf, filter_tool: Option<&str>, skip_confirm: boo
         println!("{} Removed {} skill(s)", "".green(), removed);
 
         // Delete manifest files
-        for tool_enum in [Tool::Claude, Tool::OpenCode, Tool::Cursor, Tool::Codex] {
-            let path = install_manifest::InstallManifest::path_for(&tool_enum, base);
+        for tool_enum in tools {
+            let path = install_manifest::InstallManifest::path_for(tool_enum, base);
             let _ = std::fs::remove_file(&path);
         }
     }
@@ -1316,29 +2572,126 @@ fn skill_matches_bundle(skill: &crate::discover::InstalledSkill, bundle_name: &s
 
 fn remove_bundle(
     bundle_name: &str,
-    base: &PathBuf,
+    base: &Path,
+    tools: &[Tool],
     filter_tool: Option<&str>,
+    install_prefix: Option<&str>,
     skip_confirm: bool,
 ) -> Result<()> {
-    use crate::discover::{
-        discover_installed, filter_by_tool, group_by_tool, remove_skill, InstalledTool, SkillType,
-    };
-    use dialoguer::{theme::ColorfulTheme, Confirm};
+    let label = format!("bundle '{}'", bundle_name.cyan());
+    let names = [bundle_name.to_string()];
+    remove_bundles(&names, &label, base, tools, filter_tool, install_prefix, skip_confirm)
+}
 
-    let mut skills = discover_installed(base)?;
+/// Bundles the install manifests record as installed from `source` (a source
+/// name, path or URL), for the tools matching `filter_tool`
+fn bundles_from_source(
+    config: &Config,
+    source: &str,
+    base: &Path,
+    filter_tool: Option<&str>,
+) -> Vec<String> {
+    // Manifests store each source's display path, which a name has to be resolved to
+    let named = config
+        .find_source_by_name(source)
+        .map(|(found, _)| found.display_path());
+    let wanted = config::expand_tilde(source);
+    let matches = |recorded: &str| {
+        named.as_deref() == Some(recorded)
+            || recorded == source
+            || config::expand_tilde(recorded) == wanted
+    };
 
-    if let Some(tool_filter) = filter_tool {
-        skills = filter_by_tool(skills, tool_filter);
+    let mut names: Vec<String> = Vec::new();
+    for tool in config.tools() {
+        if filter_tool.is_some_and(|f| f != tool.id()) {
+            continue;
+        }
+        let manifest = install_manifest::InstallManifest::load(&tool, base);
+        for entry in manifest.bundles.iter().filter(|e| matches(&e.source)) {
+            if !names.contains(&entry.name) {
+                names.push(entry.name.clone());
+            }
+        }
     }
+    names
+}
 
-    // Filter to skills belonging to this bundle
-    skills.retain(|s| skill_matches_bundle(s, bundle_name));
+/// `skm rm --source`: remove every bundle installed from one source
+fn remove_source_bundles(
+    config: &Config,
+    source: &str,
+    base: &Path,
+    filter_tool: Option<&str>,
+    skip_confirm: bool,
+) -> Result<()> {
+    let names = bundles_from_source(config, source, base, filter_tool);
+    if names.is_empty() {
+        println!("No installed bundles recorded from source '{}'.", source.cyan());
+        return Ok(());
+    }
+    let label = format!("source '{}' ({})", source.cyan(), names.join(", "));
+    let prefix = config.install_prefix.as_deref();
+    let tools = config.tools();
+    remove_bundles(&names, &label, base, &tools, filter_tool, prefix, skip_confirm)
+}
+
+/// Files the install manifests under `base` record for each of `bundle_names`,
+/// keyed by tool id and bundle, for the bundles that have a file list
+fn recorded_files<'a>(
+    base: &Path,
+    tools: &[Tool],
+    bundle_names: &'a [String],
+) -> std::collections::HashMap<(&'static str, &'a str), Vec<PathBuf>> {
+    let mut recorded = std::collections::HashMap::new();
+    for tool in tools {
+        let manifest = install_manifest::InstallManifest::load(tool, base);
+        for name in bundle_names {
+            let files = manifest.files_of(name);
+            if !files.is_empty() {
+                let files = files.iter().map(|f| base.join(f)).collect();
+                recorded.insert((tool.id(), name.as_str()), files);
+            }
+        }
+    }
+    recorded
+}
+
+/// Remove the installed files of `bundle_names` for `tools`, described as
+/// `label` in output. Bundles without recorded files are matched by name,
+/// behind `install_prefix`.
+fn remove_bundles(
+    bundle_names: &[String],
+    label: &str,
+    base: &Path,
+    tools: &[Tool],
+    filter_tool: Option<&str>,
+    install_prefix: Option<&str>,
+    skip_confirm: bool,
+) -> Result<()> {
+    use crate::discover::{
+        discover_installed_for, filter_by_tool, group_by_tool, remove_skill, InstalledTool,
+        SkillType,
+    };
+
+    let mut skills = discover_installed_for(base, tools)?;
+
+    if let Some(tool_filter) = filter_tool {
+        skills = filter_by_tool(skills, tool_filter);
+    }
+
+    // Filter to skills belonging to these bundles: exactly the files the install
+    // manifests recorded, or by name for bundles installed before files were kept
+    let recorded = recorded_files(base, tools, bundle_names);
+    skills.retain(|s| {
+        bundle_names.iter().any(|b| match recorded.get(&(s.tool.as_str(), b.as_str())) {
+            Some(files) => files.contains(&s.path),
+            None => skill_matches_bundle(s, &prefixed_bundle_name(install_prefix, b)),
+        })
+    });
 
     if skills.is_empty() {
-        println!(
-            "No installed skills found for bundle '{}'.",
-            bundle_name.cyan()
-        );
+        println!("No installed skills found for {}.", label);
         return Ok(());
     }
 
@@ -1352,6 +2705,7 @@ fn remove_bundle(
         InstalledTool::OpenCode,
         InstalledTool::Cursor,
         InstalledTool::Codex,
+        InstalledTool::Windsurf,
     ];
     let type_order = [SkillType::Skill, SkillType::Agent, SkillType::Command, SkillType::Rule];
 
@@ -1375,18 +2729,10 @@ fn remove_bundle(
     println!();
 
     // Confirm unless --yes
-    let confirmed = if skip_confirm {
-        true
-    } else {
-        Confirm::with_theme(&ColorfulTheme::default())
-            .with_prompt(format!(
-                "Remove {} file(s) from bundle '{}'?",
-                skills.len(),
-                bundle_name
-            ))
-            .default(false)
-            .interact()?
-    };
+    let confirmed = confirm(
+        &format!("Remove {} file(s) from {}?", skills.len(), label),
+        skip_confirm,
+    )?;
 
     if !confirmed {
         println!("{}", "Cancelled.".yellow());
@@ -1418,10 +2764,14 @@ fn remove_bundle(
         println!("{} Removed {} file(s)", "".green(), removed);
 
         // Remove from manifest for all tools
-        for tool_enum in [Tool::Claude, Tool::OpenCode, Tool::Cursor, Tool::Codex] {
-            let mut manifest = install_manifest::InstallManifest::load(&tool_enum, base);
-            if manifest.remove_bundle(bundle_name) {
-                if let Err(e) = manifest.save(&tool_enum, base) {
+        for tool_enum in tools {
+            let mut manifest = install_manifest::InstallManifest::load(tool_enum, base);
+            let mut changed = false;
+            for bundle_name in bundle_names {
+                changed |= manifest.remove_bundle(bundle_name);
+            }
+            if changed {
+                if let Err(e) = manifest.save(tool_enum, base) {
                     eprintln!("Warning: could not save install manifest: {}", e);
                 }
             }
@@ -1434,7 +2784,7 @@ fn remove_bundle(
     Ok(())
 }
 
-fn convert_format(source: &PathBuf, to_rule: bool, output: Option<&PathBuf>) -> Result<()> {
+fn convert_format(source: &Path, to_rule: bool, output: Option<&Path>) -> Result<()> {
     use std::fs;
     use std::io::Write;
 
@@ -1472,7 +2822,37 @@ fn convert_format(source: &PathBuf, to_rule: bool, output: Option<&PathBuf>) ->
     Ok(())
 }
 
-fn convert_to_rule(content: &str, source_path: &PathBuf) -> String {
+/// Convert `source` and overwrite it, first copying the original to
+/// `<file>.bak` when `backup` is set
+fn convert_in_place(source: &Path, to_rule: bool, backup: bool) -> Result<()> {
+    let content = std::fs::read_to_string(source).map_err(|e| {
+        anyhow::anyhow!("Could not read {}: {}", source.display(), e)
+    })?;
+    let converted = if to_rule {
+        convert_to_rule(&content, source)
+    } else {
+        convert_to_command(&content)
+    };
+
+    if converted == content {
+        println!("{} {} is already converted", "Unchanged:".dimmed(), source.display());
+        return Ok(());
+    }
+
+    if backup {
+        let mut backup_name = source.as_os_str().to_owned();
+        backup_name.push(".bak");
+        let backup_path = PathBuf::from(backup_name);
+        std::fs::copy(source, &backup_path)?;
+        println!("{} {}", "Backup:".dimmed(), backup_path.display());
+    }
+    std::fs::write(source, converted)?;
+    println!("{} Converted {}", "Success:".green(), source.display());
+
+    Ok(())
+}
+
+fn convert_to_rule(content: &str, source_path: &Path) -> String {
     let lines: Vec<&str> = content.lines().collect();
 
     // Check if already has frontmatter
@@ -1500,7 +2880,7 @@ fn convert_to_rule(content: &str, source_path: &PathBuf) -> String {
     // Create rule frontmatter
     let mut result = String::new();
     result.push_str("---\n");
-    result.push_str(&format!("description: \"{}\"\n", title));
+    result.push_str(&format!("description: {}\n", transform::yaml_quote(&title)));
     result.push_str("alwaysApply: false\n");
     result.push_str("---\n");
     result.push('\n');
@@ -1510,33 +2890,80 @@ fn convert_to_rule(content: &str, source_path: &PathBuf) -> String {
 }
 
 fn convert_to_command(content: &str) -> String {
-    let lines: Vec<&str> = content.lines().collect();
+    // Work on raw lines (terminators included) so CRLF endings and the
+    // trailing newline of the body survive untouched
+    let mut lines = content.split_inclusive('\n');
 
     // Check if it has frontmatter
-    if lines.first() == Some(&"---") {
-        // Find the end of frontmatter
-        let mut in_frontmatter = false;
-        let mut end_idx = 0;
-
-        for (i, line) in lines.iter().enumerate() {
-            if *line == "---" {
-                if in_frontmatter {
-                    end_idx = i + 1;
-                    break;
-                }
-                in_frontmatter = true;
+    if lines.next().map(str::trim_end) != Some("---") {
+        // No frontmatter, return as-is
+        return content.to_string();
+    }
+
+    // Find the end of frontmatter
+    let mut offset = content.find('\n').map_or(content.len(), |i| i + 1);
+    for line in lines {
+        offset += line.len();
+        if line.trim_end() == "---" {
+            // Skip frontmatter and return the rest
+            let body = &content[offset..];
+            if body.is_empty() {
+                break;
             }
+            return body.trim_start().to_string();
         }
+    }
 
-        // Skip frontmatter and return the rest
-        if end_idx > 0 && end_idx < lines.len() {
-            lines[end_idx..].join("\n").trim_start().to_string()
-        } else {
-            content.to_string()
-        }
-    } else {
-        // No frontmatter, return as-is
-        content.to_string()
+    // Unterminated or frontmatter-only: leave untouched
+    content.to_string()
+}
+
+/// `skm new`: create an item in the first local source, prompting for
+/// whatever the arguments leave out
+fn new_item(
+    config: &Config,
+    item_type: Option<String>,
+    name: Option<String>,
+    description: Option<String>,
+    bundle: Option<String>,
+) -> Result<(scaffold::NewItem, PathBuf)> {
+    let item_type = match item_type {
+        Some(t) => Some(SkillType::from_name(&t).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Unknown type `{}` (expected one of: skill, agent, command, rule)",
+                t
+            )
+        })?),
+        None => None,
+    };
+    let source_root = first_local_source(config)?;
+    let item = scaffold::NewItem::prompt_missing(item_type, name, description, bundle)?;
+    let path = item.create(&source_root)?;
+    Ok((item, path))
+}
+
+/// Root of the first configured local source, where `skm new` creates items
+fn first_local_source(config: &Config) -> Result<PathBuf> {
+    config
+        .source_configs()
+        .iter()
+        .find_map(|s| match s {
+            SourceConfig::Local { path, .. } => Some(config::expand_tilde(path)),
+            SourceConfig::Git { .. } | SourceConfig::Archive { .. } => None,
+        })
+        .ok_or_else(|| {
+            anyhow::anyhow!("No local source configured; add one with `skm sources add <path>`")
+        })
+}
+
+/// A configured transform hook is ignored unless `--allow-exec` is passed
+fn warn_if_hook_blocked(config: &Config, opts: &InstallOptions) {
+    if let (Some(hook), None) = (&config.transform_hook, &opts.hook) {
+        eprintln!(
+            "{} transform_hook `{}` is configured but was not run; pass --allow-exec to enable it",
+            "Warning:".yellow(),
+            hook
+        );
     }
 }
 
@@ -1551,14 +2978,136 @@ fn parse_bundle_ref(input: &str) -> (Option<&str>, Option<&str>) {
     }
 }
 
+/// `--to` must be a directory, or a path one can be created at
+fn check_target_dir(path: &Path) -> Result<()> {
+    if path.exists() && !path.is_dir() {
+        anyhow::bail!(
+            "--to {} is a file, not a directory; pass the project directory to install into",
+            path.display()
+        );
+    }
+    // The nearest existing ancestor must be a directory for create_dir_all to work
+    if let Some(ancestor) = path.ancestors().skip(1).find(|a| a.exists()) {
+        if !ancestor.is_dir() {
+            anyhow::bail!(
+                "--to {} can't be created because {} is a file",
+                path.display(),
+                ancestor.display()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// A bundle reference that names an existing directory, to install from ad hoc.
+/// Bare words are always bundle names, and `source/bundle` references to a
+/// configured source win over a same-named relative directory.
+fn local_path_ref(config: &Config, bundle_ref: &str) -> Option<PathBuf> {
+    let path = config::expand_tilde(bundle_ref);
+    if !path.is_dir() {
+        return None;
+    }
+    let explicit = path.is_absolute() || bundle_ref.starts_with('.') || bundle_ref.starts_with('~');
+    if explicit {
+        return Some(path);
+    }
+    match parse_bundle_ref(bundle_ref) {
+        (Some(source), _) if config.find_source_by_name(source).is_none() => Some(path),
+        _ => None,
+    }
+}
+
 /// Dispatch install command with support for source-scoped references
 fn do_install(
     config: &Config,
     bundle_ref: &str,
     tool: &Tool,
-    target_dir: &PathBuf,
+    target_dir: &Path,
     types: &[SkillType],
+    opts: &InstallOptions,
 ) -> Result<()> {
+    // Config aliases install the target bundle under the alias name
+    let alias_opts;
+    let (bundle_ref, opts) = match config.resolve_alias(bundle_ref) {
+        Some(target) => {
+            alias_opts = InstallOptions {
+                bundle_alias: Some(bundle_ref.to_string()),
+                ..opts.clone()
+            };
+            (target, &alias_opts)
+        }
+        None => (bundle_ref, opts),
+    };
+
+    warn_if_hook_blocked(config, opts);
+
+    if opts.dry_run {
+        let plan = InstallPlan::build(tool, target_dir, opts, |opts| {
+            install_ref(config, bundle_ref, tool, target_dir, types, opts)
+        })?;
+        if opts.plan_json {
+            println!("{}", serde_json::to_string_pretty(&plan)?);
+        } else {
+            plan.print();
+        }
+        return Ok(());
+    }
+
+    let started = std::time::Instant::now();
+    let stats_opts = InstallOptions {
+        stats: Default::default(),
+        ..opts.clone()
+    };
+    install_ref(config, bundle_ref, tool, target_dir, types, &stats_opts)?;
+    if opts.stats_after {
+        println!("{}", install_stats_line(&stats_opts.stats, started.elapsed()));
+    }
+
+    Ok(())
+}
+
+/// One-line summary of an install for `--stats-after`, e.g.
+/// `Wrote 4 files (1.2 KB) in 0.01s: 1 skill, 3 commands`
+fn install_stats_line(stats: &crate::target::WriteStats, elapsed: std::time::Duration) -> String {
+    let all_types = [SkillType::Skill, SkillType::Agent, SkillType::Command, SkillType::Rule];
+    let per_type: Vec<String> = all_types
+        .into_iter()
+        .filter_map(|t| match stats.files_of_type(t) {
+            0 => None,
+            1 => Some(format!("1 {}", t.as_str())),
+            n => Some(format!("{} {}", n, t.dir_name())),
+        })
+        .collect();
+    let mut line = format!(
+        "{} {} file(s) ({}) in {:.2}s",
+        "Wrote".bold(),
+        stats.files(),
+        crate::stats::format_size(stats.bytes()),
+        elapsed.as_secs_f64()
+    );
+    if !per_type.is_empty() {
+        line.push_str(&format!(": {}", per_type.join(", ")));
+    }
+    line
+}
+
+/// Install a resolved bundle reference: a local path, `source/bundle`, a
+/// source name (all of its bundles) or a bundle name searched across sources
+fn install_ref(
+    config: &Config,
+    bundle_ref: &str,
+    tool: &Tool,
+    target_dir: &Path,
+    types: &[SkillType],
+    opts: &InstallOptions,
+) -> Result<Vec<InstallRecord>> {
+    if let Some(path) = local_path_ref(config, bundle_ref) {
+        if opts.prune {
+            anyhow::bail!("`--prune` only works with a configured source, not a path");
+        }
+        return install_from_path(config, &path, tool, target_dir, types, opts);
+    }
+
     let (source_name, bundle_name) = parse_bundle_ref(bundle_ref);
 
     let records = match (source_name, bundle_name) {
@@ -1566,7 +3115,7 @@ fn do_install(
             // Explicit source/bundle: "fg/synapse-docs"
             match config.find_source_by_name(source_name) {
                 Some((source, _)) => {
-                    install_bundle_from_source(source.as_ref(), bundle_name, tool, target_dir, types)?
+                    install_bundle_from_source(source.as_ref(), bundle_name, tool, target_dir, types, opts)?
                 }
                 None => {
                     anyhow::bail!("Source '{}' not found. Add it with: skm sources add {} <path>", source_name, source_name);
@@ -1578,10 +3127,14 @@ fn do_install(
             // First check if it's a named source
             if let Some((source, _)) = config.find_source_by_name(name) {
                 // Install all bundles from this source
-                install_from_source(source.as_ref(), tool, target_dir, types)?
+                let records = install_from_source(source.as_ref(), tool, target_dir, types, opts)?;
+                if opts.prune {
+                    prune_source(source.as_ref(), tool, target_dir, opts)?;
+                }
+                records
             } else {
                 // Otherwise, search all sources for a bundle with this name
-                install_bundle(config, name, tool, target_dir, types)?
+                install_bundle(config, name, tool, target_dir, types, opts)?
             }
         }
         (None, None) => {
@@ -1592,18 +3145,46 @@ fn do_install(
         }
     };
 
-    // Record installed bundles in manifest
-    if !records.is_empty() {
-        let mut manifest = install_manifest::InstallManifest::load(tool, target_dir);
-        for rec in &records {
-            manifest.record_install(&rec.bundle_name, &rec.source_display);
-        }
-        if let Err(e) = manifest.save(tool, target_dir) {
-            eprintln!("Warning: could not save install manifest: {}", e);
+    Ok(records)
+}
+
+/// `--prune`: remove bundles the install manifest records from `source` that
+/// the source no longer provides
+fn prune_source(
+    source: &dyn crate::source::Source,
+    tool: &Tool,
+    target_dir: &Path,
+    opts: &InstallOptions,
+) -> Result<()> {
+    let display = source.display_path();
+    let current: Vec<String> = source.list_bundles()?.into_iter().map(|b| b.name).collect();
+    let manifest = install_manifest::InstallManifest::load(tool, target_dir);
+    let stale: Vec<String> = manifest
+        .bundles
+        .iter()
+        .filter(|e| e.source == display && !current.contains(&e.name))
+        .map(|e| e.name.clone())
+        .collect();
+    if stale.is_empty() {
+        return Ok(());
+    }
+
+    let label = format!("bundles no longer in {} ({})", display.cyan(), stale.join(", "));
+    if opts.dry_run {
+        // Keep `--plan-json` output parseable
+        let line = format!("{} {}", "Would remove".yellow(), label);
+        if opts.plan_json {
+            eprintln!("{}", line);
+        } else {
+            println!("{}", line);
         }
+        return Ok(());
     }
 
-    Ok(())
+    println!();
+    let prefix = opts.install_prefix.as_deref();
+    let tools = std::slice::from_ref(tool);
+    remove_bundles(&stale, &label, target_dir, tools, Some(tool.id()), prefix, opts.assume_yes)
 }
 
 #[cfg(test)]
@@ -1622,6 +3203,30 @@ mod convert_tests {
         assert!(result.contains("# Test Rule"));
     }
 
+    #[test]
+    fn test_convert_in_place_keeps_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("style.md");
+        let original = "# Style Guide\n\nUse tabs.";
+        std::fs::write(&file, original).unwrap();
+
+        convert_in_place(&file, true, true).unwrap();
+        let converted = std::fs::read_to_string(&file).unwrap();
+        assert_eq!(converted, convert_to_rule(original, &file));
+        assert!(converted.starts_with("---\n"));
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("style.md.bak")).unwrap(),
+            original
+        );
+
+        // --no-backup
+        let other = dir.path().join("other.md");
+        std::fs::write(&other, original).unwrap();
+        convert_in_place(&other, true, false).unwrap();
+        assert!(std::fs::read_to_string(&other).unwrap().starts_with("---\n"));
+        assert!(!dir.path().join("other.md.bak").exists());
+    }
+
     #[test]
     fn test_convert_to_rule_with_existing_frontmatter() {
         let content = "---\ndescription: existing\n---\n# Content";
@@ -1662,6 +3267,17 @@ mod convert_tests {
         assert_eq!(result, content);
     }
 
+    #[test]
+    fn test_convert_to_command_preserves_crlf_and_trailing_newline() {
+        let body = "# Deploy\r\n\r\nRun the deploy.\r\n";
+        let content = format!("---\r\ndescription: test\r\nalwaysApply: false\r\n---\r\n{}", body);
+        assert_eq!(convert_to_command(&content), body);
+
+        let lf_body = "# Deploy\n\nRun the deploy.\n";
+        let content = format!("---\ndescription: test\n---\n\n{}", lf_body);
+        assert_eq!(convert_to_command(&content), lf_body);
+    }
+
     #[test]
     fn test_convert_to_command_only_frontmatter() {
         let content = "---\ndescription: test\n---";
@@ -1671,3 +3287,764 @@ mod convert_tests {
         assert_eq!(result, content);
     }
 }
+
+#[cfg(test)]
+mod cli_tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_global_yes_skips_confirmation() {
+        for args in [
+            vec!["skm", "here", "--clean", "--yes"],
+            vec!["skm", "here", "--remove", "-y"],
+            vec!["skm", "rm", "pdf", "-y"],
+            vec!["skm", "pdf", "--yes"],
+        ] {
+            assert!(Cli::try_parse_from(&args).unwrap().yes, "{:?}", args);
+        }
+
+        // Tests have no terminal, so a prompt would fail instead of removing
+        let dir = tempdir().unwrap();
+        let command = dir.path().join(".claude/commands/pdf/extract.md");
+        std::fs::create_dir_all(command.parent().unwrap()).unwrap();
+        std::fs::write(&command, "# Extract").unwrap();
+
+        remove_bundle("pdf", dir.path(), &Tool::ALL, None, None, true).unwrap();
+        assert!(!command.exists());
+    }
+
+    #[test]
+    fn test_claude_skill_and_rule_are_discovered_and_removed() {
+        use crate::bundle::SkillFile;
+        use crate::discover::{discover_installed, SkillType as InstalledType};
+
+        let src = tempdir().unwrap();
+        let project = tempdir().unwrap();
+        for (name, file, skill_type) in [
+            ("fill", "SKILL.md", SkillType::Skill),
+            ("style", "RULE.md", SkillType::Rule),
+        ] {
+            let dir = src.path().join(name);
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(dir.join(file), format!("# {}", name)).unwrap();
+            let item = SkillFile {
+                name: name.to_string(),
+                path: dir.join(file),
+                skill_type,
+                source_dir: Some(dir),
+            };
+            Tool::Claude.write_file(project.path(), "pdf", &item).unwrap();
+        }
+
+        let mut found: Vec<_> = discover_installed(project.path())
+            .unwrap()
+            .into_iter()
+            .map(|s| (s.skill_type, s.display_name(&["pdf"])))
+            .collect();
+        found.sort_by_key(|(t, _)| t.plural());
+        assert_eq!(
+            found,
+            vec![
+                (InstalledType::Rule, "pdf/style".to_string()),
+                (InstalledType::Skill, "pdf/fill".to_string()),
+            ]
+        );
+
+        remove_bundle("pdf", project.path(), &Tool::ALL, None, None, true).unwrap();
+        assert!(discover_installed(project.path()).unwrap().is_empty());
+        assert!(!project.path().join(".claude/skills/pdf-fill").exists());
+        assert!(!project.path().join(".claude/rules/pdf-style").exists());
+    }
+
+    #[test]
+    fn test_rm_matches_prefixed_installs() {
+        let dir = tempdir().unwrap();
+        let skill = dir.path().join(".claude/skills/acme-pdf-fill/SKILL.md");
+        let command = dir.path().join(".claude/commands/acme-pdf/extract.md");
+        let other = dir.path().join(".claude/skills/pdf-fill/SKILL.md");
+        for path in [&skill, &command, &other] {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "# Item").unwrap();
+        }
+
+        remove_bundle("pdf", dir.path(), &Tool::ALL, None, Some("acme"), true).unwrap();
+        assert!(!skill.exists());
+        assert!(!command.exists());
+        assert!(other.exists(), "unprefixed installs aren't this config's");
+    }
+
+    #[test]
+    fn test_rm_removes_exactly_the_recorded_files() {
+        let src = tempdir().unwrap();
+        for bundle in ["pdf", "pdf-extra"] {
+            let commands = src.path().join(bundle).join("commands");
+            std::fs::create_dir_all(&commands).unwrap();
+            std::fs::write(commands.join("run.md"), "# Run").unwrap();
+        }
+        let source = crate::source::LocalSource::new(src.path().to_path_buf());
+        let project = tempdir().unwrap();
+        for bundle in ["pdf", "pdf-extra"] {
+            crate::install::install_bundle_from_source(
+                &source,
+                bundle,
+                &Tool::OpenCode,
+                project.path(),
+                &[SkillType::Command],
+                &InstallOptions::default(),
+            )
+            .unwrap();
+        }
+        let commands = project.path().join(".opencode/commands");
+        assert!(commands.join("pdf-run.md").exists());
+        assert!(commands.join("pdf-extra-run.md").exists());
+
+        // Name matching would take `pdf-extra-run` for part of `pdf` as well
+        remove_bundle("pdf", project.path(), &Tool::ALL, None, None, true).unwrap();
+
+        assert!(!commands.join("pdf-run.md").exists());
+        assert!(commands.join("pdf-extra-run.md").exists());
+        let manifest = install_manifest::InstallManifest::load(&Tool::OpenCode, project.path());
+        assert_eq!(manifest.bundle_names(), vec!["pdf-extra"]);
+    }
+
+    #[test]
+    fn test_rm_source_removes_every_bundle_from_it() {
+        let cli = Cli::try_parse_from(["skm", "rm", "--source", "fg"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Commands::Rm { bundle: None, source: Some(_) })
+        ));
+        assert!(Cli::try_parse_from(["skm", "rm"]).is_err());
+
+        let source_dir = tempdir().unwrap();
+        let config = Config::new(vec![
+            SourceConfig::local(source_dir.path().to_string_lossy().to_string(), Some("fg".into())),
+        ]);
+        let display = config.find_source_by_name("fg").unwrap().0.display_path();
+
+        let dir = tempdir().unwrap();
+        let mut manifest = install_manifest::InstallManifest::default();
+        for (bundle, source) in [("pdf", display.as_str()), ("docx", &display), ("cl", "~/other")] {
+            let command = dir.path().join(format!(".claude/commands/{}/run.md", bundle));
+            std::fs::create_dir_all(command.parent().unwrap()).unwrap();
+            std::fs::write(&command, "# Run").unwrap();
+            manifest.record_install(bundle, source, None);
+        }
+        manifest.save(&Tool::Claude, dir.path()).unwrap();
+
+        remove_source_bundles(&config, "fg", dir.path(), None, true).unwrap();
+
+        assert!(!dir.path().join(".claude/commands/pdf/run.md").exists());
+        assert!(!dir.path().join(".claude/commands/docx/run.md").exists());
+        assert!(dir.path().join(".claude/commands/cl/run.md").exists());
+        let manifest = install_manifest::InstallManifest::load(&Tool::Claude, dir.path());
+        assert_eq!(manifest.bundle_names(), vec!["cl"]);
+    }
+
+    #[test]
+    fn test_update_targets_one_source_or_bundle() {
+        let cli = Cli::try_parse_from(["skm", "update", "team", "--sources-only"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Commands::Update { name: Some(ref n), sources_only: true }) if n == "team"
+        ));
+
+        let team = tempdir().unwrap();
+        let other = tempdir().unwrap();
+        for (dir, bundle) in [(&team, "pdf"), (&other, "docx")] {
+            let commands = dir.path().join(bundle).join("commands");
+            std::fs::create_dir_all(&commands).unwrap();
+            std::fs::write(commands.join("run.md"), "# Run v1").unwrap();
+        }
+        let config = Config::new(vec![
+            SourceConfig::local(team.path().to_string_lossy().to_string(), Some("team".into())),
+            SourceConfig::local(other.path().to_string_lossy().to_string(), Some("other".into())),
+        ]);
+
+        assert_eq!(UpdateTarget::resolve(&config, None).unwrap(), UpdateTarget::All);
+        let target = UpdateTarget::resolve(&config, Some("team")).unwrap();
+        assert!(matches!(target, UpdateTarget::Source { ref name, .. } if name == "team"));
+        assert!(matches!(
+            UpdateTarget::resolve(&config, Some("docx")).unwrap(),
+            UpdateTarget::Bundle { ref name, .. } if name == "docx"
+        ));
+        let err = UpdateTarget::resolve(&config, Some("nope")).unwrap_err().to_string();
+        assert!(err.contains("`nope`") && err.contains("team, other"), "{}", err);
+
+        let project = tempdir().unwrap();
+        for bundle in ["pdf", "docx"] {
+            let (source, _) = config.find_bundle(bundle).unwrap().unwrap();
+            crate::install::install_bundle_from_source(
+                source.as_ref(),
+                bundle,
+                &Tool::OpenCode,
+                project.path(),
+                &[SkillType::Command],
+                &InstallOptions::default(),
+            )
+            .unwrap();
+        }
+        for (dir, bundle) in [(&team, "pdf"), (&other, "docx")] {
+            std::fs::write(dir.path().join(bundle).join("commands/run.md"), "# Run v2").unwrap();
+        }
+
+        refresh_installed_skills(
+            &config,
+            &Tool::OpenCode,
+            project.path(),
+            &[SkillType::Command],
+            &InstallOptions::default(),
+            &target,
+        )
+        .unwrap();
+
+        let commands = project.path().join(".opencode/commands");
+        let read = |file: &str| std::fs::read_to_string(commands.join(file)).unwrap();
+        assert!(read("pdf-run.md").contains("v2"));
+        assert!(read("docx-run.md").contains("v1"), "other sources are left alone");
+    }
+
+    #[test]
+    fn test_update_refreshes_bundles_whose_version_increased() {
+        let source = tempdir().unwrap();
+        for bundle in ["pdf", "docx"] {
+            let commands = source.path().join(bundle).join("commands");
+            std::fs::create_dir_all(&commands).unwrap();
+            std::fs::write(commands.join("run.md"), "# Run v1").unwrap();
+            std::fs::write(source.path().join(bundle).join("meta.yaml"), "version: 1.0\n")
+                .unwrap();
+        }
+        let path = source.path().to_string_lossy().to_string();
+        let config = Config::new(vec![SourceConfig::local(path, None)]);
+
+        let project = tempdir().unwrap();
+        for bundle in ["pdf", "docx"] {
+            let (source, _) = config.find_bundle(bundle).unwrap().unwrap();
+            crate::install::install_bundle_from_source(
+                source.as_ref(),
+                bundle,
+                &Tool::OpenCode,
+                project.path(),
+                &[SkillType::Command],
+                &InstallOptions::default(),
+            )
+            .unwrap();
+        }
+        let manifest = install_manifest::InstallManifest::load(&Tool::OpenCode, project.path());
+        assert_eq!(manifest.version_of("pdf"), Some("1.0"));
+
+        // Both change, but only pdf gets a new version
+        for bundle in ["pdf", "docx"] {
+            let file = source.path().join(bundle).join("commands/run.md");
+            std::fs::write(file, "# Run v2").unwrap();
+        }
+        std::fs::write(source.path().join("pdf/meta.yaml"), "version: 1.1\n").unwrap();
+
+        refresh_installed_skills(
+            &config,
+            &Tool::OpenCode,
+            project.path(),
+            &[SkillType::Command],
+            &InstallOptions::default(),
+            &UpdateTarget::All,
+        )
+        .unwrap();
+
+        let commands = project.path().join(".opencode/commands");
+        let read = |file: &str| std::fs::read_to_string(commands.join(file)).unwrap();
+        assert!(read("pdf-run.md").contains("v2"));
+        assert!(read("docx-run.md").contains("v1"), "same version is left alone");
+        let manifest = install_manifest::InstallManifest::load(&Tool::OpenCode, project.path());
+        assert_eq!(manifest.version_of("pdf"), Some("1.1"));
+        assert_eq!(manifest.version_of("docx"), Some("1.0"));
+    }
+
+    #[test]
+    fn test_rm_after_update_removes_files_added_upstream() {
+        let source = tempdir().unwrap();
+        let commands = source.path().join("pdf/commands");
+        std::fs::create_dir_all(&commands).unwrap();
+        std::fs::write(commands.join("run.md"), "# Run").unwrap();
+        let path = source.path().to_string_lossy().to_string();
+        let config = Config::new(vec![SourceConfig::local(path, None)]);
+
+        let project = tempdir().unwrap();
+        let (found, _) = config.find_bundle("pdf").unwrap().unwrap();
+        crate::install::install_bundle_from_source(
+            found.as_ref(),
+            "pdf",
+            &Tool::OpenCode,
+            project.path(),
+            &[SkillType::Command],
+            &InstallOptions::default(),
+        )
+        .unwrap();
+
+        std::fs::write(commands.join("extract.md"), "# Extract").unwrap();
+        refresh_installed_skills(
+            &config,
+            &Tool::OpenCode,
+            project.path(),
+            &[SkillType::Command],
+            &InstallOptions::default(),
+            &UpdateTarget::All,
+        )
+        .unwrap();
+        let installed = project.path().join(".opencode/commands");
+        assert!(installed.join("pdf-extract.md").exists());
+
+        remove_bundle("pdf", project.path(), &Tool::ALL, None, None, true).unwrap();
+        assert!(!installed.join("pdf-run.md").exists());
+        assert!(!installed.join("pdf-extract.md").exists(), "added by the update");
+    }
+
+    #[test]
+    fn test_rm_removes_files_installed_through_a_destination_template() {
+        let source = tempdir().unwrap();
+        let skill = source.path().join("pdf/skills/fill");
+        std::fs::create_dir_all(skill.join("scripts")).unwrap();
+        std::fs::write(skill.join("SKILL.md"), "---\ndescription: Fill\n---\n").unwrap();
+        std::fs::write(skill.join("scripts/fill.sh"), "echo fill").unwrap();
+        let path = source.path().to_string_lossy().to_string();
+        let config = Config::new(vec![SourceConfig::local(path, None)]);
+
+        let project = tempdir().unwrap();
+        let opts = InstallOptions {
+            destinations: crate::target::DestTemplates::new(vec![(
+                Tool::Claude,
+                SkillType::Skill,
+                ".config/claude/skills/{bundle}-{name}/SKILL.md".to_string(),
+            )]),
+            ..Default::default()
+        };
+        let (found, _) = config.find_bundle("pdf").unwrap().unwrap();
+        crate::install::install_bundle_from_source(
+            found.as_ref(),
+            "pdf",
+            &Tool::Claude,
+            project.path(),
+            &[SkillType::Skill],
+            &opts,
+        )
+        .unwrap();
+        let installed = project.path().join(".config/claude/skills/pdf-fill");
+        assert!(installed.join("scripts/fill.sh").exists());
+
+        // `skm here` lists the skill once, without its companion
+        let here = crate::discover::discover_installed(project.path()).unwrap();
+        assert_eq!(here.len(), 1, "{:?}", here);
+        assert_eq!(here[0].path, installed.join("SKILL.md"));
+        assert_eq!(here[0].bundle.as_deref(), Some("pdf"));
+
+        remove_bundle("pdf", project.path(), &Tool::ALL, None, None, true).unwrap();
+        assert!(!installed.exists());
+        let manifest = install_manifest::InstallManifest::load(&Tool::Claude, project.path());
+        assert!(manifest.is_empty());
+    }
+
+    #[test]
+    fn test_here_tool_all_or_known_tool() {
+        let dir = tempdir().unwrap();
+        let claude = dir.path().join(".claude/commands/pdf/run.md");
+        let cursor = dir.path().join(".cursor/commands/pdf-run.md");
+        for path in [&claude, &cursor] {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "# Run").unwrap();
+        }
+        let shown = |tool: Option<&str>| {
+            let filter = here_tool_filter(tool).unwrap();
+            let skills = crate::discover::discover_installed(dir.path()).unwrap();
+            match filter {
+                Some(id) => crate::discover::filter_by_tool(skills, &id).len(),
+                None => skills.len(),
+            }
+        };
+
+        assert_eq!(shown(Some("all")), 2);
+        assert_eq!(shown(None), 2);
+        assert_eq!(shown(Some("Cursor")), 1);
+        let err = here_tool_filter(Some("curser")).unwrap_err().to_string();
+        assert!(err.contains("`curser`") && err.contains("windsurf"), "{}", err);
+    }
+
+    #[test]
+    fn test_prune_removes_bundles_gone_from_source() {
+        let cli = Cli::try_parse_from(["skm", "team", "--prune", "-y"]).unwrap();
+        assert!(cli.prune && cli.yes);
+        assert!(Cli::try_parse_from(["skm", "team", "--prune", "--into-bundle", "x"]).is_err());
+
+        let src = tempdir().unwrap();
+        for bundle in ["pdf", "docx"] {
+            let commands = src.path().join(bundle).join("commands");
+            std::fs::create_dir_all(&commands).unwrap();
+            std::fs::write(commands.join("run.md"), "# Run").unwrap();
+        }
+        let config = Config::new(vec![SourceConfig::local(
+            src.path().to_string_lossy().to_string(),
+            Some("team".into()),
+        )]);
+        let project = tempdir().unwrap();
+        let opts = InstallOptions {
+            prune: true,
+            assume_yes: true,
+            ..Default::default()
+        };
+        let types = [SkillType::Command];
+        let target = project.path();
+        let install = || install_ref(&config, "team", &Tool::OpenCode, target, &types, &opts);
+
+        install().unwrap();
+        let commands = project.path().join(".opencode/commands");
+        assert!(commands.join("docx-run.md").exists());
+
+        std::fs::remove_dir_all(src.path().join("docx")).unwrap();
+        let dry_run = InstallOptions {
+            dry_run: true,
+            ..opts.clone()
+        };
+        install_ref(&config, "team", &Tool::OpenCode, target, &types, &dry_run).unwrap();
+        assert!(commands.join("docx-run.md").exists());
+        install().unwrap();
+
+        assert!(!commands.join("docx-run.md").exists());
+        assert!(commands.join("pdf-run.md").exists());
+        let manifest = install_manifest::InstallManifest::load(&Tool::OpenCode, project.path());
+        assert_eq!(manifest.bundle_names(), vec!["pdf"]);
+
+        // A path isn't a source the manifest records bundles against
+        let path = src.path().to_str().unwrap();
+        let err = install_ref(&config, path, &Tool::OpenCode, target, &types, &opts).unwrap_err();
+        assert!(err.to_string().contains("`--prune` only works"), "{}", err);
+    }
+
+    #[test]
+    fn test_browser_installs_picked_bundle_and_types() {
+        let (first, team) = (tempdir().unwrap(), tempdir().unwrap());
+        for (src, content) in [(&first, "# First"), (&team, "# Team")] {
+            let bundle = src.path().join("pdf");
+            std::fs::create_dir_all(bundle.join("commands")).unwrap();
+            std::fs::create_dir_all(bundle.join("skills")).unwrap();
+            std::fs::write(bundle.join("commands/run.md"), content).unwrap();
+            std::fs::write(bundle.join("skills/fill.md"), "# Fill").unwrap();
+        }
+        let config = Config::new(vec![
+            SourceConfig::local(first.path().to_string_lossy().to_string(), None),
+            SourceConfig::local(team.path().to_string_lossy().to_string(), Some("team".into())),
+        ]);
+        let sources = config.sources();
+        assert_eq!(browsed_bundle_ref(&config, sources[0].as_ref(), "pdf"), "pdf");
+        let bundle_ref = browsed_bundle_ref(&config, sources[1].as_ref(), "pdf");
+        assert_eq!(bundle_ref, "team/pdf");
+
+        let project = tempdir().unwrap();
+        let install = BrowseInstall {
+            tool: &Tool::Claude,
+            target_dir: project.path(),
+            types: &SkillType::ALL,
+            opts: &InstallOptions::default(),
+        };
+        install_browsed_bundle(&config, &bundle_ref, &[SkillType::Command], &install).unwrap();
+
+        let command = project.path().join(".claude/commands/pdf/run.md");
+        assert_eq!(std::fs::read_to_string(command).unwrap(), "# Team");
+        assert!(!project.path().join(".claude/skills").exists(), "skills were not picked");
+    }
+
+    #[test]
+    fn test_jobs_flag_bounds_source_reads() {
+        let cli = Cli::try_parse_from(["skm", "list", "--jobs", "1"]).unwrap();
+        assert_eq!(cli.jobs, Some(1));
+        assert!(Cli::try_parse_from(["skm", "list", "-j", "0"]).is_err());
+
+        let mut config = Config::new(vec![]);
+        assert!(config.jobs() >= 1);
+        config.jobs = cli.jobs.map(usize::from);
+        assert_eq!(config.jobs(), 1);
+    }
+
+    #[test]
+    fn test_items_of_multi_type_bundle() {
+        let dir = tempdir().unwrap();
+        let bundle_dir = dir.path().join("tools");
+        for (sub, file) in [("skills", "helper"), ("agents", "reviewer"), ("commands", "commit")] {
+            std::fs::create_dir_all(bundle_dir.join(sub)).unwrap();
+            std::fs::write(bundle_dir.join(sub).join(format!("{}.md", file)), "# x").unwrap();
+        }
+        std::fs::write(bundle_dir.join("commands/debug.md"), "# x").unwrap();
+        let mut bundle = crate::bundle::Bundle::from_path(bundle_dir).unwrap();
+        bundle.commands.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let items = bundle.items();
+        assert_eq!(
+            format_items(&items, false).unwrap(),
+            "skill helper\nagent reviewer\ncommand commit\ncommand debug\n"
+        );
+
+        let json: serde_json::Value =
+            serde_json::from_str(&format_items(&items, true).unwrap()).unwrap();
+        assert_eq!(json[1]["type"], "agent");
+        assert_eq!(json[1]["name"], "reviewer");
+        assert_eq!(json.as_array().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_install_from_bare_path() {
+        let source = tempdir().unwrap();
+        let bundle_dir = source.path().join("my-bundle");
+        std::fs::create_dir_all(bundle_dir.join("commands")).unwrap();
+        std::fs::write(bundle_dir.join("commands/commit.md"), "# Commit").unwrap();
+        let target = tempdir().unwrap();
+        let config = Config::new(vec![]);
+
+        // Neither path is a configured source; the bundle dir installs as itself,
+        // its parent as a source of bundles
+        for path in [&bundle_dir, &source.path().to_path_buf()] {
+            do_install(
+                &config,
+                path.to_str().unwrap(),
+                &Tool::Claude,
+                target.path(),
+                &[SkillType::Command],
+                &InstallOptions::default(),
+            )
+            .unwrap();
+        }
+
+        assert!(target.path().join(".claude/commands/my-bundle/commit.md").exists());
+        let manifest = install_manifest::InstallManifest::load(&Tool::Claude, target.path());
+        assert_eq!(manifest.bundle_names(), vec!["my-bundle"]);
+        assert!(local_path_ref(&config, "my-bundle").is_none());
+    }
+
+    #[test]
+    fn test_install_from_path_respects_deny_list() {
+        let source = tempdir().unwrap();
+        let bundle_dir = source.path().join("my-bundle");
+        std::fs::create_dir_all(bundle_dir.join("commands")).unwrap();
+        std::fs::write(bundle_dir.join("commands/commit.md"), "# Commit").unwrap();
+        let target = tempdir().unwrap();
+        let mut config = Config::new(vec![]);
+        config.deny = vec!["my-bundle".to_string()];
+
+        let install = |path: &Path| {
+            do_install(
+                &config,
+                path.to_str().unwrap(),
+                &Tool::Claude,
+                target.path(),
+                &[SkillType::Command],
+                &InstallOptions::default(),
+            )
+        };
+        let err = install(&bundle_dir).unwrap_err();
+        assert!(err.to_string().contains("blocked by the `allow`/`deny` lists"), "{}", err);
+        // Installing the parent as a source skips the denied bundle
+        install(source.path()).unwrap();
+        assert!(!target.path().join(".claude/commands/my-bundle").exists());
+    }
+
+    #[test]
+    fn test_stats_line_counts_installed_files() {
+        let source = tempdir().unwrap();
+        let bundle_dir = source.path().join("kit");
+        std::fs::create_dir_all(bundle_dir.join("commands")).unwrap();
+        std::fs::write(bundle_dir.join("commands/commit.md"), "# Commit").unwrap();
+        std::fs::write(bundle_dir.join("commands/review.md"), "# Review").unwrap();
+        std::fs::create_dir_all(bundle_dir.join("agents")).unwrap();
+        std::fs::write(bundle_dir.join("agents/critic.md"), "# Critic").unwrap();
+        let target = tempdir().unwrap();
+
+        let config = Config::default();
+        let opts = InstallOptions::default();
+        install::install_from_path(
+            &config,
+            &bundle_dir,
+            &Tool::Claude,
+            target.path(),
+            &[SkillType::Agent, SkillType::Command],
+            &opts,
+        )
+        .unwrap();
+
+        assert_eq!(opts.stats.files(), 3);
+        assert_eq!(opts.stats.bytes(), 24);
+        let line = install_stats_line(&opts.stats, std::time::Duration::from_millis(20));
+        assert!(line.contains("3 file(s) (24 B) in 0.02s: 1 agent, 2 commands"), "{}", line);
+
+        // Files kept because of local changes aren't counted
+        std::fs::write(target.path().join(".claude/commands/kit/commit.md"), "# Mine").unwrap();
+        let again = InstallOptions::default();
+        let types = [SkillType::Command];
+        let claude = Tool::Claude;
+        install::install_from_path(&config, &bundle_dir, &claude, target.path(), &types, &again)
+            .unwrap();
+        assert_eq!(again.stats.files(), 1);
+    }
+
+    #[test]
+    fn test_target_must_be_a_directory() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("notes.md");
+        std::fs::write(&file, "# Notes").unwrap();
+
+        let err = check_target_dir(&file).unwrap_err().to_string();
+        assert!(err.contains("is a file, not a directory"), "{}", err);
+        let err = check_target_dir(&file.join("sub")).unwrap_err().to_string();
+        assert!(err.contains("can't be created"), "{}", err);
+
+        assert!(check_target_dir(dir.path()).is_ok());
+        assert!(check_target_dir(&dir.path().join("new/project")).is_ok());
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "# Notes");
+    }
+
+    /// Source whose files only exist in memory, like entries of an unextracted archive
+    struct InMemorySource(std::collections::HashMap<PathBuf, String>);
+
+    impl crate::source::Source for InMemorySource {
+        fn list_bundles(&self) -> Result<Vec<crate::bundle::Bundle>> {
+            Ok(Vec::new())
+        }
+
+        fn display_path(&self) -> String {
+            "skills.zip".to_string()
+        }
+
+        fn read_file(&self, path: &Path) -> Result<String> {
+            self.0
+                .get(path)
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("{} not in archive", path.display()))
+        }
+    }
+
+    #[test]
+    fn test_preview_reads_through_source() {
+        let path = PathBuf::from("skills.zip/pdf/commands/extract.md");
+        let source = InMemorySource(
+            [(path.clone(), "---\nname: extract\n---\n\n# Extract text\n".to_string())]
+                .into_iter()
+                .collect(),
+        );
+
+        assert!(!path.exists());
+        assert_eq!(get_file_preview(&source, &path), "- Extract text");
+        assert_eq!(get_file_preview(&source, Path::new("missing.md")), "");
+    }
+
+    #[test]
+    fn test_new_with_all_args_creates_item_without_prompting() {
+        let git_only = Config::new(vec![SourceConfig::git("https://example.com/x".into(), None)]);
+        let source = tempdir().unwrap();
+        let config = Config::new(vec![
+            SourceConfig::git("https://example.com/x".to_string(), None),
+            SourceConfig::local(source.path().to_string_lossy().to_string(), None),
+        ]);
+
+        let cli = Cli::try_parse_from([
+            "skm", "new", "agent", "reviewer", "-d", "Reviews diffs", "--bundle", "team",
+        ])
+        .unwrap();
+        let Some(Commands::New {
+            item_type,
+            name,
+            description,
+            bundle,
+        }) = cli.command
+        else {
+            panic!("expected `skm new`");
+        };
+        assert!(new_item(
+            &git_only,
+            item_type.clone(),
+            name.clone(),
+            description.clone(),
+            bundle.clone()
+        )
+        .is_err());
+
+        let (_, path) = new_item(&config, item_type, name, description, bundle).unwrap();
+        assert_eq!(path, source.path().join("team/agents/reviewer.md"));
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with("---\nname: reviewer\ndescription: \"Reviews diffs\"\n---\n"));
+
+        let err = new_item(&config, Some("widget".into()), None, None, None).unwrap_err();
+        assert!(err.to_string().contains("Unknown type `widget`"));
+    }
+
+    #[test]
+    fn test_here_json_round_trips_installed_skills() {
+        use crate::discover::{InstalledSkill, InstalledTool, Scope, SkillType as InstalledType};
+
+        let project = tempdir().unwrap();
+        let commands = project.path().join(".claude/commands/pdf");
+        std::fs::create_dir_all(&commands).unwrap();
+        std::fs::write(commands.join("extract.md"), "# Extract\n").unwrap();
+        let rules = project.path().join("packages/web/.cursor/rules");
+        std::fs::create_dir_all(&rules).unwrap();
+        std::fs::write(rules.join("style.mdc"), "---\ndescription: x\n---\n").unwrap();
+
+        let config = Config::new(vec![]);
+        let skills = installed_skills(&config, project.path(), None, true).unwrap();
+        let json = serde_json::to_string_pretty(&skills).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value[0]["name"], "extract");
+        assert_eq!(value[0]["skill_type"], "command");
+        assert_eq!(value[0]["tool"], "claude");
+        assert_eq!(value[0]["bundle"], "pdf");
+        assert_eq!(value[0]["scope"], "local");
+        let parsed: Vec<InstalledSkill> = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            parsed,
+            vec![
+                InstalledSkill {
+                    name: "extract".to_string(),
+                    skill_type: InstalledType::Command,
+                    tool: InstalledTool::Claude,
+                    path: commands.join("extract.md"),
+                    bundle: Some("pdf".to_string()),
+                    scope: Scope::Local,
+                },
+                InstalledSkill {
+                    name: "style".to_string(),
+                    skill_type: InstalledType::Rule,
+                    tool: InstalledTool::Cursor,
+                    path: rules.join("style.mdc"),
+                    bundle: None,
+                    scope: Scope::Local,
+                },
+            ]
+        );
+
+        // Cursor has no global location, so only the project is searched
+        let web = project.path().join("packages/web");
+        let mut config = Config::new(vec![]);
+        config.enabled_tools = vec!["cursor".to_string()];
+        let cursor = installed_skills(&config, &web, Some("cursor"), false).unwrap();
+        assert_eq!(cursor, parsed[1..]);
+    }
+
+    #[test]
+    fn test_validate_fails_on_problems_in_named_or_default_source() {
+        let good = tempdir().unwrap();
+        let skill = good.path().join("pdf/skills/fill");
+        std::fs::create_dir_all(&skill).unwrap();
+        std::fs::write(skill.join("SKILL.md"), "---\ndescription: Fill\n---\n").unwrap();
+        let bad = tempdir().unwrap();
+        std::fs::create_dir_all(bad.path().join("pdf/skills/fill")).unwrap();
+        std::fs::create_dir_all(bad.path().join("pdf/commands")).unwrap();
+        std::fs::write(bad.path().join("pdf/commands/extract.md"), "# Extract\n").unwrap();
+        let config = Config::new(vec![
+            SourceConfig::local(good.path().to_string_lossy().to_string(), None),
+            SourceConfig::local(bad.path().to_string_lossy().to_string(), Some("wip".into())),
+        ]);
+
+        validate_source(&config, None).unwrap();
+        let err = validate_source(&config, Some("wip")).unwrap_err();
+        assert!(err.to_string().starts_with("1 problem(s) found"), "{}", err);
+        let bad_dir = bad.path().to_string_lossy().to_string();
+        assert!(validate_source(&config, Some(&bad_dir)).is_err());
+        let err = validate_source(&config, Some("nowhere")).unwrap_err();
+        assert!(err.to_string().contains("No source named `nowhere`"), "{}", err);
+    }
+}
@@ -1,29 +1,30 @@
 use serde::Deserialize;
-use std::path::PathBuf;
+use std::path::Path;
 
 use crate::bundle::{Bundle, BundleMeta, SkillFile, SkillType};
 
 #[derive(Debug, Deserialize)]
 pub struct SourceManifest {
-    pub source: Option<SourceMeta>,
     #[serde(default)]
     pub bundles: Vec<BundleDeclaration>,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct SourceMeta {
-    pub name: Option<String>,
-    pub description: Option<String>,
-}
-
 #[derive(Debug, Deserialize)]
 pub struct BundleDeclaration {
     pub name: String,
     pub path: String,
     pub description: Option<String>,
+    pub homepage: Option<String>,
+    pub repository: Option<String>,
+    pub license: Option<String>,
+    #[serde(default, deserialize_with = "crate::bundle::deserialize_version")]
+    pub version: Option<String>,
     pub tags: Option<Vec<String>>,
     #[serde(default)]
     pub paths: ComponentPaths,
+    /// Bundles to install before this one
+    #[serde(default)]
+    pub requires: Vec<String>,
 }
 
 #[derive(Debug, Deserialize, Default)]
@@ -50,7 +51,7 @@ impl ComponentPaths {
 }
 
 /// Load and parse an skm.toml manifest from a source root directory
-pub fn load_manifest(source_root: &PathBuf) -> Option<SourceManifest> {
+pub fn load_manifest(source_root: &Path) -> Option<SourceManifest> {
     let manifest_path = source_root.join("skm.toml");
     if !manifest_path.exists() {
         return None;
@@ -61,7 +62,7 @@ pub fn load_manifest(source_root: &PathBuf) -> Option<SourceManifest> {
 
 /// Build a Bundle from a manifest declaration by scanning its declared paths
 pub fn bundle_from_declaration(
-    source_root: &PathBuf,
+    source_root: &Path,
     decl: &BundleDeclaration,
 ) -> anyhow::Result<Bundle> {
     let bundle_root = source_root.join(&decl.path);
@@ -93,23 +94,26 @@ pub fn bundle_from_declaration(
         meta: BundleMeta {
             author: None,
             description: decl.description.clone(),
+            homepage: decl.homepage.clone(),
+            repository: decl.repository.clone(),
+            license: decl.license.clone(),
+            version: decl.version.clone(),
+            requires: decl.requires.clone(),
+            tags: decl.tags.clone().unwrap_or_default(),
         },
     })
 }
 
 /// Scan a component directory for skill files.
 /// Handles BOTH flat .md files AND {name}/SKILL.md directory format.
-fn scan_component_dir(dir: &PathBuf, skill_type: SkillType) -> anyhow::Result<Vec<SkillFile>> {
+fn scan_component_dir(dir: &Path, skill_type: SkillType) -> anyhow::Result<Vec<SkillFile>> {
     if !dir.exists() {
         return Ok(vec![]);
     }
 
     let mut files = vec![];
 
-    for entry in std::fs::read_dir(dir)? {
-        let entry = entry?;
-        let path = entry.path();
-
+    for path in crate::bundle::dir_entries(dir)? {
         if path.is_file() && path.extension().is_some_and(|e| e == "md" || e == "mdc") {
             // Flat .md file (e.g., agents/base/review-agent.md)
             let name = path
@@ -192,7 +196,7 @@ mod tests {
     #[test]
     fn test_load_manifest_not_present() {
         let dir = tempdir().unwrap();
-        assert!(load_manifest(&dir.path().to_path_buf()).is_none());
+        assert!(load_manifest(dir.path()).is_none());
     }
 
     #[test]
@@ -207,10 +211,9 @@ path = "src"
 "#,
         )
         .unwrap();
-        let manifest = load_manifest(&dir.path().to_path_buf()).unwrap();
+        let manifest = load_manifest(dir.path()).unwrap();
         assert_eq!(manifest.bundles.len(), 1);
         assert_eq!(manifest.bundles[0].name, "my-bundle");
-        assert!(manifest.source.is_none());
     }
 
     #[test]
@@ -241,11 +244,8 @@ path = "plugins/b"
 "#,
         )
         .unwrap();
-        let manifest = load_manifest(&dir.path().to_path_buf()).unwrap();
-        assert_eq!(
-            manifest.source.as_ref().unwrap().name.as_deref(),
-            Some("test-source")
-        );
+        // The `[source]` table is accepted but not used
+        let manifest = load_manifest(dir.path()).unwrap();
         assert_eq!(manifest.bundles.len(), 2);
         assert_eq!(manifest.bundles[0].paths.skills_dir(), "skills/base");
         assert_eq!(manifest.bundles[1].paths.skills_dir(), "skills"); // default
@@ -334,6 +334,10 @@ path = "plugins/b"
             name: "synapse-docs".to_string(),
             path: "plugins/docs".to_string(),
             description: Some("Documentation plugin".to_string()),
+            homepage: None,
+            repository: None,
+            license: None,
+            version: Some("2.1".to_string()),
             tags: None,
             paths: ComponentPaths {
                 skills: Some("skills/base".to_string()),
@@ -341,10 +345,13 @@ path = "plugins/b"
                 commands: Some("commands/base".to_string()),
                 rules: Some("rules/base".to_string()),
             },
+            requires: vec!["synapse-core".to_string()],
         };
 
-        let bundle = bundle_from_declaration(&dir.path().to_path_buf(), &decl).unwrap();
+        let bundle = bundle_from_declaration(dir.path(), &decl).unwrap();
         assert_eq!(bundle.name, "synapse-docs");
+        assert_eq!(bundle.meta.requires, vec!["synapse-core"]);
+        assert_eq!(bundle.meta.version.as_deref(), Some("2.1"));
         assert_eq!(bundle.skills.len(), 1);
         assert_eq!(bundle.agents.len(), 1);
         assert_eq!(
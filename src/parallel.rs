@@ -0,0 +1,81 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// Worker count used when neither `--jobs` nor the `jobs` config key is set
+pub fn default_jobs() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+}
+
+/// Apply `f` to every item on up to `jobs` threads, returning the results in
+/// input order. With `jobs <= 1` the items are processed one after another on
+/// the calling thread.
+pub fn map<T, R, F>(items: &[T], jobs: usize, f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let workers = jobs.min(items.len());
+    if workers <= 1 {
+        return items.iter().map(f).collect();
+    }
+
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<R>>> = Mutex::new(items.iter().map(|_| None).collect());
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(item) = items.get(i) else {
+                    break;
+                };
+                let result = f(item);
+                results.lock().unwrap()[i] = Some(result);
+            });
+        }
+    });
+
+    results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|r| r.expect("every item is processed before the scope ends"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_one_job_runs_in_order_on_the_caller() {
+        let items: Vec<u64> = (0..8).collect();
+        let started = Mutex::new(Vec::new());
+        let caller = std::thread::current().id();
+
+        let results = map(&items, 1, |&n| {
+            started.lock().unwrap().push(n);
+            // Later items finish sooner; only sequential runs keep the start order
+            std::thread::sleep(Duration::from_millis(8 - n));
+            (n * 10, std::thread::current().id())
+        });
+
+        assert_eq!(started.into_inner().unwrap(), items);
+        assert!(results.iter().all(|(_, thread)| *thread == caller));
+        let values: Vec<u64> = results.into_iter().map(|(v, _)| v).collect();
+        assert_eq!(values, vec![0, 10, 20, 30, 40, 50, 60, 70]);
+    }
+
+    #[test]
+    fn test_many_jobs_keep_result_order() {
+        let items: Vec<u64> = (0..8).collect();
+        let results = map(&items, 4, |&n| {
+            std::thread::sleep(Duration::from_millis(8 - n));
+            n * 10
+        });
+        assert_eq!(results, vec![0, 10, 20, 30, 40, 50, 60, 70]);
+    }
+}
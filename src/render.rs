@@ -0,0 +1,91 @@
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+use crate::bundle::{Bundle, SkillType};
+use crate::install::InstallOptions;
+use crate::target::{Output, Tool, WriteOptions};
+
+/// Write every item of `types` in `bundle`, transformed as `tool` would install
+/// it, flat into `out_dir` as `{bundle}-{type}-{name}.md` (`skm render`).
+/// Returns the files written.
+pub fn render_bundle(
+    bundle: &Bundle,
+    tool: &Tool,
+    types: &[SkillType],
+    out_dir: &Path,
+    opts: &InstallOptions,
+) -> Result<Vec<PathBuf>> {
+    std::fs::create_dir_all(out_dir)
+        .with_context(|| format!("Failed to create {}", out_dir.display()))?;
+
+    let mut written = Vec::new();
+    for skill_type in SkillType::ALL {
+        if !types.contains(&skill_type) {
+            continue;
+        }
+        for skill in bundle.files_of_type(skill_type) {
+            let write_opts = WriteOptions {
+                output: Output::dry_run(),
+                overwrite: true,
+                ..opts.write_options()
+            };
+            // Install into an in-memory project to get the transformed content
+            let dest = tool.write_file_with(Path::new(""), &bundle.name, skill, &write_opts)?;
+            let content = write_opts.output.buffered(&dest).unwrap_or_default();
+
+            let file_name = format!("{}-{}-{}.md", bundle.name, skill_type.as_str(), skill.name);
+            let path = out_dir.join(file_name);
+            std::fs::write(&path, content)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            written.push(path);
+        }
+    }
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_render_writes_flat_transformed_files() {
+        let src = tempdir().unwrap();
+        let bundle_dir = src.path().join("pdf");
+        for (dir, file, content) in [
+            ("skills", "fill.md", "# Fill forms\n\nFill in PDFs.\n"),
+            ("agents", "reader.md", "---\nname: reader\ntools: Read, Grep\n---\nReads.\n"),
+            ("commands", "extract.md", "# Extract\n"),
+        ] {
+            fs::create_dir_all(bundle_dir.join(dir)).unwrap();
+            fs::write(bundle_dir.join(dir).join(file), content).unwrap();
+        }
+        let bundle = Bundle::from_path(bundle_dir).unwrap();
+        let out = tempdir().unwrap();
+        let types = [SkillType::Skill, SkillType::Agent];
+
+        let written = render_bundle(
+            &bundle,
+            &Tool::OpenCode,
+            &types,
+            out.path(),
+            &InstallOptions::default(),
+        )
+        .unwrap();
+
+        let mut names: Vec<String> = fs::read_dir(out.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["pdf-agent-reader.md", "pdf-skill-fill.md"]);
+        assert_eq!(written.len(), 2);
+
+        let skill = fs::read_to_string(out.path().join("pdf-skill-fill.md")).unwrap();
+        assert!(skill.starts_with("---\nname: pdf-fill\ndescription: \"Fill forms\"\n---\n"));
+        let agent = fs::read_to_string(out.path().join("pdf-agent-reader.md")).unwrap();
+        assert!(agent.contains("tools:\n  read: true\n  grep: true\n"), "{}", agent);
+        assert!(!out.path().join(".opencode").exists());
+    }
+}
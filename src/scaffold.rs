@@ -0,0 +1,220 @@
+use anyhow::Result;
+use colored::Colorize;
+use dialoguer::{theme::ColorfulTheme, Input, Select};
+use std::path::{Path, PathBuf};
+
+use crate::bundle::{Bundle, SkillType};
+use crate::transform::{yaml_quote, yaml_scalar};
+
+/// A skill, agent, command or rule to create with `skm new`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewItem {
+    pub skill_type: SkillType,
+    pub name: String,
+    pub description: String,
+    /// Bundle folder to create it in (default: one named after the item)
+    pub bundle: Option<String>,
+}
+
+impl NewItem {
+    /// Fill in whatever wasn't given on the command line by prompting
+    pub fn prompt_missing(
+        skill_type: Option<SkillType>,
+        name: Option<String>,
+        description: Option<String>,
+        bundle: Option<String>,
+    ) -> Result<Self> {
+        let theme = ColorfulTheme::default();
+        let skill_type = match skill_type {
+            Some(t) => t,
+            None => {
+                let names: Vec<&str> = SkillType::ALL.iter().map(|t| t.as_str()).collect();
+                let selection = Select::with_theme(&theme)
+                    .with_prompt("Type")
+                    .items(&names)
+                    .default(0)
+                    .interact()?;
+                SkillType::ALL[selection]
+            }
+        };
+        let name = match name {
+            Some(n) => n,
+            None => Input::with_theme(&theme)
+                .with_prompt("Name")
+                .validate_with(|n: &String| check_name(n))
+                .interact_text()?,
+        };
+        let description = match description {
+            Some(d) => d,
+            None => Input::with_theme(&theme)
+                .with_prompt("Description")
+                .interact_text()?,
+        };
+        Ok(NewItem {
+            skill_type,
+            name: name.trim().to_string(),
+            description: description.trim().to_string(),
+            bundle,
+        })
+    }
+
+    /// Where the item goes in the local source at `source_root`. Anthropic-format
+    /// sources take skills as `skills/{name}/SKILL.md`; otherwise the item goes in
+    /// a flat bundle folder, e.g. `{bundle}/commands/{name}.md`.
+    pub fn path_in(&self, source_root: &Path) -> Result<PathBuf> {
+        check_name(&self.name).map_err(|e| anyhow::anyhow!(e))?;
+        if let Some(bundle) = &self.bundle {
+            check_name(bundle).map_err(|e| anyhow::anyhow!("Bundle {}", e))?;
+        }
+
+        let anthropic = Bundle::is_anthropic_format(source_root);
+        if anthropic && self.skill_type == SkillType::Skill && self.bundle.is_none() {
+            return Ok(source_root.join("skills").join(&self.name).join("SKILL.md"));
+        }
+        if anthropic
+            || Bundle::is_resources_format(source_root)
+            || crate::manifest::load_manifest(source_root).is_some()
+        {
+            anyhow::bail!(
+                "{} doesn't use the flat bundle layout; create the {} by hand",
+                source_root.display(),
+                self.skill_type.as_str()
+            );
+        }
+
+        let bundle = self.bundle.as_deref().unwrap_or(&self.name);
+        let type_dir = source_root.join(bundle).join(self.skill_type.dir_name());
+        Ok(match self.skill_type {
+            SkillType::Skill => type_dir.join(&self.name).join("SKILL.md"),
+            _ => type_dir.join(format!("{}.md", self.name)),
+        })
+    }
+
+    /// The file content: frontmatter and a starting body
+    pub fn render(&self) -> String {
+        let description = yaml_quote(&self.description);
+        let frontmatter = match self.skill_type {
+            SkillType::Skill | SkillType::Agent => {
+                format!("name: {}\ndescription: {}\n", yaml_scalar(&self.name), description)
+            }
+            SkillType::Command | SkillType::Rule => format!("description: {}\n", description),
+        };
+        format!(
+            "---\n{}---\n\n# {}\n\n{}\n",
+            frontmatter, self.name, self.description
+        )
+    }
+
+    /// Write the item into the source at `source_root`, refusing to replace an
+    /// existing item of the same name. Returns the created file.
+    pub fn create(&self, source_root: &Path) -> Result<PathBuf> {
+        let path = self.path_in(source_root)?;
+        // Either form (`{name}.md` or `{name}/`) counts as taken
+        let folder = match self.skill_type {
+            SkillType::Skill => path.parent().map(Path::to_path_buf),
+            _ => Some(path.with_extension("")),
+        };
+        let flat = folder.as_ref().map(|f| f.with_extension("md"));
+        if [Some(path.clone()), folder, flat].iter().flatten().any(|p| p.exists()) {
+            anyhow::bail!(
+                "A {} named `{}` already exists in {}",
+                self.skill_type.as_str(),
+                self.name,
+                source_root.display()
+            );
+        }
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, self.render())?;
+        Ok(path)
+    }
+}
+
+/// Why `name` can't name an item or bundle folder, if it can't
+fn check_name(name: &str) -> std::result::Result<(), String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("name must not be empty".to_string());
+    }
+    if name.starts_with('.') || name.contains(['/', '\\', ':']) {
+        return Err(format!("name `{}` must not contain `/` or `:`, or start with `.`", name));
+    }
+    Ok(())
+}
+
+/// Print where `skm new` put the item and how to install it
+pub fn print_created(item: &NewItem, path: &Path) {
+    println!(
+        "{} Created {} {}",
+        "✓".green(),
+        item.skill_type.as_str(),
+        path.display().to_string().cyan()
+    );
+    let bundle = item.bundle.as_deref().unwrap_or(&item.name);
+    println!("  Install it with: skm {}", bundle);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn item(skill_type: SkillType, name: &str) -> NewItem {
+        NewItem {
+            skill_type,
+            name: name.to_string(),
+            description: "Checks \"things\": twice".to_string(),
+            bundle: None,
+        }
+    }
+
+    /// The parsed YAML frontmatter of a generated file
+    fn frontmatter(content: &str) -> serde_yaml::Mapping {
+        let rest = content.strip_prefix("---\n").unwrap();
+        let (yaml, _) = rest.split_once("---\n").unwrap();
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn test_new_items_have_parseable_frontmatter() {
+        let source = tempdir().unwrap();
+
+        let skill = item(SkillType::Skill, "checker").create(source.path()).unwrap();
+        assert_eq!(skill, source.path().join("checker/skills/checker/SKILL.md"));
+        let fm = frontmatter(&fs::read_to_string(&skill).unwrap());
+        assert_eq!(fm["name"], "checker");
+        assert_eq!(fm["description"], "Checks \"things\": twice");
+
+        let command = NewItem {
+            bundle: Some("tools".to_string()),
+            ..item(SkillType::Command, "deploy")
+        };
+        let path = command.create(source.path()).unwrap();
+        assert_eq!(path, source.path().join("tools/commands/deploy.md"));
+        let fm = frontmatter(&fs::read_to_string(&path).unwrap());
+        assert_eq!(fm["description"], "Checks \"things\": twice");
+
+        // The new items are picked up as bundles of the source
+        let bundle = Bundle::from_path(source.path().join("tools")).unwrap();
+        assert_eq!(bundle.commands.len(), 1);
+        let bundle = Bundle::from_path(source.path().join("checker")).unwrap();
+        assert_eq!(bundle.skills[0].name, "checker");
+    }
+
+    #[test]
+    fn test_new_refuses_existing_name() {
+        let source = tempdir().unwrap();
+        let commands = source.path().join("deploy/commands");
+        fs::create_dir_all(&commands).unwrap();
+        fs::write(commands.join("deploy.md"), "# Mine").unwrap();
+
+        let err = item(SkillType::Command, "deploy").create(source.path()).unwrap_err();
+        assert!(err.to_string().contains("already exists"), "{}", err);
+        assert_eq!(fs::read_to_string(commands.join("deploy.md")).unwrap(), "# Mine");
+
+        assert!(item(SkillType::Rule, "../escape").create(source.path()).is_err());
+    }
+}
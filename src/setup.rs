@@ -3,6 +3,7 @@ use colored::Colorize;
 use dialoguer::{theme::ColorfulTheme, Input, Select};
 
 use crate::config::{Config, SourceConfig};
+use crate::target::Tool;
 
 /// Run the first-time setup wizard
 pub fn run_setup_wizard() -> Result<Config> {
@@ -31,7 +32,7 @@ pub fn run_setup_wizard() -> Result<Config> {
             let path = "~/.claude-skills".to_string();
             println!();
             println!("  {} {}", "Adding source:".dimmed(), path);
-            vec![SourceConfig::Local { path, name: None }]
+            vec![SourceConfig::local(path, None)]
         }
         1 => {
             // Custom path
@@ -43,13 +44,13 @@ pub fn run_setup_wizard() -> Result<Config> {
                 path
             } else {
                 // Make relative paths absolute
-                let cwd = std::env::current_dir()?;
+                let cwd = crate::config::working_dir("Enter an absolute path instead")?;
                 cwd.join(&path).to_string_lossy().to_string()
             };
 
             println!();
             println!("  {} {}", "Adding source:".dimmed(), path);
-            vec![SourceConfig::Local { path, name: None }]
+            vec![SourceConfig::local(path, None)]
         }
         2 => {
             // Skip
@@ -74,3 +75,87 @@ pub fn run_setup_wizard() -> Result<Config> {
 
     Ok(config)
 }
+
+/// Re-run setup on an existing config (`skm setup`): show its sources, choose
+/// the default tool and optionally add a source. Passing `tool` skips the prompts.
+pub fn run_setup_edit(mut config: Config, tool: Option<Tool>) -> Result<Config> {
+    println!();
+    println!("{}", "Current sources:".bold());
+    if config.source_configs().is_empty() {
+        println!("  {}", "(none)".dimmed());
+    }
+    for (i, source) in config.source_configs().iter().enumerate() {
+        match source.name() {
+            Some(name) => println!(
+                "  {}. {} {}",
+                i + 1,
+                source.display(),
+                format!("({})", name).dimmed()
+            ),
+            None => println!("  {}. {}", i + 1, source.display()),
+        }
+    }
+    println!();
+
+    let (tool, new_source) = match tool {
+        Some(tool) => (tool, None),
+        None => {
+            let current = Tool::from_id(&config.default_tool).unwrap_or(Tool::Claude);
+            let names: Vec<&str> = Tool::ALL.iter().map(|t| t.name()).collect();
+            let selection = Select::with_theme(&ColorfulTheme::default())
+                .with_prompt("Default tool")
+                .items(&names)
+                .default(Tool::ALL.iter().position(|t| *t == current).unwrap_or(0))
+                .interact()?;
+
+            let add: String = Input::with_theme(&ColorfulTheme::default())
+                .with_prompt("Add a source (path or git URL, empty to skip)")
+                .allow_empty(true)
+                .interact_text()?;
+            let add = add.trim();
+            (Tool::ALL[selection], (!add.is_empty()).then(|| add.to_string()))
+        }
+    };
+
+    apply_setup(&mut config, tool, new_source)?;
+    config.save()?;
+
+    let config_path = Config::config_path()?;
+    println!();
+    println!("{} {}", "Config saved to:".green(), config_path.display());
+    println!();
+
+    Ok(config)
+}
+
+/// Apply `skm setup` choices to a config
+fn apply_setup(config: &mut Config, tool: Tool, new_source: Option<String>) -> Result<()> {
+    config.default_tool = tool.id().to_string();
+    if let Some(location) = new_source {
+        config.add_source(SourceConfig::from_location(location, None))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_setup_updates_existing_config() {
+        let mut config: Config = toml::from_str(
+            "default_tool = \"claude\"\n\n[[sources]]\ntype = \"local\"\npath = \"~/skills\"\n",
+        )
+        .unwrap();
+
+        let url = "https://github.com/example/skills".to_string();
+        apply_setup(&mut config, Tool::Cursor, Some(url)).unwrap();
+        // Adding a source that is already configured keeps a single entry
+        apply_setup(&mut config, Tool::Cursor, Some("~/skills".to_string())).unwrap();
+
+        let saved: Config = toml::from_str(&toml::to_string_pretty(&config).unwrap()).unwrap();
+        assert_eq!(saved.default_tool, "cursor");
+        let locations: Vec<&str> = saved.source_configs().iter().map(|s| s.display()).collect();
+        assert_eq!(locations, vec!["~/skills", "https://github.com/example/skills"]);
+    }
+}
@@ -1,26 +1,124 @@
 use anyhow::{Context, Result};
 use colored::Colorize;
-use std::path::PathBuf;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 
-use crate::bundle::Bundle;
+use crate::bundle::{Bundle, SkillType};
+use crate::git_auth::GitCredentials;
 
 /// Trait for skill sources (local directories, git repos, etc.)
-pub trait Source {
+pub trait Source: Send + Sync {
     /// List all bundles in this source
     fn list_bundles(&self) -> Result<Vec<Bundle>>;
 
     /// Get display path for this source
     fn display_path(&self) -> String;
+
+    /// Revision the bundles are read from (a git commit), if the source has one
+    fn revision(&self) -> Option<String> {
+        None
+    }
+
+    /// Read one of this source's files (a `SkillFile::path` from `list_bundles`).
+    /// Sources whose files aren't plain files on disk, such as archives, override
+    /// this so previews don't need them extracted.
+    fn read_file(&self, path: &Path) -> Result<String> {
+        std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))
+    }
+
+    /// Names of the bundles whose files changed since `since` (a git revision
+    /// like `HEAD~5`, or a `YYYY-MM-DD` date), or `None` for sources without history
+    fn changed_since(&self, _since: &str) -> Result<Option<HashSet<String>>> {
+        Ok(None)
+    }
+}
+
+/// Limits which bundles another source contributes (per-source `include`/`exclude`)
+pub struct FilteredSource {
+    inner: Box<dyn Source>,
+    include: Vec<String>,
+    exclude: Vec<String>,
+}
+
+impl FilteredSource {
+    pub fn new(inner: Box<dyn Source>, include: Vec<String>, exclude: Vec<String>) -> Self {
+        FilteredSource {
+            inner,
+            include,
+            exclude,
+        }
+    }
+
+    fn allows(&self, bundle_name: &str) -> bool {
+        (self.include.is_empty() || self.include.iter().any(|n| n == bundle_name))
+            && !self.exclude.iter().any(|n| n == bundle_name)
+    }
+}
+
+impl Source for FilteredSource {
+    fn list_bundles(&self) -> Result<Vec<Bundle>> {
+        let bundles = self.inner.list_bundles()?;
+        Ok(bundles.into_iter().filter(|b| self.allows(&b.name)).collect())
+    }
+
+    fn display_path(&self) -> String {
+        self.inner.display_path()
+    }
+
+    fn revision(&self) -> Option<String> {
+        self.inner.revision()
+    }
+
+    fn read_file(&self, path: &Path) -> Result<String> {
+        self.inner.read_file(path)
+    }
+
+    fn changed_since(&self, since: &str) -> Result<Option<HashSet<String>>> {
+        self.inner.changed_since(since)
+    }
 }
 
 /// A local directory source
 pub struct LocalSource {
     path: PathBuf,
+    /// Type given to loose `.md` files when no folder uses the component layout
+    loose_type: SkillType,
 }
 
 impl LocalSource {
     pub fn new(path: PathBuf) -> Self {
-        LocalSource { path }
+        LocalSource {
+            path,
+            loose_type: SkillType::Command,
+        }
+    }
+
+    /// Treat loose `.md` files as this type instead of commands
+    pub fn with_loose_type(mut self, loose_type: SkillType) -> Self {
+        self.loose_type = loose_type;
+        self
+    }
+
+    /// Fallback for sources without `skills/`, `agents/`, ... subdirectories:
+    /// each folder of loose `.md` files is a bundle, as is the root itself.
+    fn list_loose_bundles(&self) -> Result<Vec<Bundle>> {
+        let mut bundles = vec![];
+        for path in crate::bundle::dir_entries(&self.path)? {
+            let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+            if !path.is_dir() || name.starts_with('.') || name == "shell" {
+                continue;
+            }
+            match Bundle::from_loose_files(path, self.loose_type) {
+                Ok(bundle) if !bundle.is_empty() => bundles.push(bundle),
+                _ => continue,
+            }
+        }
+
+        let root = Bundle::from_loose_files(self.path.clone(), self.loose_type)?;
+        if !root.is_empty() {
+            bundles.push(root);
+        }
+        Ok(bundles)
     }
 }
 
@@ -84,10 +182,7 @@ impl Source for LocalSource {
 
         let mut bundles = vec![];
 
-        for entry in std::fs::read_dir(&self.path)? {
-            let entry = entry?;
-            let path = entry.path();
-
+        for path in crate::bundle::dir_entries(&self.path)? {
             // Skip non-directories
             if !path.is_dir() {
                 continue;
@@ -100,13 +195,28 @@ impl Source for LocalSource {
                 continue;
             }
 
+            if crate::bundle::symlink_escapes(&path, &self.path) {
+                crate::bundle::warn_symlink_escapes(&path);
+                continue;
+            }
+
             // Try to create a bundle from this directory
-            match Bundle::from_path(path) {
+            match Bundle::from_path(path.clone()) {
                 Ok(bundle) if !bundle.is_empty() => bundles.push(bundle),
-                _ => continue,
+                Ok(_) => continue,
+                Err(e) => eprintln!(
+                    "  {}: failed to scan bundle {}: {}",
+                    "Warning".yellow(),
+                    path.display(),
+                    e
+                ),
             }
         }
 
+        if bundles.is_empty() {
+            bundles = self.list_loose_bundles()?;
+        }
+
         // Sort bundles by name
         bundles.sort_by(|a, b| a.name.cmp(&b.name));
 
@@ -128,25 +238,54 @@ impl Source for LocalSource {
 /// A git repository source
 pub struct GitSource {
     url: String,
+    /// Where clones and fetches go; `url` unless a mirror rewrites it
+    clone_url: String,
     cache_path: PathBuf,
+    /// Read bundles from this commit instead of the cached branch head
+    pin: Option<String>,
 }
 
 impl GitSource {
     pub fn new(url: String) -> Result<Self> {
         let cache_path = Self::cache_path_for_url(&url)?;
-        Ok(GitSource { url, cache_path })
+        Ok(GitSource {
+            clone_url: url.clone(),
+            url,
+            cache_path,
+            pin: None,
+        })
     }
 
-    /// Get the cache directory for a git URL
-    fn cache_path_for_url(url: &str) -> Result<PathBuf> {
-        let cache_dir = directories::ProjectDirs::from("", "", "skm")
+    /// Clone `https://github.com/...` URLs through `mirror` instead. The original
+    /// URL is still what's displayed and what the cache directory is named after.
+    pub fn with_mirror(mut self, mirror: Option<&str>) -> Self {
+        if let Some(mirror) = mirror {
+            if let Some(path) = self.url.strip_prefix("https://github.com/") {
+                self.clone_url = format!("{}/{}", mirror.trim_end_matches('/'), path);
+            }
+        }
+        self
+    }
+
+    /// Pin this source to a specific commit (used when syncing from `skm.lock`)
+    pub fn pinned(mut self, commit: String) -> Self {
+        self.pin = Some(commit);
+        self
+    }
+
+    /// Directory holding every git source's cached clone
+    pub fn cache_root() -> Result<PathBuf> {
+        Ok(directories::ProjectDirs::from("", "", "skm")
             .ok_or_else(|| anyhow::anyhow!("Could not determine cache directory"))?
             .cache_dir()
-            .to_path_buf();
+            .to_path_buf())
+    }
 
+    /// Get the cache directory for a git URL
+    fn cache_path_for_url(url: &str) -> Result<PathBuf> {
         // Parse URL to create a path like github.com/user/repo
         let path_suffix = Self::url_to_path(url);
-        Ok(cache_dir.join(path_suffix))
+        Ok(Self::cache_root()?.join(path_suffix))
     }
 
     /// Convert a git URL to a filesystem path
@@ -181,18 +320,78 @@ impl GitSource {
             std::fs::create_dir_all(parent)?;
         }
 
-        // Clone the repository
-        git2::Repository::clone(&self.url, &self.cache_path)
-            .with_context(|| format!("Failed to clone {}", self.url))?;
-
+        self.clone_into(&self.cache_path)?;
         Ok(())
     }
 
+    /// Clone the repository to `path`, authenticating as needed
+    fn clone_into(&self, path: &Path) -> Result<git2::Repository> {
+        let credentials = GitCredentials::from_env();
+        let mut builder = git2::build::RepoBuilder::new();
+        builder.fetch_options(credentials.fetch_options());
+        let repo = builder
+            .clone(&self.clone_url, path)
+            .with_context(|| format!("Failed to clone {}", self.clone_url))?;
+        Ok(repo)
+    }
+
     /// Get the URL for display
     pub fn url(&self) -> &str {
         &self.url
     }
 
+    /// Local clone location under the skm cache directory
+    pub fn cache_path(&self) -> &Path {
+        &self.cache_path
+    }
+
+    /// Commit currently checked out in the cache
+    pub fn head_commit(&self) -> Result<String> {
+        self.ensure_cloned()?;
+        let repo = git2::Repository::open(&self.cache_path)
+            .with_context(|| format!("Failed to open repository at {:?}", self.cache_path))?;
+        let commit = repo.head()?.peel_to_commit()?;
+        Ok(commit.id().to_string())
+    }
+
+    /// Check out `commit` into its own directory next to the cache, so pinning
+    /// never moves the shared clone that `skm update` pulls
+    fn pinned_checkout(&self, commit: &str) -> Result<PathBuf> {
+        let repo_name = self
+            .cache_path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let pinned_path = self
+            .cache_path
+            .with_file_name(format!("{}@{}", repo_name, commit));
+        if pinned_path.exists() {
+            return Ok(pinned_path);
+        }
+
+        println!("  {} {} at {}...", "Cloning".cyan(), self.url, commit);
+        let checkout = || -> Result<()> {
+            let oid = git2::Oid::from_str(commit)
+                .with_context(|| format!("Invalid commit id: {}", commit))?;
+            let repo = self.clone_into(&pinned_path)?;
+            let target = repo
+                .find_commit(oid)
+                .with_context(|| format!("Commit {} not found in {}", commit, self.url))?;
+            repo.checkout_tree(
+                target.as_object(),
+                Some(git2::build::CheckoutBuilder::default().force()),
+            )?;
+            repo.set_head_detached(oid)?;
+            Ok(())
+        };
+
+        if let Err(e) = checkout() {
+            let _ = std::fs::remove_dir_all(&pinned_path);
+            return Err(e);
+        }
+        Ok(pinned_path)
+    }
+
     /// Pull latest changes from the remote
     pub fn pull(&self) -> Result<bool> {
         if !self.cache_path.exists() {
@@ -205,7 +404,10 @@ impl GitSource {
 
         // Fetch from origin
         let mut remote = repo.find_remote("origin")?;
-        remote.fetch(&["HEAD"], None, None)?;
+        let credentials = GitCredentials::from_env();
+        remote
+            .fetch(&["HEAD"], Some(&mut credentials.fetch_options()), None)
+            .with_context(|| format!("Failed to fetch {}", self.url))?;
 
         // Get the fetch head
         let fetch_head = repo.find_reference("FETCH_HEAD")?;
@@ -228,16 +430,301 @@ impl GitSource {
 
         Ok(true)
     }
+
+    /// Where bundles are read from: the pinned commit's checkout, or the clone
+    fn checkout(&self) -> Result<PathBuf> {
+        match &self.pin {
+            Some(commit) => self.pinned_checkout(commit),
+            None => {
+                // Ensure the repo is cloned first
+                self.ensure_cloned()?;
+                Ok(self.cache_path.clone())
+            }
+        }
+    }
+
+    /// Who to credit for bundles without an author: the owner in the URL
+    /// (`github.com/owner/repo`), or else the author of the checked-out commit
+    fn repo_author(&self, checkout: &Path) -> Option<String> {
+        let remote = self.url.starts_with("https://")
+            || self.url.starts_with("http://")
+            || self.url.starts_with("ssh://")
+            || self.url.starts_with("git@");
+        if remote {
+            let path = Self::url_to_path(self.url.trim_start_matches("http://"))
+                .trim_start_matches("ssh://")
+                .to_string();
+            let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+            if let [_host, owner, _, ..] = segments.as_slice() {
+                return Some(owner.to_string());
+            }
+        }
+
+        let repo = git2::Repository::open(checkout).ok()?;
+        let commit = repo.head().ok()?.peel_to_commit().ok()?;
+        let name = commit.author().name()?.trim().to_string();
+        (!name.is_empty()).then_some(name)
+    }
+
+    /// Pull every source on up to `jobs` threads; results come back in input order
+    pub fn pull_all(sources: &[GitSource], jobs: usize) -> Vec<Result<bool>> {
+        crate::parallel::map(sources, jobs, GitSource::pull)
+    }
 }
 
 impl Source for GitSource {
     fn list_bundles(&self) -> Result<Vec<Bundle>> {
-        // Ensure the repo is cloned first
-        self.ensure_cloned()?;
+        let checkout = self.checkout()?;
 
         // Delegate to LocalSource for actual bundle discovery
-        let local = LocalSource::new(self.cache_path.clone());
-        local.list_bundles()
+        let local = LocalSource::new(checkout.clone());
+        let mut bundles = local.list_bundles()?;
+
+        // Bundles without an author are credited to the repo
+        if bundles.iter().any(|b| b.meta.author.is_none()) {
+            let author = self.repo_author(&checkout);
+            for bundle in bundles.iter_mut().filter(|b| b.meta.author.is_none()) {
+                bundle.meta.author = author.clone();
+            }
+        }
+        Ok(bundles)
+    }
+
+    fn display_path(&self) -> String {
+        self.url.clone()
+    }
+
+    fn revision(&self) -> Option<String> {
+        self.pin.clone().or_else(|| self.head_commit().ok())
+    }
+
+    fn changed_since(&self, since: &str) -> Result<Option<HashSet<String>>> {
+        let checkout = self.checkout()?;
+        let repo = git2::Repository::open(&checkout)
+            .with_context(|| format!("Failed to open repository at {:?}", checkout))?;
+        let changed = paths_changed_since(&repo, since)
+            .with_context(|| format!("Failed to read the history of {}", self.url))?;
+
+        let names = self
+            .list_bundles()?
+            .into_iter()
+            .filter(|bundle| {
+                let dir = bundle.path.strip_prefix(&checkout).unwrap_or(&bundle.path);
+                changed.iter().any(|path| path.starts_with(dir))
+            })
+            .map(|bundle| bundle.name)
+            .collect();
+        Ok(Some(names))
+    }
+}
+
+/// Paths, relative to the repository root, touched by the commits on HEAD
+/// after `since`: a revision (`HEAD~5`, a tag, a commit id) or a `YYYY-MM-DD`
+/// date, taken as midnight UTC
+fn paths_changed_since(repo: &git2::Repository, since: &str) -> Result<HashSet<PathBuf>> {
+    let mut walk = repo.revwalk()?;
+    walk.push_head()?;
+    let after = match repo.revparse_single(since) {
+        Ok(object) => {
+            walk.hide(object.peel_to_commit()?.id())?;
+            None
+        }
+        Err(_) => Some(parse_date(since).ok_or_else(|| {
+            anyhow::anyhow!(
+                "`{}` is neither a revision nor a date (expected e.g. HEAD~5 or 2024-01-31)",
+                since
+            )
+        })?),
+    };
+
+    let mut paths = HashSet::new();
+    for id in walk {
+        let commit = repo.find_commit(id?)?;
+        if after.is_some_and(|after| commit.time().seconds() < after) {
+            continue;
+        }
+        let parent_tree = match commit.parent(0) {
+            Ok(parent) => Some(parent.tree()?),
+            Err(_) => None,
+        };
+        let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&commit.tree()?), None)?;
+        for delta in diff.deltas() {
+            paths.extend(delta.old_file().path().map(Path::to_path_buf));
+            paths.extend(delta.new_file().path().map(Path::to_path_buf));
+        }
+    }
+    Ok(paths)
+}
+
+/// Seconds since the Unix epoch at midnight UTC of a `YYYY-MM-DD` date
+fn parse_date(date: &str) -> Option<i64> {
+    let mut parts = date.splitn(3, '-').map(|p| p.parse::<i64>().ok());
+    let (year, month, day) = (parts.next()??, parts.next()??, parts.next()??);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    // Days from civil date, counting years from March so leap days come last
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    Some((era * 146_097 + day_of_era - 719_468) * 86_400)
+}
+
+/// Archive formats an `ArchiveSource` can extract
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ArchiveFormat {
+    TarGz,
+    Zip,
+}
+
+impl ArchiveFormat {
+    /// The format named by a URL's extension (ignoring any query string)
+    fn from_url(url: &str) -> Option<Self> {
+        let path = url.split(['?', '#']).next().unwrap_or(url).to_lowercase();
+        if path.ends_with(".tar.gz") || path.ends_with(".tgz") {
+            Some(ArchiveFormat::TarGz)
+        } else if path.ends_with(".zip") {
+            Some(ArchiveFormat::Zip)
+        } else {
+            None
+        }
+    }
+}
+
+/// A `.tar.gz` or `.zip` of bundles published at a URL, such as a release
+/// tarball. It is downloaded and extracted into the cache once; clear the
+/// cache (`skm cache clear`) to fetch it again.
+pub struct ArchiveSource {
+    url: String,
+    format: ArchiveFormat,
+    cache_path: PathBuf,
+}
+
+impl ArchiveSource {
+    pub fn new(url: String) -> Result<Self> {
+        let format = ArchiveFormat::from_url(&url)
+            .ok_or_else(|| anyhow::anyhow!("Not a .tar.gz, .tgz or .zip URL: {}", url))?;
+        let cache_path = GitSource::cache_root()?.join(Self::url_to_path(&url));
+        Ok(ArchiveSource {
+            url,
+            format,
+            cache_path,
+        })
+    }
+
+    /// Whether `url` names an archive this source can read
+    pub fn is_archive_url(url: &str) -> bool {
+        let remote = ["https://", "http://", "file://"]
+            .iter()
+            .any(|scheme| url.starts_with(scheme));
+        remote && ArchiveFormat::from_url(url).is_some()
+    }
+
+    /// Convert an archive URL to a cache path, as `GitSource` does for repos:
+    /// https://example.com/skills.tar.gz -> example.com/skills.tar.gz
+    fn url_to_path(url: &str) -> String {
+        let url = url.split(['?', '#']).next().unwrap_or(url);
+        let path = url.split_once("://").map_or(url, |(_, rest)| rest);
+        path.trim_start_matches('/').replace(':', "_")
+    }
+
+    pub fn cache_path(&self) -> &Path {
+        &self.cache_path
+    }
+
+    /// Download and extract the archive unless it's already cached
+    pub fn ensure_extracted(&self) -> Result<()> {
+        if self.cache_path.exists() {
+            return Ok(());
+        }
+
+        println!("  {} {}...", "Downloading".cyan(), self.url);
+        let data = self.download()?;
+
+        // Extract next to the cache so a failed extraction leaves nothing behind
+        let parent = self.cache_path.parent().unwrap_or(Path::new("."));
+        std::fs::create_dir_all(parent)?;
+        let staging = staging_dir(parent)?;
+        self.extract(&data, &staging)
+            .with_context(|| format!("Failed to extract {}", self.url))?;
+        std::fs::rename(&staging, &self.cache_path).or_else(|e| {
+            let _ = std::fs::remove_dir_all(&staging);
+            // Another process may have extracted it first
+            if self.cache_path.exists() {
+                Ok(())
+            } else {
+                Err(e)
+            }
+        })?;
+        Ok(())
+    }
+
+    fn download(&self) -> Result<Vec<u8>> {
+        if let Some(path) = self.url.strip_prefix("file://") {
+            return std::fs::read(path).with_context(|| format!("Failed to read {}", path));
+        }
+
+        let response = ureq::get(&self.url)
+            .call()
+            .with_context(|| format!("Failed to download {}", self.url))?;
+        let mut data = Vec::new();
+        std::io::Read::read_to_end(&mut response.into_reader(), &mut data)
+            .with_context(|| format!("Failed to download {}", self.url))?;
+        Ok(data)
+    }
+
+    fn extract(&self, data: &[u8], dest: &Path) -> Result<()> {
+        match self.format {
+            ArchiveFormat::TarGz => {
+                let decoder = flate2::read::GzDecoder::new(data);
+                tar::Archive::new(decoder).unpack(dest)?;
+            }
+            ArchiveFormat::Zip => {
+                zip::ZipArchive::new(std::io::Cursor::new(data))?.extract(dest)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Where the bundles are: release archives usually wrap everything in one
+    /// `name-version/` folder, which is skipped unless it is a bundle itself
+    fn bundle_root(&self) -> PathBuf {
+        let entries = crate::bundle::dir_entries(&self.cache_path).unwrap_or_default();
+        let visible: Vec<&PathBuf> = entries
+            .iter()
+            .filter(|p| !p.file_name().is_some_and(|n| n.to_string_lossy().starts_with('.')))
+            .collect();
+        if let [only] = visible.as_slice() {
+            let is_bundle = SkillType::ALL.iter().any(|t| only.join(t.dir_name()).is_dir())
+                || only.join("meta.yaml").is_file();
+            if only.is_dir() && !is_bundle {
+                return only.to_path_buf();
+            }
+        }
+        self.cache_path.clone()
+    }
+}
+
+/// A fresh, uniquely named directory in `parent`
+fn staging_dir(parent: &Path) -> Result<PathBuf> {
+    for attempt in 0..100u32 {
+        let dir = parent.join(format!(".skm-extract-{}-{}", std::process::id(), attempt));
+        match std::fs::create_dir(&dir) {
+            Ok(()) => return Ok(dir),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e.into()),
+        }
+    }
+    anyhow::bail!("Could not create a directory in {}", parent.display())
+}
+
+impl Source for ArchiveSource {
+    fn list_bundles(&self) -> Result<Vec<Bundle>> {
+        self.ensure_extracted()?;
+        LocalSource::new(self.bundle_root()).list_bundles()
     }
 
     fn display_path(&self) -> String {
@@ -330,4 +817,336 @@ mod tests {
         assert_eq!(bundles[0].name, "Another Skill");
         assert_eq!(bundles[1].name, "My Skill");
     }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_unreadable_entry_keeps_other_bundles() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let dir = tempdir().unwrap();
+        for bundle in ["pdf", "tools"] {
+            let commands = dir.path().join(bundle).join("commands");
+            fs::create_dir_all(&commands).unwrap();
+            fs::write(commands.join("run.md"), "# Run").unwrap();
+        }
+        // A name that isn't UTF-8 used to fail the whole `tools` bundle
+        let bad = std::ffi::OsStr::from_bytes(b"\xffbad.md");
+        fs::write(dir.path().join("tools/commands").join(bad), "# Bad").unwrap();
+
+        let bundles = LocalSource::new(dir.path().to_path_buf()).list_bundles().unwrap();
+        let names: Vec<&str> = bundles.iter().map(|b| b.name.as_str()).collect();
+        assert_eq!(names, vec!["pdf", "tools"]);
+        assert_eq!(bundles[1].commands.len(), 1);
+        assert_eq!(bundles[1].commands[0].name, "run");
+    }
+
+    /// Make a repo at `path` with one commit per entry of `versions`, each
+    /// writing `bundle/commands/run.md`. Returns the commit ids in order.
+    fn init_repo_with_history(path: &Path, versions: &[&str]) -> Vec<String> {
+        let repo = git2::Repository::init(path).unwrap();
+        let sig = git2::Signature::now("test", "test@example.com").unwrap();
+        let commands = path.join("bundle/commands");
+        fs::create_dir_all(&commands).unwrap();
+
+        let mut ids = Vec::new();
+        for content in versions {
+            fs::write(commands.join("run.md"), content).unwrap();
+            let mut index = repo.index().unwrap();
+            index
+                .add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)
+                .unwrap();
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+            let parents: Vec<&git2::Commit> = parent.iter().collect();
+            let id = repo
+                .commit(Some("HEAD"), &sig, &sig, content, &tree, &parents)
+                .unwrap();
+            ids.push(id.to_string());
+        }
+        ids
+    }
+
+    #[test]
+    fn test_git_source_pinned_to_commit() {
+        let dir = tempdir().unwrap();
+        let origin = dir.path().join("origin");
+        let commits = init_repo_with_history(&origin, &["# v1", "# v2"]);
+
+        let url = origin.to_string_lossy().into_owned();
+        let source = GitSource {
+            clone_url: url.clone(),
+            url,
+            cache_path: dir.path().join("cache/origin"),
+            pin: None,
+        };
+        assert_eq!(source.head_commit().unwrap(), commits[1]);
+        assert_eq!(source.revision(), Some(commits[1].clone()));
+
+        let pinned = source.pinned(commits[0].clone());
+        let bundles = pinned.list_bundles().unwrap();
+        let run = &bundles[0].commands[0];
+        assert_eq!(fs::read_to_string(&run.path).unwrap(), "# v1");
+        assert_eq!(pinned.revision(), Some(commits[0].clone()));
+
+        // The shared cache still tracks the latest commit
+        assert_eq!(pinned.head_commit().unwrap(), commits[1]);
+        assert_eq!(
+            fs::read_to_string(dir.path().join("cache/origin/bundle/commands/run.md")).unwrap(),
+            "# v2"
+        );
+    }
+
+    #[test]
+    fn test_pull_all_reports_each_source() {
+        let dir = tempdir().unwrap();
+        let source_for = |name: &str| {
+            let url = dir.path().join(name).to_string_lossy().into_owned();
+            GitSource {
+                clone_url: url.clone(),
+                url,
+                cache_path: dir.path().join("cache").join(name),
+                pin: None,
+            }
+        };
+        init_repo_with_history(&dir.path().join("changed"), &["# v1"]);
+        init_repo_with_history(&dir.path().join("current"), &["# v1"]);
+        let sources = vec![source_for("changed"), source_for("current"), source_for("missing")];
+        sources[0].ensure_cloned().unwrap();
+        sources[1].ensure_cloned().unwrap();
+        init_repo_with_history(&dir.path().join("changed"), &["# v2"]);
+
+        let results = GitSource::pull_all(&sources, 3);
+        assert_eq!(results.len(), 3);
+        assert!(results[0].as_ref().unwrap());
+        assert!(!results[1].as_ref().unwrap());
+        assert!(results[2].is_err());
+        assert_eq!(
+            fs::read_to_string(dir.path().join("cache/changed/bundle/commands/run.md")).unwrap(),
+            "# v2"
+        );
+    }
+
+    #[test]
+    fn test_git_mirror_rewrites_clone_url_only() {
+        let dir = tempdir().unwrap();
+        let mirror = dir.path().join("mirror");
+        init_repo_with_history(&mirror.join("acme/skills"), &["# v1"]);
+
+        let url = "https://github.com/acme/skills".to_string();
+        let mut source = GitSource::new(url.clone())
+            .unwrap()
+            .with_mirror(Some(&format!("{}/", mirror.display())));
+        assert_eq!(source.clone_url, mirror.join("acme/skills").to_string_lossy());
+        assert_eq!(source.display_path(), url);
+        assert_eq!(source.url(), url);
+        assert_eq!(source.cache_path, GitSource::new(url).unwrap().cache_path);
+
+        // Clones come from the mirror
+        source.cache_path = dir.path().join("cache/github.com/acme/skills");
+        let bundles = source.list_bundles().unwrap();
+        assert_eq!(bundles[0].commands[0].name, "run");
+
+        // Only GitHub URLs are rewritten
+        let gitlab = GitSource::new("https://gitlab.com/acme/skills".to_string())
+            .unwrap()
+            .with_mirror(Some("https://mirror.internal"));
+        assert_eq!(gitlab.clone_url, gitlab.url);
+    }
+
+    #[test]
+    fn test_git_bundle_without_author_credits_repo() {
+        let dir = tempdir().unwrap();
+        let mirror = dir.path().join("mirror");
+        init_repo_with_history(&mirror.join("acme/skills"), &["# v1"]);
+
+        // The owner in a hosted URL
+        let mut source = GitSource::new("https://github.com/acme/skills".to_string())
+            .unwrap()
+            .with_mirror(Some(&format!("{}/", mirror.display())));
+        source.cache_path = dir.path().join("cache/github.com/acme/skills");
+        let bundles = source.list_bundles().unwrap();
+        assert_eq!(bundles[0].meta.author.as_deref(), Some("acme"));
+
+        // The last commit's author when the URL has no owner
+        let local = GitSource {
+            clone_url: mirror.join("acme/skills").to_string_lossy().to_string(),
+            url: mirror.join("acme/skills").to_string_lossy().to_string(),
+            cache_path: dir.path().join("cache/local"),
+            pin: None,
+        };
+        let bundles = local.list_bundles().unwrap();
+        assert_eq!(bundles[0].meta.author.as_deref(), Some("test"));
+    }
+
+    #[test]
+    fn test_changed_since_revision_or_date() {
+        let dir = tempdir().unwrap();
+        let origin = dir.path().join("origin");
+        let repo = git2::Repository::init(&origin).unwrap();
+        // Both bundles added on 2024-01-10; only `pdf` edited on 2024-03-05
+        let commits = [
+            (1_704_880_800, vec!["git/commands/commit.md", "pdf/commands/run.md"]),
+            (1_709_632_800, vec!["pdf/commands/run.md"]),
+        ];
+        for (i, (time, files)) in commits.iter().enumerate() {
+            for file in files {
+                let path = origin.join(file);
+                fs::create_dir_all(path.parent().unwrap()).unwrap();
+                fs::write(path, format!("# v{}", i)).unwrap();
+            }
+            let mut index = repo.index().unwrap();
+            index
+                .add_all(["*"].iter(), git2::IndexAddOption::DEFAULT, None)
+                .unwrap();
+            index.write().unwrap();
+            let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+            let sig = git2::Signature::new("test", "t@example.com", &git2::Time::new(*time, 0))
+                .unwrap();
+            let parent = repo.head().ok().and_then(|h| h.peel_to_commit().ok());
+            let parents: Vec<&git2::Commit> = parent.iter().collect();
+            repo.commit(Some("HEAD"), &sig, &sig, "change", &tree, &parents)
+                .unwrap();
+        }
+
+        let source = GitSource {
+            clone_url: origin.to_string_lossy().to_string(),
+            url: origin.to_string_lossy().to_string(),
+            cache_path: dir.path().join("cache"),
+            pin: None,
+        };
+        let changed = |since: &str| {
+            let mut names: Vec<String> =
+                source.changed_since(since).unwrap().unwrap().into_iter().collect();
+            names.sort();
+            names
+        };
+        assert_eq!(changed("HEAD~1"), vec!["pdf"]);
+        assert_eq!(changed("HEAD"), Vec::<String>::new());
+        assert_eq!(changed("2024-02-01"), vec!["pdf"]);
+        assert_eq!(changed("2024-01-10"), vec!["git", "pdf"]);
+
+        let err = source.changed_since("last-tuesday").unwrap_err();
+        assert!(format!("{:#}", err).contains("neither a revision nor a date"), "{:#}", err);
+        assert!(LocalSource::new(origin).changed_since("HEAD").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_parse_date() {
+        assert_eq!(parse_date("1970-01-01"), Some(0));
+        assert_eq!(parse_date("2024-01-10"), Some(1_704_844_800));
+        assert_eq!(parse_date("2000-03-01"), Some(951_868_800));
+        assert_eq!(parse_date("2024-13-01"), None);
+        assert_eq!(parse_date("HEAD~5"), None);
+    }
+
+    /// An archive source for a fixture file, cached under `dir`
+    fn archive_source(dir: &Path, archive: &Path) -> ArchiveSource {
+        let mut source = ArchiveSource::new(format!("file://{}", archive.display())).unwrap();
+        source.cache_path = dir.join("cache").join(ArchiveSource::url_to_path(&source.url));
+        source
+    }
+
+    #[test]
+    fn test_archive_source_lists_tarball_bundles() {
+        let dir = tempdir().unwrap();
+        let archive = dir.path().join("skills-1.0.tar.gz");
+        let encoder = flate2::write::GzEncoder::new(
+            fs::File::create(&archive).unwrap(),
+            flate2::Compression::default(),
+        );
+        let mut tar = tar::Builder::new(encoder);
+        for (path, content) in [
+            ("skills-1.0/pdf/commands/run.md", "# Run"),
+            ("skills-1.0/pdf/skills/fill.md", "# Fill"),
+            ("skills-1.0/git/commands/commit.md", "# Commit"),
+        ] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            tar.append_data(&mut header, path, content.as_bytes()).unwrap();
+        }
+        tar.into_inner().unwrap().finish().unwrap();
+
+        let source = archive_source(dir.path(), &archive);
+        let bundles = source.list_bundles().unwrap();
+        let names: Vec<&str> = bundles.iter().map(|b| b.name.as_str()).collect();
+        assert_eq!(names, vec!["git", "pdf"], "the release folder is skipped");
+        assert_eq!(bundles[1].commands[0].name, "run");
+        assert_eq!(bundles[1].skills[0].name, "fill");
+
+        // Later listings read the extracted cache
+        fs::remove_file(&archive).unwrap();
+        assert_eq!(source.list_bundles().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_archive_source_lists_zip_bundles() {
+        let dir = tempdir().unwrap();
+        let archive = dir.path().join("skills.zip");
+        let mut zip = zip::ZipWriter::new(fs::File::create(&archive).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        zip.start_file("pdf/commands/run.md", options).unwrap();
+        std::io::Write::write_all(&mut zip, b"# Run").unwrap();
+        zip.finish().unwrap();
+
+        // A single top-level bundle is not mistaken for a release folder
+        let bundles = archive_source(dir.path(), &archive).list_bundles().unwrap();
+        assert_eq!(bundles.len(), 1);
+        assert_eq!(bundles[0].name, "pdf");
+        assert_eq!(bundles[0].commands[0].name, "run");
+    }
+
+    #[test]
+    fn test_archive_source_rejects_other_urls() {
+        assert!(ArchiveSource::is_archive_url("https://example.com/skills.tar.gz"));
+        assert!(ArchiveSource::is_archive_url("https://example.com/s.zip?token=1"));
+        assert!(ArchiveSource::is_archive_url("file:///tmp/skills.tgz"));
+        assert!(!ArchiveSource::is_archive_url("https://github.com/acme/skills"));
+        assert!(!ArchiveSource::is_archive_url("/tmp/skills.zip"));
+        assert!(ArchiveSource::new("https://example.com/skills.tar.bz2".to_string()).is_err());
+        assert_eq!(
+            ArchiveSource::url_to_path("https://example.com:8080/r/skills.zip?x=1"),
+            "example.com_8080/r/skills.zip"
+        );
+    }
+
+    /// Source serving generated content for paths that don't exist on disk
+    struct SyntheticSource;
+
+    impl Source for SyntheticSource {
+        fn list_bundles(&self) -> Result<Vec<Bundle>> {
+            Ok(Vec::new())
+        }
+
+        fn display_path(&self) -> String {
+            "synthetic".to_string()
+        }
+
+        fn read_file(&self, path: &Path) -> Result<String> {
+            Ok(format!("# {}", path.display()))
+        }
+    }
+
+    #[test]
+    fn test_read_file_goes_through_source() {
+        let dir = tempdir().unwrap();
+        let commands_dir = dir.path().join("bundle/commands");
+        fs::create_dir_all(&commands_dir).unwrap();
+        fs::write(commands_dir.join("run.md"), "# Run").unwrap();
+
+        // Local sources read the listed path from disk
+        let local = LocalSource::new(dir.path().to_path_buf());
+        let run = &local.list_bundles().unwrap()[0].commands[0];
+        assert_eq!(local.read_file(&run.path).unwrap(), "# Run");
+        assert!(local.read_file(&dir.path().join("missing.md")).is_err());
+
+        // Filtering doesn't bypass a source's own reader
+        let filtered = FilteredSource::new(Box::new(SyntheticSource), Vec::new(), Vec::new());
+        assert_eq!(
+            filtered.read_file(Path::new("remote/run.md")).unwrap(),
+            "# remote/run.md"
+        );
+    }
 }
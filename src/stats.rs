@@ -0,0 +1,204 @@
+use anyhow::Result;
+use colored::Colorize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use walkdir::WalkDir;
+
+use crate::config::{Config, SourceConfig};
+use crate::discover::discover_installed;
+use crate::source::{ArchiveSource, GitSource};
+use crate::target::Tool;
+
+/// Overview of configured sources, available bundles and installs (`skm stats`).
+#[derive(Debug, Serialize, Default, PartialEq)]
+pub struct Stats {
+    pub sources: SourceCounts,
+    /// Bundles available across all readable sources
+    pub bundles: usize,
+    /// Sources that could not be read, or git and archive sources not fetched yet
+    pub unavailable_sources: usize,
+    /// Installed items per tool id
+    pub installed: BTreeMap<String, usize>,
+    /// Disk usage of the git source cache, in bytes
+    pub cache_bytes: u64,
+}
+
+#[derive(Debug, Serialize, Default, PartialEq)]
+pub struct SourceCounts {
+    pub local: usize,
+    pub git: usize,
+    pub archive: usize,
+}
+
+impl Stats {
+    /// Gather stats for `target_dir`, measuring the git cache under `cache_root`.
+    ///
+    /// Git sources are only read when already cloned, so this never hits the network.
+    pub fn collect(config: &Config, target_dir: &Path, cache_root: Option<&Path>) -> Result<Self> {
+        let mut stats = Stats::default();
+
+        for source_config in config.source_configs() {
+            let cloned = match source_config {
+                SourceConfig::Local { .. } => {
+                    stats.sources.local += 1;
+                    true
+                }
+                SourceConfig::Git { url, .. } => {
+                    stats.sources.git += 1;
+                    GitSource::new(url.clone())
+                        .map(|git| git.cache_path().exists())
+                        .unwrap_or(false)
+                }
+                SourceConfig::Archive { url, .. } => {
+                    stats.sources.archive += 1;
+                    ArchiveSource::new(url.clone())
+                        .map(|archive| archive.cache_path().exists())
+                        .unwrap_or(false)
+                }
+            };
+            let bundles = if cloned {
+                config
+                    .source_for(source_config)
+                    .and_then(|s| s.list_bundles())
+            } else {
+                Err(anyhow::anyhow!("not cloned"))
+            };
+            match bundles {
+                Ok(bundles) => stats.bundles += bundles.len(),
+                Err(_) => stats.unavailable_sources += 1,
+            }
+        }
+
+        for tool in Tool::ALL {
+            stats.installed.insert(tool.id().to_string(), 0);
+        }
+        for skill in discover_installed(target_dir)? {
+            *stats
+                .installed
+                .entry(skill.tool.as_str().to_string())
+                .or_default() += 1;
+        }
+
+        if let Some(root) = cache_root {
+            stats.cache_bytes = dir_size(root);
+        }
+
+        Ok(stats)
+    }
+
+    /// Print the stats as a short human-readable summary
+    pub fn print(&self) {
+        println!("{}", "Sources".bold());
+        print!(
+            "  {} local, {} git",
+            self.sources.local.to_string().cyan(),
+            self.sources.git.to_string().cyan()
+        );
+        if self.sources.archive > 0 {
+            print!(", {} archive", self.sources.archive.to_string().cyan());
+        }
+        println!();
+        if self.unavailable_sources > 0 {
+            println!(
+                "  {}",
+                format!(
+                    "{} unavailable (unreadable or not cloned yet)",
+                    self.unavailable_sources
+                )
+                .dimmed()
+            );
+        }
+        println!();
+        println!("{} {}", "Bundles available:".bold(), self.bundles.to_string().cyan());
+        println!();
+        println!("{}", "Installed".bold());
+        for tool in Tool::ALL {
+            let count = self.installed.get(tool.id()).copied().unwrap_or(0);
+            println!("  {:<10} {}", tool.name(), count.to_string().cyan());
+        }
+        println!();
+        println!("{} {}", "Git cache:".bold(), format_size(self.cache_bytes).cyan());
+    }
+}
+
+/// Total size of the files under `path` (0 if it does not exist)
+pub fn dir_size(path: &Path) -> u64 {
+    WalkDir::new(path)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| e.metadata().ok())
+        .map(|m| m.len())
+        .sum()
+}
+
+/// Human-readable byte count (B, KB, MB)
+pub fn format_size(bytes: u64) -> String {
+    const KB: u64 = 1024;
+    const MB: u64 = KB * 1024;
+    if bytes >= MB {
+        format!("{:.1} MB", bytes as f64 / MB as f64)
+    } else if bytes >= KB {
+        format!("{:.1} KB", bytes as f64 / KB as f64)
+    } else {
+        format!("{} B", bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_stats_counts_fixture() {
+        let source_dir = tempdir().unwrap();
+        for bundle in ["alpha", "beta"] {
+            let commands = source_dir.path().join(bundle).join("commands");
+            fs::create_dir_all(&commands).unwrap();
+            fs::write(commands.join("run.md"), "# Run").unwrap();
+        }
+
+        let target = tempdir().unwrap();
+        let claude_commands = target.path().join(".claude/commands/alpha");
+        fs::create_dir_all(&claude_commands).unwrap();
+        fs::write(claude_commands.join("run.md"), "# Run").unwrap();
+        fs::write(claude_commands.join("build.md"), "# Build").unwrap();
+        let cursor_commands = target.path().join(".cursor/commands");
+        fs::create_dir_all(&cursor_commands).unwrap();
+        fs::write(cursor_commands.join("beta-run.md"), "# Run").unwrap();
+
+        let cache = tempdir().unwrap();
+        fs::create_dir_all(cache.path().join("example.com/repo")).unwrap();
+        fs::write(cache.path().join("example.com/repo/file"), [0u8; 100]).unwrap();
+
+        let config = Config::new(vec![
+            SourceConfig::local(source_dir.path().to_string_lossy().to_string(), None),
+            SourceConfig::local("/nonexistent/skm-stats".to_string(), None),
+        ]);
+        let stats = Stats::collect(&config, target.path(), Some(cache.path())).unwrap();
+
+        assert_eq!(
+            stats.sources,
+            SourceCounts {
+                local: 2,
+                git: 0,
+                archive: 0
+            }
+        );
+        assert_eq!(stats.bundles, 2);
+        assert_eq!(stats.installed["claude"], 2);
+        assert_eq!(stats.installed["cursor"], 1);
+        assert_eq!(stats.installed["opencode"], 0);
+        assert_eq!(stats.cache_bytes, 100);
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KB");
+        assert_eq!(format_size(3 * 1024 * 1024), "3.0 MB");
+    }
+}
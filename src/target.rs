@@ -1,9 +1,15 @@
 use anyhow::Result;
+use colored::Colorize;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs;
 use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::rc::Rc;
 
 use crate::bundle::{SkillFile, SkillType};
+use crate::transform::{self, AgentFormat};
 
 /// Target AI coding tool
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -12,20 +18,505 @@ pub enum Tool {
     OpenCode,
     Cursor,
     Codex,
+    Windsurf,
 }
 
-/// Detected agent file format based on tools field syntax
-#[derive(Debug, PartialEq)]
-enum AgentFormat {
-    /// Claude format: `tools: Read, Grep, Glob` (PascalCase, comma-separated)
-    Claude,
-    /// OpenCode format: `tools:\n  read: true` (lowercase, YAML object)
-    OpenCode,
-    /// No tools field found
-    Unknown,
+/// Per-install options that change how files are written.
+#[derive(Debug, Clone, Default)]
+pub struct WriteOptions {
+    /// Replaces the `{bundle}-{name}` combined name (or the bare `{name}` for
+    /// Claude agents/commands) with a custom one. Only meaningful for single-item installs.
+    pub name_override: Option<String>,
+    /// Executable that rewrites each written file: it gets the content on stdin
+    /// and prints the replacement on stdout. Only set when `--allow-exec` is given.
+    pub hook: Option<PathBuf>,
+    /// Collects agent tool names that have no OpenCode mapping
+    pub unknown_tools: UnknownTools,
+    /// Collects skills that got the placeholder description
+    pub placeholder_descriptions: PlaceholderDescriptions,
+    /// List each written entry in the tool directory's `.gitignore`
+    pub gitignore: bool,
+    /// Sets `model:` in agent frontmatter (Claude, OpenCode and Cursor only)
+    pub model: Option<String>,
+    /// Skip documentation companions (see `DOC_FILES`)
+    pub no_docs: bool,
+    /// Collects every `(source, destination)` file pair written
+    pub written: WrittenFiles,
+    /// Where the files go: the filesystem, or a buffer for `--dry-run`
+    pub output: Output,
+    /// Replace destination files whose content differs from what would be
+    /// written. Otherwise they are kept and listed in `skipped`.
+    pub overwrite: bool,
+    /// Collects destinations kept because they differ and `overwrite` is off
+    pub skipped: SkippedFiles,
+    /// Collects the type and size of every file actually written
+    pub stats: WriteStats,
+    /// Configured path templates replacing the built-in layout
+    pub destinations: DestTemplates,
+    /// Namespace put before the bundle name in every install path
+    /// (`{prefix}-{bundle}`), from the `install_prefix` config key
+    pub install_prefix: Option<String>,
+}
+
+/// Path templates from the `[destinations]` config table, each replacing the
+/// built-in destination of one tool and item type. Templates are relative to
+/// the install target and use `{bundle}` and `{name}` placeholders, e.g.
+/// `.claude/skills/{bundle}-{name}/SKILL.md`. Clones share the same list.
+#[derive(Debug, Clone, Default)]
+pub struct DestTemplates(Rc<Vec<(Tool, SkillType, String)>>);
+
+impl DestTemplates {
+    /// Templates must already be valid (see `check_dest_template`)
+    pub fn new(templates: Vec<(Tool, SkillType, String)>) -> Self {
+        DestTemplates(Rc::new(templates))
+    }
+
+    /// The template for `tool` and `skill_type`, if one is configured
+    pub fn get(&self, tool: Tool, skill_type: SkillType) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(t, ty, _)| *t == tool && *ty == skill_type)
+            .map(|(_, _, template)| template.as_str())
+    }
+}
+
+/// Placeholders every destination template must use, so items of different
+/// bundles (and of one bundle) don't overwrite each other
+const DEST_PLACEHOLDERS: &[&str] = &["{bundle}", "{name}"];
+
+/// Why `template` can't be used as a destination, if it can't
+pub fn check_dest_template(template: &str) -> std::result::Result<(), String> {
+    let missing: Vec<&str> = DEST_PLACEHOLDERS
+        .iter()
+        .copied()
+        .filter(|p| !template.contains(p))
+        .collect();
+    if !missing.is_empty() {
+        return Err(format!("must contain {}", missing.join(" and ")));
+    }
+    let path = Path::new(template);
+    if path.is_absolute() || template.starts_with('~') {
+        return Err("must be relative to the install target".to_string());
+    }
+    if path.components().any(|c| c == std::path::Component::ParentDir) {
+        return Err("must not contain `..`".to_string());
+    }
+    Ok(())
+}
+
+/// Fill in a destination template's placeholders
+fn render_dest_template(template: &str, bundle_name: &str, name: &str) -> String {
+    template
+        .replace("{bundle}", &fit_file_name(bundle_name))
+        .replace("{name}", name)
+}
+
+/// Destination of installed files. Normally the filesystem; a dry run keeps
+/// the content in memory instead, so transforms still run but nothing on disk
+/// changes. Clones share the same buffer.
+#[derive(Debug, Clone, Default)]
+pub struct Output(Option<Rc<RefCell<BufferedFiles>>>);
+
+type BufferedFiles = BTreeMap<PathBuf, BufferedFile>;
+
+/// A file held in memory by a dry-run `Output`
+#[derive(Debug, Clone)]
+struct BufferedFile {
+    content: Vec<u8>,
+    /// Source of a plain copy, so flushing it can keep the file's permissions
+    copied_from: Option<PathBuf>,
+}
+
+impl Output {
+    /// An output that buffers every write instead of touching the disk
+    pub fn dry_run() -> Self {
+        Output(Some(Rc::default()))
+    }
+
+    pub fn is_dry_run(&self) -> bool {
+        self.0.is_some()
+    }
+
+    /// The content a dry run would have written to `path`
+    pub fn buffered(&self, path: &Path) -> Option<Vec<u8>> {
+        Some(self.0.as_ref()?.borrow().get(path)?.content.clone())
+    }
+
+    fn create_dir_all(&self, path: &Path) -> Result<()> {
+        if !self.is_dry_run() {
+            fs::create_dir_all(path)?;
+        }
+        Ok(())
+    }
+
+    fn write(&self, path: &Path, content: impl AsRef<[u8]>) -> Result<()> {
+        let file = BufferedFile {
+            content: content.as_ref().to_vec(),
+            copied_from: None,
+        };
+        self.put(path, file)
+    }
+
+    fn copy(&self, src: &Path, dest: &Path) -> Result<()> {
+        if !self.is_dry_run() {
+            fs::copy(src, dest)?;
+            return Ok(());
+        }
+        let file = BufferedFile {
+            content: fs::read(src)?,
+            copied_from: Some(src.to_path_buf()),
+        };
+        self.put(dest, file)
+    }
+
+    fn put(&self, path: &Path, file: BufferedFile) -> Result<()> {
+        match (&self.0, &file.copied_from) {
+            (Some(buffer), _) => {
+                buffer.borrow_mut().insert(path.to_path_buf(), file);
+            }
+            (None, Some(src)) => {
+                fs::copy(src, path)?;
+            }
+            (None, None) => fs::write(path, &file.content)?,
+        }
+        Ok(())
+    }
+
+    /// Move the files buffered here into `into`, creating their directories,
+    /// and return the size of each one written. A file whose destination
+    /// already holds different content is left alone and recorded in
+    /// `skipped`, unless `overwrite` allows replacing it.
+    fn flush(
+        &self,
+        into: &Output,
+        overwrite: impl Fn(&Path) -> bool,
+        skipped: &SkippedFiles,
+    ) -> Result<Vec<u64>> {
+        let mut sizes = Vec::new();
+        let Some(buffer) = &self.0 else {
+            return Ok(sizes);
+        };
+        for (path, file) in buffer.borrow().iter() {
+            let changed = into.read(path).is_ok_and(|existing| existing != file.content);
+            if changed && !overwrite(path) {
+                skipped.record(path);
+                continue;
+            }
+            if let Some(parent) = path.parent() {
+                into.create_dir_all(parent)?;
+            }
+            into.put(path, file.clone())?;
+            sizes.push(file.content.len() as u64);
+        }
+        Ok(sizes)
+    }
+
+    /// Current content of `path`, including writes buffered by a dry run
+    fn read(&self, path: &Path) -> Result<Vec<u8>> {
+        match self.buffered(path) {
+            Some(content) => Ok(content),
+            None => Ok(fs::read(path)?),
+        }
+    }
+
+    fn read_to_string(&self, path: &Path) -> Result<String> {
+        Ok(String::from_utf8(self.read(path)?)?)
+    }
+}
+
+/// Source and destination of every file written during an install, used to
+/// build the `--dry-run` plan and the install manifest's file lists.
+/// Clones share the same list.
+#[derive(Debug, Clone, Default)]
+pub struct WrittenFiles(Rc<RefCell<Vec<(PathBuf, PathBuf)>>>);
+
+impl WrittenFiles {
+    fn record(&self, src: &Path, dest: &Path) {
+        self.0.borrow_mut().push((src.to_path_buf(), dest.to_path_buf()));
+    }
+
+    /// The recorded `(source, destination)` pairs, in write order
+    pub fn pairs(&self) -> Vec<(PathBuf, PathBuf)> {
+        self.0.borrow().clone()
+    }
+
+    /// Position to pass to `destinations_since` to get the files written after now
+    pub fn mark(&self) -> usize {
+        self.0.borrow().len()
+    }
+
+    /// Whether `dest` was written before `mark`
+    fn wrote_before(&self, mark: usize, dest: &Path) -> bool {
+        self.0.borrow()[..mark].iter().any(|(_, d)| d == dest)
+    }
+
+    /// Destinations written since `mark`, without repeats
+    pub fn destinations_since(&self, mark: usize) -> Vec<PathBuf> {
+        let mut dests: Vec<PathBuf> = Vec::new();
+        for (_, dest) in &self.0.borrow()[mark..] {
+            if !dests.contains(dest) {
+                dests.push(dest.clone());
+            }
+        }
+        dests
+    }
+}
+
+/// Destinations left untouched because they were changed locally, reported
+/// at the end of an install. Clones share the same list.
+#[derive(Debug, Clone, Default)]
+pub struct SkippedFiles(Rc<RefCell<Vec<PathBuf>>>);
+
+impl SkippedFiles {
+    fn record(&self, path: &Path) {
+        self.0.borrow_mut().push(path.to_path_buf());
+    }
+
+    /// The kept destinations, in the order they were found
+    pub fn paths(&self) -> Vec<PathBuf> {
+        self.0.borrow().clone()
+    }
+}
+
+/// Type and size of each file written during an install, for `--stats-after`.
+/// Clones share the same list.
+#[derive(Debug, Clone, Default)]
+pub struct WriteStats(Rc<RefCell<Vec<(SkillType, u64)>>>);
+
+impl WriteStats {
+    fn record(&self, skill_type: SkillType, bytes: u64) {
+        self.0.borrow_mut().push((skill_type, bytes));
+    }
+
+    /// Number of files written
+    pub fn files(&self) -> usize {
+        self.0.borrow().len()
+    }
+
+    /// Total bytes written
+    pub fn bytes(&self) -> u64 {
+        self.0.borrow().iter().map(|(_, bytes)| bytes).sum()
+    }
+
+    /// Number of files written for items of `skill_type`, companions included
+    pub fn files_of_type(&self, skill_type: SkillType) -> usize {
+        self.0.borrow().iter().filter(|(t, _)| *t == skill_type).count()
+    }
+}
+
+/// Agent tool names with no OpenCode mapping, collected across an install so
+/// they can be reported once at the end instead of once per agent.
+/// Clones share the same set.
+#[derive(Debug, Clone, Default)]
+pub struct UnknownTools(Rc<RefCell<BTreeSet<String>>>);
+
+impl UnknownTools {
+    fn record(&self, tool: &str) {
+        self.0.borrow_mut().insert(tool.to_string());
+    }
+
+    /// The collected names, sorted and deduplicated
+    pub fn names(&self) -> Vec<String> {
+        self.0.borrow().iter().cloned().collect()
+    }
+
+    /// Print a single warning listing every collected tool, if any
+    pub fn report(&self) {
+        let names = self.names();
+        if !names.is_empty() {
+            eprintln!(
+                "{} Unknown agent tool(s) passed through as-is: {}",
+                "Warning:".yellow(),
+                names.join(", ")
+            );
+        }
+    }
+}
+
+/// Installed skills whose description fell back to `PLACEHOLDER_DESCRIPTION`,
+/// reported once at the end of an install. Clones share the same set.
+#[derive(Debug, Clone, Default)]
+pub struct PlaceholderDescriptions(Rc<RefCell<BTreeSet<String>>>);
+
+impl PlaceholderDescriptions {
+    fn record(&self, skill_name: &str) {
+        self.0.borrow_mut().insert(skill_name.to_string());
+    }
+
+    /// The collected skill names, sorted and deduplicated
+    pub fn names(&self) -> Vec<String> {
+        self.0.borrow().iter().cloned().collect()
+    }
+
+    /// Print a single warning naming every collected skill, if any
+    pub fn report(&self) {
+        let names = self.names();
+        if !names.is_empty() {
+            eprintln!(
+                "{} Skill(s) with nothing to describe them, given the placeholder \"{}\": {}",
+                "Warning:".yellow(),
+                transform::PLACEHOLDER_DESCRIPTION,
+                names.join(", ")
+            );
+        }
+    }
+}
+
+/// The bundle part of install paths: `bundle_name` behind the configured
+/// `install_prefix`, if any
+pub fn prefixed_bundle_name(install_prefix: Option<&str>, bundle_name: &str) -> String {
+    match install_prefix {
+        Some(prefix) => format!("{}-{}", prefix, bundle_name),
+        None => bundle_name.to_string(),
+    }
+}
+
+impl WriteOptions {
+    /// The bundle name as it appears in install paths
+    pub fn install_bundle_name(&self, bundle_name: &str) -> String {
+        prefixed_bundle_name(self.install_prefix.as_deref(), bundle_name)
+    }
+
+    /// The `{bundle}-{name}` name used for folders and flat files, unless overridden
+    fn combined_name(&self, bundle_name: &str, skill_name: &str) -> String {
+        match &self.name_override {
+            Some(name) => fit_file_name(name),
+            None => {
+                let bundle_name = self.install_bundle_name(bundle_name);
+                fit_file_name(&format!("{}-{}", bundle_name, skill_name))
+            }
+        }
+    }
+
+    /// The file stem used inside a per-bundle directory (Claude agents/commands)
+    fn item_name(&self, skill_name: &str) -> String {
+        fit_file_name(self.name_override.as_deref().unwrap_or(skill_name))
+    }
+}
+
+/// Pipe a written file through the user's hook and replace it with the output
+fn run_hook(out: &Output, hook: &Path, dest: &Path) -> Result<()> {
+    use anyhow::Context;
+    use std::process::{Command, Stdio};
+
+    let content = out.read(dest)?;
+    let mut child = Command::new(hook)
+        .env("SKM_FILE", dest)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .with_context(|| format!("Could not run hook {}", hook.display()))?;
+
+    // Feed stdin from a thread so a hook that streams output can't deadlock us
+    let mut stdin = child.stdin.take().expect("stdin is piped");
+    let writer = std::thread::spawn(move || stdin.write_all(&content));
+    let output = child.wait_with_output()?;
+    let sent = writer
+        .join()
+        .map_err(|_| anyhow::anyhow!("hook input writer panicked"))?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "Hook {} failed on {} ({})",
+            hook.display(),
+            dest.display(),
+            output.status
+        );
+    }
+    // A successful hook is allowed to ignore its input
+    if let Err(e) = sent {
+        if e.kind() != std::io::ErrorKind::BrokenPipe {
+            return Err(e)
+                .with_context(|| format!("Could not send {} to hook", dest.display()));
+        }
+    }
+
+    out.write(dest, output.stdout)
+}
+
+/// Most filesystems cap a single path component at 255 bytes.
+const MAX_FILE_NAME_BYTES: usize = 255;
+
+/// Room kept free for the `.md` extension of flat files.
+const FILE_EXTENSION_RESERVE: usize = 3;
+
+/// Shorten an over-long name so it (plus `.md`) fits in one path component.
+/// The name is cut on a char boundary and tagged with a hash of the full name,
+/// so distinct long names stay distinct and reinstalls land in the same place.
+fn fit_file_name(name: &str) -> String {
+    let limit = MAX_FILE_NAME_BYTES - FILE_EXTENSION_RESERVE;
+    if name.len() <= limit {
+        return name.to_string();
+    }
+
+    let suffix = format!("-{:08x}", fnv1a_hash(name.as_bytes()) as u32);
+    let mut cut = limit - suffix.len();
+    while !name.is_char_boundary(cut) {
+        cut -= 1;
+    }
+    let shortened = format!("{}{}", &name[..cut], suffix);
+    eprintln!(
+        "Warning: name is too long for the filesystem ({} bytes), installing as '{}'",
+        name.len(),
+        shortened
+    );
+    shortened
+}
+
+/// Turn a display name (e.g. an agent's frontmatter `name: Code Reviewer`) into a
+/// file name: names that are already plain are kept, anything else is lowercased
+/// with each run of other characters (spaces, slashes, ...) replaced by `-`.
+fn slugify(name: &str) -> String {
+    let plain = |c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.';
+    if !name.is_empty() && name.chars().all(plain) && !name.starts_with('.') {
+        return name.to_string();
+    }
+
+    let mut slug = String::new();
+    for c in name.chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() || c == '_' {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() {
+        format!("{:08x}", fnv1a_hash(name.as_bytes()) as u32)
+    } else {
+        slug.to_string()
+    }
+}
+
+/// 64-bit FNV-1a; stable across Rust versions unlike `DefaultHasher`
+pub fn fnv1a_hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+    })
 }
 
 impl Tool {
+    /// Every supported tool, in display order
+    pub const ALL: [Tool; 5] =
+        [Tool::Claude, Tool::OpenCode, Tool::Cursor, Tool::Codex, Tool::Windsurf];
+
+    /// Lowercase identifier used in config and lock files (e.g. "claude")
+    pub fn id(&self) -> &'static str {
+        match self {
+            Tool::Claude => "claude",
+            Tool::OpenCode => "opencode",
+            Tool::Cursor => "cursor",
+            Tool::Codex => "codex",
+            Tool::Windsurf => "windsurf",
+        }
+    }
+
+    /// Parse a lowercase tool identifier
+    pub fn from_id(id: &str) -> Option<Tool> {
+        Tool::ALL.into_iter().find(|t| t.id() == id)
+    }
+
     /// Get the global install target for this tool
     pub fn global_target(&self) -> PathBuf {
         let home = std::env::var_os("HOME")
@@ -40,9 +531,20 @@ impl Tool {
                 std::env::current_dir().unwrap_or(home.clone())
             }
             Tool::Codex => home.join(".codex"),
+            Tool::Windsurf => {
+                eprintln!(
+                    "Warning: Windsurf doesn't support global rule folders, using current directory"
+                );
+                std::env::current_dir().unwrap_or(home.clone())
+            }
         }
     }
 
+    /// Whether the tool's directory already exists in `target_dir`
+    pub fn is_present(&self, target_dir: &Path) -> bool {
+        target_dir.join(self.tool_dir_name()).is_dir()
+    }
+
     /// Get the tool directory name (e.g. ".claude", ".opencode")
     pub fn tool_dir_name(&self) -> &'static str {
         match self {
@@ -50,6 +552,7 @@ impl Tool {
             Tool::OpenCode => ".opencode",
             Tool::Cursor => ".cursor",
             Tool::Codex => ".codex",
+            Tool::Windsurf => ".windsurf",
         }
     }
 
@@ -60,26 +563,117 @@ impl Tool {
             Tool::OpenCode => "OpenCode",
             Tool::Cursor => "Cursor",
             Tool::Codex => "Codex",
+            Tool::Windsurf => "Windsurf",
         }
     }
 
     /// Write a skill file to the appropriate location for this tool
+    #[cfg(test)]
     pub fn write_file(
         &self,
-        target_dir: &PathBuf,
+        target_dir: &Path,
         bundle_name: &str,
         skill: &SkillFile,
     ) -> Result<PathBuf> {
-        match self {
-            Tool::Claude => self.write_claude(target_dir, bundle_name, skill),
-            Tool::OpenCode => self.write_opencode(target_dir, bundle_name, skill),
-            Tool::Cursor => self.write_cursor(target_dir, bundle_name, skill),
-            Tool::Codex => self.write_codex(target_dir, bundle_name, skill),
+        self.write_file_with(target_dir, bundle_name, skill, &WriteOptions::default())
+    }
+
+    /// Write a skill file with explicit write options
+    pub fn write_file_with(
+        &self,
+        target_dir: &Path,
+        bundle_name: &str,
+        skill: &SkillFile,
+        opts: &WriteOptions,
+    ) -> Result<PathBuf> {
+        // Produce every file in memory first, so what's compared with the
+        // destination is the final content (after `model` and the hook)
+        let mark = opts.written.mark();
+        let staged = WriteOptions {
+            output: Output::dry_run(),
+            ..opts.clone()
+        };
+        let dest = match self {
+            Tool::Claude => self.write_claude(target_dir, bundle_name, skill, &staged),
+            Tool::OpenCode => self.write_opencode(target_dir, bundle_name, skill, &staged),
+            Tool::Cursor => self.write_cursor(target_dir, bundle_name, skill, &staged),
+            Tool::Codex => self.write_codex(target_dir, bundle_name, skill, &staged),
+            Tool::Windsurf => self.write_windsurf(target_dir, bundle_name, skill, &staged),
+        }?;
+
+        if let Some(model) = &opts.model {
+            if skill.skill_type == SkillType::Agent && self.supports_agent_model() {
+                let content = staged.output.read_to_string(&dest)?;
+                staged.output.write(&dest, transform::set_field(&content, "model", model))?;
+            }
+        }
+        if let Some(hook) = &opts.hook {
+            run_hook(&staged.output, hook, &dest)?;
+        }
+        // Files this install already wrote aren't local changes
+        let overwrite = |path: &Path| opts.overwrite || opts.written.wrote_before(mark, path);
+        for bytes in staged.output.flush(&opts.output, overwrite, &opts.skipped)? {
+            opts.stats.record(skill.skill_type, bytes);
         }
+        if opts.gitignore {
+            self.ignore_in_git(&opts.output, target_dir, &dest)?;
+        }
+        opts.written.record(&skill.path, &dest);
+
+        Ok(dest)
+    }
+
+    /// Whether the tool reads a `model:` field from agent frontmatter
+    fn supports_agent_model(&self) -> bool {
+        matches!(self, Tool::Claude | Tool::OpenCode | Tool::Cursor)
+    }
+
+    /// Add the installed entry holding `dest` (its folder, or the file itself
+    /// for flat installs) and the install manifest to `{tool dir}/.gitignore`.
+    /// Existing lines are kept and entries are never duplicated.
+    fn ignore_in_git(&self, out: &Output, target_dir: &Path, dest: &Path) -> Result<()> {
+        let tool_dir = target_dir.join(self.tool_dir_name());
+        let Ok(relative) = dest.strip_prefix(&tool_dir) else {
+            return Ok(());
+        };
+        let parts: Vec<String> = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().into_owned())
+            .collect();
+        let entry = match parts.as_slice() {
+            [kind, item, _, ..] => format!("/{}/{}/", kind, item),
+            _ => format!("/{}", parts.join("/")),
+        };
+
+        let path = tool_dir.join(".gitignore");
+        let mut content = out.read_to_string(&path).unwrap_or_default();
+        let missing: Vec<&str> = ["/.skm.toml", entry.as_str()]
+            .into_iter()
+            .filter(|e| !content.lines().any(|line| line.trim() == *e))
+            .collect();
+        if missing.is_empty() {
+            return Ok(());
+        }
+        if !content.is_empty() && !content.ends_with('\n') {
+            content.push('\n');
+        }
+        for e in missing {
+            content.push_str(e);
+            content.push('\n');
+        }
+        out.write(&path, content)
     }
 
     /// Get the destination info string for display
-    pub fn dest_info(&self, skill_type: SkillType, bundle_name: &str) -> String {
+    pub fn dest_info(
+        &self,
+        skill_type: SkillType,
+        bundle_name: &str,
+        destinations: &DestTemplates,
+    ) -> String {
+        if let Some(template) = destinations.get(*self, skill_type) {
+            return render_dest_template(template, bundle_name, "*");
+        }
         match self {
             Tool::Claude => match skill_type {
                 SkillType::Skill => format!(".claude/skills/{}-*/SKILL.md", bundle_name),
@@ -105,9 +699,91 @@ impl Tool {
                 SkillType::Command => format!(".codex/commands/{}-*.md", bundle_name),
                 SkillType::Rule => format!(".codex/rules/{}-*/RULE.md", bundle_name),
             },
+            Tool::Windsurf => match skill_type {
+                SkillType::Command => format!(".windsurf/workflows/{}-*.md", bundle_name),
+                _ => format!(".windsurf/rules/{}-*.md", bundle_name),
+            },
+        }
+    }
+
+    /// Where `skill` goes in `target_dir`: the configured destination
+    /// template for this tool and type, or the built-in layout
+    fn dest_path(
+        &self,
+        target_dir: &Path,
+        bundle_name: &str,
+        skill: &SkillFile,
+        opts: &WriteOptions,
+    ) -> PathBuf {
+        let install_bundle = opts.install_bundle_name(bundle_name);
+        if let Some(template) = opts.destinations.get(*self, skill.skill_type) {
+            let name = opts.item_name(&skill.name);
+            return target_dir.join(render_dest_template(template, &install_bundle, &name));
+        }
+
+        let base = target_dir
+            .join(self.tool_dir_name())
+            .join(skill.skill_type.dir_name());
+        let combined_name = opts.combined_name(bundle_name, &skill.name);
+        match (self, skill.skill_type) {
+            // Windsurf only has flat rules and workflows
+            (Tool::Windsurf, SkillType::Command) => target_dir
+                .join(".windsurf/workflows")
+                .join(format!("{}.md", combined_name)),
+            (Tool::Windsurf, _) => target_dir
+                .join(".windsurf/rules")
+                .join(format!("{}.md", combined_name)),
+            (_, SkillType::Skill) => base.join(combined_name).join("SKILL.md"),
+            (_, SkillType::Rule) => base.join(combined_name).join("RULE.md"),
+            // The agent name may come from frontmatter; the file gets a slug of it
+            (Tool::Claude, SkillType::Agent) => base
+                .join(fit_file_name(&install_bundle))
+                .join(format!("{}.md", opts.item_name(&slugify(&skill.name)))),
+            (Tool::Claude, SkillType::Command) => base
+                .join(fit_file_name(&install_bundle))
+                .join(format!("{}.md", opts.item_name(&skill.name))),
+            _ => base.join(format!("{}.md", combined_name)),
         }
     }
 
+    /// Pairs of `files` this tool would install to the same destination, with
+    /// that destination. Happens when a layout flattens types into one folder,
+    /// e.g. a skill and a rule of the same name in Windsurf's `rules/`.
+    pub fn dest_collisions<'a>(
+        &self,
+        target_dir: &Path,
+        bundle_name: &str,
+        files: &[&'a SkillFile],
+        opts: &WriteOptions,
+    ) -> Vec<(PathBuf, &'a SkillFile, &'a SkillFile)> {
+        let mut seen: BTreeMap<PathBuf, &SkillFile> = BTreeMap::new();
+        let mut collisions = Vec::new();
+        for file in files {
+            let dest = self.dest_path(target_dir, bundle_name, file, opts);
+            match seen.get(&dest) {
+                Some(first) => collisions.push((dest, *first, *file)),
+                None => {
+                    seen.insert(dest, file);
+                }
+            }
+        }
+        collisions
+    }
+
+    /// Resolve the destination of `skill` and create its directory
+    fn prepare_dest(
+        &self,
+        target_dir: &Path,
+        bundle_name: &str,
+        skill: &SkillFile,
+        opts: &WriteOptions,
+    ) -> Result<(PathBuf, PathBuf)> {
+        let dest_file = self.dest_path(target_dir, bundle_name, skill, opts);
+        let dest_dir = dest_file.parent().unwrap_or(target_dir).to_path_buf();
+        opts.output.create_dir_all(&dest_dir)?;
+        Ok((dest_file, dest_dir))
+    }
+
     // Claude:
     //   skills -> .claude/skills/{bundle}-{name}/SKILL.md (folder-based with frontmatter)
     //   agents -> .claude/agents/{bundle}/{name}.md (flat file within bundle dir)
@@ -116,134 +792,85 @@ impl Tool {
     // Phase 1+4: detect agent format and reverse-transform if needed
     fn write_claude(
         &self,
-        target_dir: &PathBuf,
+        target_dir: &Path,
         bundle_name: &str,
         skill: &SkillFile,
+        opts: &WriteOptions,
     ) -> Result<PathBuf> {
-        match skill.skill_type {
-            SkillType::Skill => {
-                // Skills use folder-based format: .claude/skills/{bundle}-{name}/SKILL.md
-                let combined_name = format!("{}-{}", bundle_name, skill.name);
-                let dest_dir = target_dir.join(".claude/skills").join(&combined_name);
-                fs::create_dir_all(&dest_dir)?;
-
-                let dest_file = dest_dir.join("SKILL.md");
-                transform_skill_file(&skill.path, &dest_file, &combined_name)?;
-
-                copy_companion_files(skill, &dest_dir)?;
+        let combined_name = opts.combined_name(bundle_name, &skill.name);
+        let (dest_file, dest_dir) = self.prepare_dest(target_dir, bundle_name, skill, opts)?;
 
-                Ok(dest_file)
-            }
-            SkillType::Rule => {
-                // Rules use folder-based format: .claude/rules/{bundle}-{name}/RULE.md
-                let combined_name = format!("{}-{}", bundle_name, skill.name);
-                let dest_dir = target_dir.join(".claude/rules").join(&combined_name);
-                fs::create_dir_all(&dest_dir)?;
-
-                let dest_file = dest_dir.join("RULE.md");
-                // Use skill transform to ensure frontmatter exists
-                transform_skill_file(&skill.path, &dest_file, &combined_name)?;
-
-                copy_companion_files(skill, &dest_dir)?;
-
-                Ok(dest_file)
+        match skill.skill_type {
+            // Skills and rules are folder-based; rules reuse the skill
+            // transform to ensure frontmatter exists
+            SkillType::Skill | SkillType::Rule => {
+                let content = transform_skill_file(
+                    &skill.path,
+                    &combined_name,
+                    &opts.placeholder_descriptions,
+                )?;
+                opts.output.write(&dest_file, content)?;
             }
-            SkillType::Agent => {
-                // Agents are flat files within bundle dir: .claude/agents/{bundle}/{name}.md
-                let dest_dir = target_dir
-                    .join(".claude/agents")
-                    .join(bundle_name);
-                fs::create_dir_all(&dest_dir)?;
-
-                let dest_file = dest_dir.join(format!("{}.md", skill.name));
-
-                match detect_agent_format(&skill.path)? {
-                    AgentFormat::OpenCode => transform_agent_for_claude(&skill.path, &dest_file)?,
-                    _ => { fs::copy(&skill.path, &dest_file)?; }
+            SkillType::Agent => match detect_agent_format(&skill.path)? {
+                AgentFormat::OpenCode => {
+                    let content = transform_file(&skill.path, transform::agent_opencode_to_claude)?;
+                    opts.output.write(&dest_file, content)?
                 }
+                AgentFormat::Claude => {
+                    let content = transform_file(&skill.path, transform::agent_claude_tools_key)?;
+                    opts.output.write(&dest_file, content)?
+                }
+                AgentFormat::Unknown => opts.output.copy(&skill.path, &dest_file)?,
+            },
+            SkillType::Command => opts.output.copy(&skill.path, &dest_file)?,
+        }
 
-                copy_companion_files(skill, &dest_dir)?;
-
-                Ok(dest_file)
-            }
-            SkillType::Command => {
-                // Commands are flat files within bundle dir: .claude/commands/{bundle}/{name}.md
-                let dest_dir = target_dir
-                    .join(".claude/commands")
-                    .join(bundle_name);
-                fs::create_dir_all(&dest_dir)?;
-
-                let dest_file = dest_dir.join(format!("{}.md", skill.name));
-                fs::copy(&skill.path, &dest_file)?;
-
-                copy_companion_files(skill, &dest_dir)?;
+        copy_companion_files(skill, &dest_dir, opts)?;
 
-                Ok(dest_file)
-            }
-        }
+        Ok(dest_file)
     }
 
     // OpenCode:
     //   skills -> .opencode/skills/{bundle}-{name}/SKILL.md (with frontmatter)
     //   agents -> .opencode/agents/{bundle}-{name}.md
-    //   commands -> .opencode/commands/{bundle}-{name}.md
+    //   commands -> .opencode/commands/{bundle}-{name}.md (with description frontmatter)
     // Phase 4: detect agent format before transforming
     fn write_opencode(
         &self,
-        target_dir: &PathBuf,
+        target_dir: &Path,
         bundle_name: &str,
         skill: &SkillFile,
+        opts: &WriteOptions,
     ) -> Result<PathBuf> {
-        let combined_name = format!("{}-{}", bundle_name, skill.name);
+        let combined_name = opts.combined_name(bundle_name, &skill.name);
+        let (dest_file, dest_dir) = self.prepare_dest(target_dir, bundle_name, skill, opts)?;
 
         match skill.skill_type {
-            SkillType::Skill => {
-                let dest_dir = target_dir.join(".opencode/skills").join(&combined_name);
-                fs::create_dir_all(&dest_dir)?;
-
-                let dest_file = dest_dir.join("SKILL.md");
-                transform_skill_file(&skill.path, &dest_file, &combined_name)?;
-
-                copy_companion_files(skill, &dest_dir)?;
-
-                Ok(dest_file)
+            SkillType::Skill | SkillType::Rule => {
+                let content = transform_skill_file(
+                    &skill.path,
+                    &combined_name,
+                    &opts.placeholder_descriptions,
+                )?;
+                opts.output.write(&dest_file, content)?;
+
+                copy_companion_files(skill, &dest_dir, opts)?;
             }
-            SkillType::Rule => {
-                let dest_dir = target_dir.join(".opencode/rules").join(&combined_name);
-                fs::create_dir_all(&dest_dir)?;
-
-                let dest_file = dest_dir.join("RULE.md");
-                transform_skill_file(&skill.path, &dest_file, &combined_name)?;
-
-                copy_companion_files(skill, &dest_dir)?;
-
-                Ok(dest_file)
-            }
-            SkillType::Agent => {
-                // Flat file target — companion files not applicable
-                let dest_dir = target_dir.join(".opencode/agents");
-                fs::create_dir_all(&dest_dir)?;
-
-                let dest_file = dest_dir.join(format!("{}.md", combined_name));
-
-                match detect_agent_format(&skill.path)? {
-                    AgentFormat::Claude => transform_agent_file(&skill.path, &dest_file)?,
-                    _ => { fs::copy(&skill.path, &dest_file)?; }
+            // Flat file targets — companion files not applicable
+            SkillType::Agent => match detect_agent_format(&skill.path)? {
+                AgentFormat::Claude => {
+                    let content = transform_agent_file(&skill.path, &opts.unknown_tools)?;
+                    opts.output.write(&dest_file, content)?
                 }
-
-                Ok(dest_file)
-            }
+                _ => opts.output.copy(&skill.path, &dest_file)?,
+            },
             SkillType::Command => {
-                // Flat file target — companion files not applicable
-                let dest_dir = target_dir.join(".opencode/commands");
-                fs::create_dir_all(&dest_dir)?;
-
-                let dest_file = dest_dir.join(format!("{}.md", combined_name));
-                fs::copy(&skill.path, &dest_file)?;
-
-                Ok(dest_file)
+                let content = transform_file(&skill.path, transform::opencode_command)?;
+                opts.output.write(&dest_file, content)?
             }
         }
+
+        Ok(dest_file)
     }
 
     // Cursor:
@@ -253,58 +880,12 @@ impl Tool {
     //   rules -> .cursor/rules/{bundle}-{name}/RULE.md (folder-based)
     fn write_cursor(
         &self,
-        target_dir: &PathBuf,
+        target_dir: &Path,
         bundle_name: &str,
         skill: &SkillFile,
+        opts: &WriteOptions,
     ) -> Result<PathBuf> {
-        let combined_name = format!("{}-{}", bundle_name, skill.name);
-
-        match skill.skill_type {
-            SkillType::Skill => {
-                // Skills use .cursor/skills/ directory with SKILL.md
-                let dest_dir = target_dir.join(".cursor/skills").join(&combined_name);
-                fs::create_dir_all(&dest_dir)?;
-
-                let dest_file = dest_dir.join("SKILL.md");
-                transform_skill_file(&skill.path, &dest_file, &combined_name)?;
-
-                copy_companion_files(skill, &dest_dir)?;
-
-                Ok(dest_file)
-            }
-            SkillType::Agent => {
-                // Agents (subagents) use .cursor/agents/ as flat files
-                let dest_dir = target_dir.join(".cursor/agents");
-                fs::create_dir_all(&dest_dir)?;
-
-                let dest_file = dest_dir.join(format!("{}.md", combined_name));
-                transform_cursor_agent(&skill.path, &dest_file, &combined_name)?;
-
-                Ok(dest_file)
-            }
-            SkillType::Command => {
-                // Commands use .cursor/commands/ as flat files
-                let dest_dir = target_dir.join(".cursor/commands");
-                fs::create_dir_all(&dest_dir)?;
-
-                let dest_file = dest_dir.join(format!("{}.md", combined_name));
-                fs::copy(&skill.path, &dest_file)?;
-
-                Ok(dest_file)
-            }
-            SkillType::Rule => {
-                // Rules use .cursor/rules/ with RULE.md (folder-based)
-                let dest_dir = target_dir.join(".cursor/rules").join(&combined_name);
-                fs::create_dir_all(&dest_dir)?;
-
-                let dest_file = dest_dir.join("RULE.md");
-                transform_cursor_rule(&skill.path, &dest_file, &combined_name)?;
-
-                copy_companion_files(skill, &dest_dir)?;
-
-                Ok(dest_file)
-            }
-        }
+        self.write_cursor_like(target_dir, bundle_name, skill, opts)
     }
 
     // Codex:
@@ -312,607 +893,200 @@ impl Tool {
     //   agents -> .codex/agents/{bundle}-{name}.md (flat file)
     //   commands -> .codex/commands/{bundle}-{name}.md (flat file)
     //   rules -> .codex/rules/{bundle}-{name}/RULE.md (folder-based)
+    // Codex uses the same file formats as Cursor
     fn write_codex(
         &self,
-        target_dir: &PathBuf,
+        target_dir: &Path,
         bundle_name: &str,
         skill: &SkillFile,
+        opts: &WriteOptions,
     ) -> Result<PathBuf> {
-        let combined_name = format!("{}-{}", bundle_name, skill.name);
-
-        match skill.skill_type {
-            SkillType::Skill => {
-                // Skills use .codex/skills/ directory with SKILL.md
-                let dest_dir = target_dir.join(".codex/skills").join(&combined_name);
-                fs::create_dir_all(&dest_dir)?;
-
-                let dest_file = dest_dir.join("SKILL.md");
-                transform_skill_file(&skill.path, &dest_file, &combined_name)?;
-
-                copy_companion_files(skill, &dest_dir)?;
-
-                Ok(dest_file)
-            }
-            SkillType::Agent => {
-                // Agents use .codex/agents/ as flat files
-                let dest_dir = target_dir.join(".codex/agents");
-                fs::create_dir_all(&dest_dir)?;
-
-                let dest_file = dest_dir.join(format!("{}.md", combined_name));
-                // Codex uses similar format to Cursor for agents
-                transform_cursor_agent(&skill.path, &dest_file, &combined_name)?;
-
-                Ok(dest_file)
-            }
-            SkillType::Command => {
-                // Commands use .codex/commands/ as flat files
-                let dest_dir = target_dir.join(".codex/commands");
-                fs::create_dir_all(&dest_dir)?;
-
-                let dest_file = dest_dir.join(format!("{}.md", combined_name));
-                fs::copy(&skill.path, &dest_file)?;
-
-                Ok(dest_file)
-            }
-            SkillType::Rule => {
-                // Rules use .codex/rules/ with RULE.md (folder-based)
-                let dest_dir = target_dir.join(".codex/rules").join(&combined_name);
-                fs::create_dir_all(&dest_dir)?;
-
-                let dest_file = dest_dir.join("RULE.md");
-                transform_cursor_rule(&skill.path, &dest_file, &combined_name)?;
-
-                copy_companion_files(skill, &dest_dir)?;
-
-                Ok(dest_file)
-            }
-        }
+        self.write_cursor_like(target_dir, bundle_name, skill, opts)
     }
-}
 
-// ---------------------------------------------------------------------------
-// Phase 4: Agent format detection
-// ---------------------------------------------------------------------------
-
-/// Detect whether an agent file uses Claude format (PascalCase comma string)
-/// or OpenCode format (lowercase YAML object)
-fn detect_agent_format(src: &PathBuf) -> Result<AgentFormat> {
-    let content = fs::read_to_string(src)?;
-    let lines: Vec<&str> = content.lines().collect();
-
-    let mut in_fm = false;
-    for line in &lines {
-        if *line == "---" {
-            if in_fm { break; }
-            in_fm = true;
-            continue;
-        }
-        if in_fm && line.trim().starts_with("tools:") {
-            if line.contains(",") {
-                return Ok(AgentFormat::Claude); // "tools: Read, Grep, ..."
-            } else {
-                return Ok(AgentFormat::OpenCode); // "tools:" (YAML object follows)
-            }
-        }
-    }
-    Ok(AgentFormat::Unknown) // No tools field
-}
-
-// ---------------------------------------------------------------------------
-// Phase 2: Skill file transformation with description injection
-// ---------------------------------------------------------------------------
+    // Windsurf:
+    //   skills, agents, rules -> .windsurf/rules/{bundle}-{name}.md (flat, with trigger)
+    //   commands -> .windsurf/workflows/{bundle}-{name}.md (flat file)
+    fn write_windsurf(
+        &self,
+        target_dir: &Path,
+        bundle_name: &str,
+        skill: &SkillFile,
+        opts: &WriteOptions,
+    ) -> Result<PathBuf> {
+        let (dest_file, _) = self.prepare_dest(target_dir, bundle_name, skill, opts)?;
 
-/// Transform a skill file to ensure it has proper frontmatter with name and description fields.
-/// - Adds `name:` if missing
-/// - Adds `description:` if missing (extracted from body content)
-fn transform_skill_file(src: &PathBuf, dest: &PathBuf, skill_name: &str) -> Result<()> {
-    let content = fs::read_to_string(src)?;
-    let lines: Vec<&str> = content.lines().collect();
-
-    let output = if lines.first() == Some(&"---") {
-        // Has frontmatter - check what fields exist
-        let mut in_frontmatter = false;
-        let mut has_name = false;
-        let mut has_description = false;
-        let mut frontmatter_end = 0;
-
-        for (i, line) in lines.iter().enumerate() {
-            if *line == "---" {
-                if in_frontmatter {
-                    frontmatter_end = i;
-                    break;
-                }
-                in_frontmatter = true;
-                continue;
-            }
-            if in_frontmatter {
-                if line.starts_with("name:") { has_name = true; }
-                if line.starts_with("description:") { has_description = true; }
+        // Flat file targets — companion files not applicable
+        match skill.skill_type {
+            SkillType::Command => opts.output.copy(&skill.path, &dest_file)?,
+            _ => {
+                let content = transform_file(&skill.path, transform::windsurf_rule)?;
+                opts.output.write(&dest_file, content)?
             }
         }
 
-        if has_name && has_description {
-            // Already has both required fields, use as-is
-            content
-        } else {
-            let mut result = String::new();
-            result.push_str("---\n");
+        Ok(dest_file)
+    }
 
-            if !has_name {
-                result.push_str(&format!("name: {}\n", skill_name));
-            }
+    /// Shared writer for Cursor and Codex, which differ only in location
+    fn write_cursor_like(
+        &self,
+        target_dir: &Path,
+        bundle_name: &str,
+        skill: &SkillFile,
+        opts: &WriteOptions,
+    ) -> Result<PathBuf> {
+        let combined_name = opts.combined_name(bundle_name, &skill.name);
+        let (dest_file, dest_dir) = self.prepare_dest(target_dir, bundle_name, skill, opts)?;
 
-            // Copy existing frontmatter lines (between first --- and closing ---)
-            for line in lines.iter().skip(1).take(frontmatter_end - 1) {
-                result.push_str(line);
-                result.push('\n');
+        match skill.skill_type {
+            SkillType::Skill => {
+                let content = transform_skill_file(
+                    &skill.path,
+                    &combined_name,
+                    &opts.placeholder_descriptions,
+                )?;
+                opts.output.write(&dest_file, content)?;
+
+                copy_companion_files(skill, &dest_dir, opts)?;
             }
+            SkillType::Rule => {
+                let content = transform_file(&skill.path, transform::cursor_rule)?;
+                opts.output.write(&dest_file, content)?;
 
-            if !has_description {
-                let desc = extract_description_from_body(&lines, frontmatter_end + 1);
-                result.push_str(&format!("description: \"{}\"\n", desc));
+                copy_companion_files(skill, &dest_dir, opts)?;
             }
-
-            // Add closing --- and body
-            for line in lines.iter().skip(frontmatter_end) {
-                result.push_str(line);
-                result.push('\n');
+            // Flat file targets — companion files not applicable
+            SkillType::Agent => {
+                let content =
+                    transform_file(&skill.path, |c| transform::cursor_agent(c, &combined_name))?;
+                opts.output.write(&dest_file, content)?;
             }
-            result
+            SkillType::Command => opts.output.copy(&skill.path, &dest_file)?,
         }
-    } else {
-        // No frontmatter - add it with both name and description
-        let desc = extract_description_from_body(&lines, 0);
-        let mut result = String::new();
-        result.push_str("---\n");
-        result.push_str(&format!("name: {}\n", skill_name));
-        result.push_str(&format!("description: \"{}\"\n", desc));
-        result.push_str("---\n");
-        result.push_str(&content);
-        result
-    };
-
-    let mut file = fs::File::create(dest)?;
-    file.write_all(output.as_bytes())?;
 
-    Ok(())
-}
-
-/// Extract a description from the markdown body content.
-/// Uses the first heading text or first non-empty paragraph.
-fn extract_description_from_body(lines: &[&str], start_from: usize) -> String {
-    for line in lines.iter().skip(start_from) {
-        let trimmed = line.trim();
-        if trimmed.is_empty() || trimmed == "---" {
-            continue;
-        }
-        if trimmed.starts_with('#') {
-            // Use heading text as description
-            let text = trimmed.trim_start_matches('#').trim();
-            return truncate_description(text);
-        }
-        // Use first paragraph text
-        return truncate_description(trimmed);
-    }
-    "Skill instructions".to_string()
-}
-
-/// Truncate a description to 200 characters max
-fn truncate_description(text: &str) -> String {
-    if text.len() <= 200 {
-        text.to_string()
-    } else {
-        format!("{}...", &text[..197])
+        Ok(dest_file)
     }
 }
 
 // ---------------------------------------------------------------------------
-// Phase 1: Agent file transformation (Claude → OpenCode)
+// Content transforms (see `transform`) applied to source files
 // ---------------------------------------------------------------------------
 
-/// Transform an agent file for OpenCode format, converting tools from string to YAML object.
-/// Phase 1: expanded tool name mapping with pass-through for unknown tools.
-fn transform_agent_file(src: &PathBuf, dest: &PathBuf) -> Result<()> {
-    let content = fs::read_to_string(src)?;
-    let lines: Vec<&str> = content.lines().collect();
-
-    if lines.first() != Some(&"---") {
-        // No frontmatter, just copy as-is
-        fs::copy(src, dest)?;
-        return Ok(());
-    }
-
-    // Parse frontmatter and transform
-    let mut result = String::new();
-    let mut in_frontmatter = false;
-    let mut frontmatter_lines = Vec::new();
-    let mut body_lines = Vec::new();
-    let mut found_end = false;
-
-    for line in &lines {
-        if *line == "---" {
-            if in_frontmatter {
-                found_end = true;
-                in_frontmatter = false;
-                continue;
-            } else {
-                in_frontmatter = true;
-                continue;
-            }
-        }
-
-        if in_frontmatter && !found_end {
-            frontmatter_lines.push(*line);
-        } else {
-            body_lines.push(*line);
-        }
-    }
-
-    // Transform frontmatter
-    result.push_str("---\n");
-
-    let mut i = 0;
-    while i < frontmatter_lines.len() {
-        let line = frontmatter_lines[i];
-
-        if line.trim().starts_with("tools:") && line.contains(",") {
-            // Found tools string (Claude format), convert to YAML object
-            let tools_str = line.trim_start_matches("tools:").trim();
-            let tool_list: Vec<&str> = tools_str.split(',').map(|s| s.trim()).collect();
-
-            result.push_str("tools:\n");
-
-            for tool in tool_list {
-                let opencode_tool = claude_to_opencode_tool(tool.trim());
-                result.push_str(&format!("  {}: true\n", opencode_tool));
-            }
-        } else if line.trim().starts_with("color:") {
-            // Remove invalid color field (not supported by OpenCode)
-            i += 1;
-            continue;
-        } else {
-            // Keep other fields
-            result.push_str(line);
-            result.push('\n');
-        }
-
-        i += 1;
-    }
-
-    result.push_str("---\n");
-
-    // Add body
-    for line in body_lines {
-        result.push_str(line);
-        result.push('\n');
-    }
-
-    let mut file = fs::File::create(dest)?;
-    file.write_all(result.as_bytes())?;
-
-    Ok(())
+/// Read `src` and apply a content transform to it
+fn transform_file(src: &Path, apply: impl FnOnce(&str) -> String) -> Result<String> {
+    Ok(apply(&fs::read_to_string(src)?))
 }
 
-/// Map a Claude tool name to its OpenCode equivalent.
-/// Unknown tools pass through as lowercase instead of being dropped.
-fn claude_to_opencode_tool(tool: &str) -> &str {
-    match tool {
-        // Direct equivalents (both directions)
-        "Read" | "read" => "read",
-        "Write" | "write" => "write",
-        "Edit" | "edit" => "edit",
-        "Grep" | "grep" => "grep",
-        "Glob" | "glob" => "glob",
-        "Bash" | "bash" => "bash",
-        "WebSearch" | "websearch" => "websearch",
-        "WebFetch" | "webfetch" => "webfetch",
-        "TodoWrite" | "todowrite" => "todowrite",
-        "TodoRead" | "todoread" => "todoread",
-        // Claude-specific → closest OpenCode equivalent
-        "LS" => "bash",
-        "MultiEdit" => "edit",
-        "Task" => "bash",
-        "NotebookEdit" => "edit",
-        "NotebookRead" => "read",
-        "AskUserQuestion" | "question" => "question",
-        "KillBash" | "BashOutput" => "bash",
-        // OpenCode-native tools (pass through)
-        "list" => "list",
-        "lsp" => "lsp",
-        "patch" => "patch",
-        "skill" => "skill",
-        // Unknown: pass through as-is (don't drop)
-        other => {
-            eprintln!("Warning: Unknown tool '{}', passing through as-is", other);
-            other
-        }
-    }
+/// Detect the format of the agent file at `src`
+fn detect_agent_format(src: &Path) -> Result<AgentFormat> {
+    Ok(transform::agent_format(&fs::read_to_string(src)?))
 }
 
-// ---------------------------------------------------------------------------
-// Phase 1: Reverse agent transform (OpenCode → Claude)
-// ---------------------------------------------------------------------------
-
-/// Transform an agent file for Claude format.
-/// Converts OpenCode YAML object tools back to Claude comma-separated PascalCase string.
-fn transform_agent_for_claude(src: &PathBuf, dest: &PathBuf) -> Result<()> {
+/// `transform::skill` on the file at `src`, recording the skill when it gets
+/// the placeholder description
+fn transform_skill_file(
+    src: &Path,
+    skill_name: &str,
+    placeholders: &PlaceholderDescriptions,
+) -> Result<String> {
     let content = fs::read_to_string(src)?;
-    let lines: Vec<&str> = content.lines().collect();
-
-    if lines.first() != Some(&"---") {
-        fs::copy(src, dest)?;
-        return Ok(());
-    }
-
-    // Parse frontmatter and body
-    let mut in_frontmatter = false;
-    let mut frontmatter_lines = Vec::new();
-    let mut body_lines = Vec::new();
-    let mut found_end = false;
-
-    for line in &lines {
-        if *line == "---" {
-            if in_frontmatter {
-                found_end = true;
-                in_frontmatter = false;
-                continue;
-            } else {
-                in_frontmatter = true;
-                continue;
-            }
-        }
-        if in_frontmatter && !found_end {
-            frontmatter_lines.push(*line);
-        } else {
-            body_lines.push(*line);
-        }
+    if transform::skill_gets_placeholder(&content, skill_name) {
+        placeholders.record(skill_name);
     }
+    Ok(transform::skill(&content, skill_name))
+}
 
-    let mut result = String::new();
-    result.push_str("---\n");
-
-    let mut i = 0;
-    while i < frontmatter_lines.len() {
-        let line = frontmatter_lines[i];
-
-        if line.trim() == "tools:" {
-            // YAML object format — collect tool entries and convert to comma string
-            let mut tools = Vec::new();
-            i += 1;
-            while i < frontmatter_lines.len() {
-                let inner = frontmatter_lines[i].trim();
-                if inner.contains(": true") {
-                    let tool_name = inner.split(':').next().unwrap_or("").trim();
-                    let claude_tool = opencode_to_claude_tool(tool_name);
-                    tools.push(claude_tool);
-                    i += 1;
-                } else if inner.contains(": false") {
-                    // Skip disabled tools
-                    i += 1;
-                } else if inner.is_empty() || (!inner.starts_with(' ') && !inner.starts_with('-')) {
-                    // No longer in tools block
-                    break;
-                } else {
-                    i += 1;
-                }
-            }
-            if !tools.is_empty() {
-                result.push_str(&format!("tools: {}\n", tools.join(", ")));
-            }
-            continue; // don't increment i again
-        } else if line.trim().starts_with("color:") {
-            // Pass through color field (valid in Claude agents)
-            result.push_str(line);
-            result.push('\n');
-        } else {
-            result.push_str(line);
-            result.push('\n');
-        }
-
-        i += 1;
-    }
-
-    result.push_str("---\n");
-
-    for line in body_lines {
-        result.push_str(line);
-        result.push('\n');
+/// `transform::agent_claude_to_opencode` on the file at `src`, recording the
+/// tools with no OpenCode mapping
+fn transform_agent_file(src: &Path, unknown_tools: &UnknownTools) -> Result<String> {
+    let content = fs::read_to_string(src)?;
+    for tool in transform::unknown_opencode_tools(&content) {
+        unknown_tools.record(&tool);
     }
-
-    let mut file = fs::File::create(dest)?;
-    file.write_all(result.as_bytes())?;
-    Ok(())
+    Ok(transform::agent_claude_to_opencode(&content))
 }
 
-/// Map an OpenCode tool name to its Claude equivalent.
-fn opencode_to_claude_tool(tool: &str) -> &str {
-    match tool {
-        "read" => "Read",
-        "write" => "Write",
-        "edit" => "Edit",
-        "grep" => "Grep",
-        "glob" => "Glob",
-        "bash" => "Bash",
-        "websearch" => "WebSearch",
-        "webfetch" => "WebFetch",
-        "todowrite" => "TodoWrite",
-        "todoread" => "TodoRead",
-        "question" => "AskUserQuestion",
-        "list" => "LS",
-        "lsp" => "lsp",
-        "patch" => "patch",
-        "skill" => "skill",
-        // Unknown: pass through as-is
-        other => other,
+/// Shorten `text` to at most `max_chars` characters, ending in `...` when cut.
+/// Counts chars rather than bytes, so multibyte text is never split.
+pub fn ellipsize(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
     }
+    let kept: String = text.chars().take(max_chars.saturating_sub(3)).collect();
+    format!("{}...", kept)
 }
 
 // ---------------------------------------------------------------------------
-// Phase 3: Cursor rule frontmatter enhancement
+// Companion file copying
 // ---------------------------------------------------------------------------
 
-/// Transform a file into Cursor rule format with proper frontmatter.
-/// Ensures description and alwaysApply fields are present so Cursor's
-/// "Apply Intelligently" system can discover and use the rule.
-fn transform_cursor_rule(src: &PathBuf, dest: &PathBuf, _skill_name: &str) -> Result<()> {
-    let content = fs::read_to_string(src)?;
-    let lines: Vec<&str> = content.lines().collect();
-
-    let output = if lines.first() == Some(&"---") {
-        // Has frontmatter — check what fields exist
-        let mut has_description = false;
-        let mut has_always_apply = false;
-        let mut in_fm = false;
-        let mut fm_end = 0;
-
-        for (i, line) in lines.iter().enumerate() {
-            if *line == "---" {
-                if in_fm { fm_end = i; break; }
-                in_fm = true;
-                continue;
-            }
-            if in_fm {
-                if line.starts_with("description:") { has_description = true; }
-                if line.starts_with("alwaysApply:") { has_always_apply = true; }
-            }
-        }
-
-        if has_description && has_always_apply {
-            content
-        } else {
-            let mut result = String::new();
-            result.push_str("---\n");
-
-            // Copy existing frontmatter lines
-            for line in lines.iter().skip(1).take(fm_end - 1) {
-                result.push_str(line);
-                result.push('\n');
-            }
-
-            if !has_description {
-                let desc = extract_description_from_body(&lines, fm_end + 1);
-                result.push_str(&format!("description: \"{}\"\n", desc));
-            }
-            if !has_always_apply {
-                result.push_str("alwaysApply: false\n");
-            }
-
-            // Closing --- and body
-            for line in lines.iter().skip(fm_end) {
-                result.push_str(line);
-                result.push('\n');
-            }
-            result
-        }
-    } else {
-        // No frontmatter — create with Cursor rule fields
-        let desc = extract_description_from_body(&lines, 0);
-        let mut result = String::new();
-        result.push_str("---\n");
-        result.push_str(&format!("description: \"{}\"\n", desc));
-        result.push_str("alwaysApply: false\n");
-        result.push_str("---\n");
-        result.push_str(&content);
-        result
-    };
-
-    let mut file = fs::File::create(dest)?;
-    file.write_all(output.as_bytes())?;
-    Ok(())
+/// Companion files treated as documentation, by case-insensitive stem.
+/// LICENSE is deliberately not listed: it stays with the files it covers.
+const DOC_FILES: &[&str] = &["README", "CHANGELOG", "CONTRIBUTING"];
+
+/// Whether a companion file is documentation that `--no-docs` leaves out
+fn is_doc_file(file_name: &str) -> bool {
+    let stem = Path::new(file_name)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or(file_name);
+    DOC_FILES.contains(&stem.to_uppercase().as_str())
 }
 
-// ---------------------------------------------------------------------------
-// Cursor agent (subagent) transformation
-// ---------------------------------------------------------------------------
-
-/// Transform an agent file for Cursor subagent format.
-/// Cursor subagents use YAML frontmatter with name and description fields.
-fn transform_cursor_agent(src: &PathBuf, dest: &PathBuf, skill_name: &str) -> Result<()> {
-    let content = fs::read_to_string(src)?;
-    let lines: Vec<&str> = content.lines().collect();
-
-    let output = if lines.first() == Some(&"---") {
-        // Has frontmatter — check what fields exist
-        let mut has_name = false;
-        let mut has_description = false;
-        let mut in_fm = false;
-        let mut fm_end = 0;
-
-        for (i, line) in lines.iter().enumerate() {
-            if *line == "---" {
-                if in_fm { fm_end = i; break; }
-                in_fm = true;
-                continue;
-            }
-            if in_fm {
-                if line.starts_with("name:") { has_name = true; }
-                if line.starts_with("description:") { has_description = true; }
-            }
+/// Companions never installed: version control data, dependency and build
+/// caches, and OS clutter. Gitignore syntax.
+const DEFAULT_IGNORES: &[&str] = &[
+    ".git",
+    ".hg",
+    ".svn",
+    "node_modules/",
+    "__pycache__/",
+    "*.pyc",
+    ".DS_Store",
+    "Thumbs.db",
+];
+
+/// Ignore files read from a skill folder, in order; later patterns win, so a
+/// `.skmignore` can `!`-include what a `.gitignore` leaves out. Neither is copied.
+const IGNORE_FILES: [&str; 2] = [".gitignore", ".skmignore"];
+
+/// Which companions under `source_dir` are left out: `DEFAULT_IGNORES` plus the
+/// patterns of the folder's ignore files
+fn companion_ignores(source_dir: &Path) -> Gitignore {
+    let mut builder = GitignoreBuilder::new(source_dir);
+    for pattern in DEFAULT_IGNORES.iter().chain(&IGNORE_FILES) {
+        let _ = builder.add_line(None, pattern);
+    }
+    for file in IGNORE_FILES {
+        let path = source_dir.join(file);
+        if !path.is_file() {
+            continue;
         }
-
-        if has_name && has_description {
-            content
-        } else {
-            let mut result = String::new();
-            result.push_str("---\n");
-
-            if !has_name {
-                result.push_str(&format!("name: {}\n", skill_name));
-            }
-
-            // Copy existing frontmatter lines (skip tools: field which isn't used by Cursor)
-            for line in lines.iter().skip(1).take(fm_end - 1) {
-                // Skip Claude-specific tools field
-                if line.trim().starts_with("tools:") && line.contains(",") {
-                    continue;
-                }
-                result.push_str(line);
-                result.push('\n');
-            }
-
-            if !has_description {
-                let desc = extract_description_from_body(&lines, fm_end + 1);
-                result.push_str(&format!("description: \"{}\"\n", desc));
-            }
-
-            // Closing --- and body
-            for line in lines.iter().skip(fm_end) {
-                result.push_str(line);
-                result.push('\n');
-            }
-            result
+        // Bad lines are reported and skipped; the rest still apply
+        if let Some(e) = builder.add(&path) {
+            eprintln!("  {}: {}: {}", "Warning".yellow(), path.display(), e);
         }
-    } else {
-        // No frontmatter — create with Cursor subagent fields
-        let desc = extract_description_from_body(&lines, 0);
-        let mut result = String::new();
-        result.push_str("---\n");
-        result.push_str(&format!("name: {}\n", skill_name));
-        result.push_str(&format!("description: \"{}\"\n", desc));
-        result.push_str("---\n");
-        result.push_str(&content);
-        result
-    };
-
-    let mut file = fs::File::create(dest)?;
-    file.write_all(output.as_bytes())?;
-    Ok(())
+    }
+    builder.build().unwrap_or_else(|e| {
+        eprintln!("  {}: ignoring {}: {}", "Warning".yellow(), source_dir.display(), e);
+        Gitignore::empty()
+    })
 }
 
-// ---------------------------------------------------------------------------
-// Companion file copying
-// ---------------------------------------------------------------------------
-
 /// Copy companion files from source_dir to dest_dir, skipping the main .md file.
 /// Companion files are scripts, templates, and other resources that live alongside
-/// the main skill/rule markdown file in directory-based bundles.
-fn copy_companion_files(skill: &SkillFile, dest_dir: &Path) -> Result<()> {
+/// the main skill/rule markdown file in directory-based bundles. A folder skill's
+/// `README.md` is copied too, since the skill may refer to it, unless `opts.no_docs`.
+/// Paths matched by `companion_ignores` are left out.
+fn copy_companion_files(skill: &SkillFile, dest_dir: &Path, opts: &WriteOptions) -> Result<()> {
     let source_dir = match &skill.source_dir {
         Some(dir) => dir,
         None => return Ok(()),
     };
 
     let main_file = &skill.path;
+    let ignores = companion_ignores(source_dir);
 
     for entry in fs::read_dir(source_dir)? {
         let entry = entry?;
@@ -933,31 +1107,51 @@ fn copy_companion_files(skill: &SkillFile, dest_dir: &Path) -> Result<()> {
             continue;
         }
 
+        if opts.no_docs && entry_path.is_file() && is_doc_file(&file_name) {
+            continue;
+        }
+
+        let is_dir = entry_path.is_dir();
+        if ignores.matched(&entry_path, is_dir).is_ignore() {
+            continue;
+        }
+
         let dest_path = dest_dir.join(&file_name);
 
-        if entry_path.is_dir() {
-            copy_dir_recursive(&entry_path, &dest_path)?;
+        if is_dir {
+            copy_dir_recursive(&entry_path, &dest_path, &ignores, opts)?;
         } else {
-            fs::copy(&entry_path, &dest_path)?;
+            opts.output.copy(&entry_path, &dest_path)?;
+            opts.written.record(&entry_path, &dest_path);
         }
     }
 
     Ok(())
 }
 
-/// Recursively copy a directory tree from src to dest.
-fn copy_dir_recursive(src: &Path, dest: &Path) -> Result<()> {
-    fs::create_dir_all(dest)?;
+/// Recursively copy a directory tree from src to dest, leaving out what `ignores` matches.
+fn copy_dir_recursive(
+    src: &Path,
+    dest: &Path,
+    ignores: &Gitignore,
+    opts: &WriteOptions,
+) -> Result<()> {
+    opts.output.create_dir_all(dest)?;
 
     for entry in fs::read_dir(src)? {
         let entry = entry?;
         let entry_path = entry.path();
+        let is_dir = entry_path.is_dir();
+        if ignores.matched(&entry_path, is_dir).is_ignore() {
+            continue;
+        }
         let dest_path = dest.join(entry.file_name());
 
-        if entry_path.is_dir() {
-            copy_dir_recursive(&entry_path, &dest_path)?;
+        if is_dir {
+            copy_dir_recursive(&entry_path, &dest_path, ignores, opts)?;
         } else {
-            fs::copy(&entry_path, &dest_path)?;
+            opts.output.copy(&entry_path, &dest_path)?;
+            opts.written.record(&entry_path, &dest_path);
         }
     }
 
@@ -981,6 +1175,48 @@ mod tests {
         assert_eq!(Tool::Cursor.name(), "Cursor");
     }
 
+    #[test]
+    fn test_gitignore_lists_installed_entries_once() {
+        let temp_dir = tempdir().unwrap();
+        let skill_src = temp_dir.path().join("helper.md");
+        let command_src = temp_dir.path().join("commit.md");
+        fs::write(&skill_src, "# Helper").unwrap();
+        fs::write(&command_src, "# Commit").unwrap();
+        let files = [
+            SkillFile {
+                name: "helper".to_string(),
+                path: skill_src,
+                skill_type: SkillType::Skill,
+                source_dir: None,
+            },
+            SkillFile {
+                name: "commit".to_string(),
+                path: command_src,
+                skill_type: SkillType::Command,
+                source_dir: None,
+            },
+        ];
+        let project = temp_dir.path().join("project");
+        fs::create_dir_all(project.join(".claude")).unwrap();
+        fs::write(project.join(".claude/.gitignore"), "settings.local.json").unwrap();
+        let opts = WriteOptions {
+            gitignore: true,
+            ..Default::default()
+        };
+
+        // Reinstalling must not add the entries again
+        for _ in 0..2 {
+            for file in &files {
+                Tool::Claude.write_file_with(&project, "bundle", file, &opts).unwrap();
+            }
+        }
+
+        assert_eq!(
+            fs::read_to_string(project.join(".claude/.gitignore")).unwrap(),
+            "settings.local.json\n/.skm.toml\n/skills/bundle-helper/\n/commands/bundle/\n"
+        );
+    }
+
     // ---- Phase 2: transform_skill_file with description injection ----
 
     #[test]
@@ -990,7 +1226,9 @@ mod tests {
         let dest = dir.path().join("dest.md");
 
         fs::write(&src, "# My Skill\n\nContent here").unwrap();
-        transform_skill_file(&src, &dest, "test-skill").unwrap();
+        let content =
+            transform_skill_file(&src, "test-skill", &PlaceholderDescriptions::default()).unwrap();
+        fs::write(&dest, content).unwrap();
 
         let result = fs::read_to_string(&dest).unwrap();
         assert!(result.contains("name: test-skill"));
@@ -998,6 +1236,55 @@ mod tests {
         assert!(result.contains("# My Skill"));
     }
 
+    #[test]
+    fn test_transform_skill_description_with_quote_and_colon() {
+        let dir = tempdir().unwrap();
+        let src = dir.path().join("src.md");
+        let dest = dir.path().join("dest.md");
+
+        fs::write(&src, "Use \"strict\" mode: always, C:\\tmp\n").unwrap();
+        let content =
+            transform_skill_file(&src, "test-skill", &PlaceholderDescriptions::default()).unwrap();
+        fs::write(&dest, content).unwrap();
+
+        let result = fs::read_to_string(&dest).unwrap();
+        let yaml = result.split("---").nth(1).unwrap();
+        let parsed: serde_yaml::Value = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(
+            parsed["description"].as_str(),
+            Some("Use \"strict\" mode: always, C:\\tmp")
+        );
+    }
+
+    #[test]
+    fn test_injected_descriptions_round_trip_through_yaml() {
+        let dir = tempdir().unwrap();
+        let src = dir.path().join("src.md");
+        let description = "The \"Best\" Skill: v2";
+        let frontmatter = |content: &str| {
+            crate::frontmatter::parse_strict(content)
+                .unwrap_or_else(|e| panic!("{}:\n{}", e, content))
+                .unwrap()
+        };
+
+        let heading = "# The \"Best\" Skill: v2\n";
+        for body in [format!("{}\nBody\n", heading), format!("---\nname: x\n---\n{}", heading)] {
+            fs::write(&src, body).unwrap();
+            let outputs = [
+                transform_skill_file(&src, "true", &PlaceholderDescriptions::default()).unwrap(),
+                transform_file(&src, transform::cursor_rule).unwrap(),
+                transform_file(&src, transform::windsurf_rule).unwrap(),
+                transform_file(&src, transform::opencode_command).unwrap(),
+                transform_file(&src, |c| transform::cursor_agent(c, "#best")).unwrap(),
+            ];
+            for content in outputs {
+                let fields = frontmatter(&content);
+                assert_eq!(fields["description"], description, "{}", content);
+                assert!(fields.get("name").is_none_or(|n| n.is_string()), "{}", content);
+            }
+        }
+    }
+
     #[test]
     fn test_transform_skill_with_frontmatter_no_name() {
         let dir = tempdir().unwrap();
@@ -1005,13 +1292,43 @@ mod tests {
         let dest = dir.path().join("dest.md");
 
         fs::write(&src, "---\ndescription: test\n---\n# My Skill").unwrap();
-        transform_skill_file(&src, &dest, "test-skill").unwrap();
+        let content =
+            transform_skill_file(&src, "test-skill", &PlaceholderDescriptions::default()).unwrap();
+        fs::write(&dest, content).unwrap();
 
         let result = fs::read_to_string(&dest).unwrap();
         assert!(result.contains("name: test-skill"));
         assert!(result.contains("description: test"));
     }
 
+    #[test]
+    fn test_transform_skill_frontmatter_only() {
+        let dir = tempdir().unwrap();
+        let src = dir.path().join("src.md");
+        let dest = dir.path().join("dest.md");
+
+        fs::write(&src, "---\nname: lint\n---\n").unwrap();
+        let content =
+            transform_skill_file(&src, "test-lint", &PlaceholderDescriptions::default()).unwrap();
+        fs::write(&dest, content).unwrap();
+        let result = fs::read_to_string(&dest).unwrap();
+        assert_eq!(result, "---\nname: lint\ndescription: \"test-lint\"\n---\n");
+
+        // Complete frontmatter-only files are left alone
+        fs::write(&src, "---\nname: lint\ndescription: Lint it\n---\n").unwrap();
+        let content =
+            transform_skill_file(&src, "test-lint", &PlaceholderDescriptions::default()).unwrap();
+        fs::write(&dest, content).unwrap();
+        assert_eq!(fs::read_to_string(&dest).unwrap(), "---\nname: lint\ndescription: Lint it\n---\n");
+
+        // An unclosed block gets a single closing fence
+        fs::write(&src, "---\nname: lint\ndescription: Lint it\n").unwrap();
+        let content =
+            transform_skill_file(&src, "test-lint", &PlaceholderDescriptions::default()).unwrap();
+        fs::write(&dest, content).unwrap();
+        assert_eq!(fs::read_to_string(&dest).unwrap(), "---\nname: lint\ndescription: Lint it\n---\n");
+    }
+
     #[test]
     fn test_transform_skill_with_name_and_description() {
         let dir = tempdir().unwrap();
@@ -1019,7 +1336,9 @@ mod tests {
         let dest = dir.path().join("dest.md");
 
         fs::write(&src, "---\nname: existing-name\ndescription: existing desc\n---\n# My Skill").unwrap();
-        transform_skill_file(&src, &dest, "test-skill").unwrap();
+        let content =
+            transform_skill_file(&src, "test-skill", &PlaceholderDescriptions::default()).unwrap();
+        fs::write(&dest, content).unwrap();
 
         let result = fs::read_to_string(&dest).unwrap();
         assert!(result.contains("name: existing-name"));
@@ -1034,7 +1353,9 @@ mod tests {
         let dest = dir.path().join("dest.md");
 
         fs::write(&src, "---\nname: my-skill\n---\n# Great Skill\n\nDoes stuff").unwrap();
-        transform_skill_file(&src, &dest, "test-skill").unwrap();
+        let content =
+            transform_skill_file(&src, "test-skill", &PlaceholderDescriptions::default()).unwrap();
+        fs::write(&dest, content).unwrap();
 
         let result = fs::read_to_string(&dest).unwrap();
         assert!(result.contains("name: my-skill"));
@@ -1048,7 +1369,9 @@ mod tests {
         let dest = dir.path().join("dest.md");
 
         fs::write(&src, "").unwrap();
-        transform_skill_file(&src, &dest, "test-skill").unwrap();
+        let content =
+            transform_skill_file(&src, "test-skill", &PlaceholderDescriptions::default()).unwrap();
+        fs::write(&dest, content).unwrap();
 
         let result = fs::read_to_string(&dest).unwrap();
         assert!(result.contains("name: test-skill"));
@@ -1062,20 +1385,14 @@ mod tests {
         let dest = dir.path().join("dest.md");
 
         fs::write(&src, "This is a paragraph description of the skill.\n\nMore content.").unwrap();
-        transform_skill_file(&src, &dest, "test-skill").unwrap();
+        let content =
+            transform_skill_file(&src, "test-skill", &PlaceholderDescriptions::default()).unwrap();
+        fs::write(&dest, content).unwrap();
 
         let result = fs::read_to_string(&dest).unwrap();
         assert!(result.contains("description: \"This is a paragraph description of the skill.\""));
     }
 
-    #[test]
-    fn test_extract_description_truncation() {
-        let long_text = "A".repeat(250);
-        let result = truncate_description(&long_text);
-        assert_eq!(result.len(), 200);
-        assert!(result.ends_with("..."));
-    }
-
     // ---- Phase 1: Tool name mapping (Claude → OpenCode) ----
 
     #[test]
@@ -1094,7 +1411,8 @@ color: yellow
 This is the agent content.
 "#;
         fs::write(&src_path, src_content).unwrap();
-        transform_agent_file(&src_path, &dest_path).unwrap();
+        let content = transform_agent_file(&src_path, &UnknownTools::default()).unwrap();
+        fs::write(&dest_path, content).unwrap();
 
         let result = fs::read_to_string(&dest_path).unwrap();
         assert!(result.contains("name: test-agent"));
@@ -1117,7 +1435,8 @@ This is the agent content.
 
         let src_content = "---\nname: full-agent\ntools: Write, Edit, Bash, Task, AskUserQuestion, MultiEdit, NotebookRead\n---\nContent\n";
         fs::write(&src_path, src_content).unwrap();
-        transform_agent_file(&src_path, &dest_path).unwrap();
+        let content = transform_agent_file(&src_path, &UnknownTools::default()).unwrap();
+        fs::write(&dest_path, content).unwrap();
 
         let result = fs::read_to_string(&dest_path).unwrap();
         assert!(result.contains("  write: true"));
@@ -1136,7 +1455,8 @@ This is the agent content.
 
         let src_content = "---\nname: mcp-agent\ntools: Read, CustomMCP, Grep\n---\nContent\n";
         fs::write(&src_path, src_content).unwrap();
-        transform_agent_file(&src_path, &dest_path).unwrap();
+        let content = transform_agent_file(&src_path, &UnknownTools::default()).unwrap();
+        fs::write(&dest_path, content).unwrap();
 
         let result = fs::read_to_string(&dest_path).unwrap();
         assert!(result.contains("  read: true"));
@@ -1144,6 +1464,175 @@ This is the agent content.
         assert!(result.contains("  grep: true"));
     }
 
+    #[test]
+    fn test_transform_agent_trailing_comma_in_tools() {
+        let temp_dir = tempdir().unwrap();
+        let src_path = temp_dir.path().join("source.md");
+
+        fs::write(&src_path, "---\nname: comma\ntools: Read, Grep,  \n---\nContent\n").unwrap();
+        let unknown = UnknownTools::default();
+        let content = transform_agent_file(&src_path, &unknown).unwrap();
+        assert_eq!(
+            content,
+            "---\nname: comma\ntools:\n  read: true\n  grep: true\n---\nContent\n"
+        );
+        assert!(unknown.names().is_empty());
+        assert!(crate::frontmatter::parse_strict(&content).is_ok());
+    }
+
+    #[test]
+    fn test_unknown_tools_collected_once_across_agents() {
+        let temp_dir = tempdir().unwrap();
+        let opts = WriteOptions::default();
+        for (name, tools) in [("one", "Read, CustomMCP, Grep"), ("two", "CustomMCP, Deploy, Bash")] {
+            let path = temp_dir.path().join(format!("{}.md", name));
+            fs::write(&path, format!("---\nname: {}\ntools: {}\n---\nBody\n", name, tools)).unwrap();
+            let agent = SkillFile {
+                name: name.to_string(),
+                path,
+                skill_type: SkillType::Agent,
+                source_dir: None,
+            };
+            Tool::OpenCode
+                .write_file_with(&temp_dir.path().join("project"), "bundle", &agent, &opts)
+                .unwrap();
+        }
+
+        assert_eq!(opts.unknown_tools.names(), vec!["CustomMCP", "Deploy"]);
+    }
+
+    #[test]
+    fn test_empty_skill_reported_with_placeholder_description() {
+        let temp_dir = tempdir().unwrap();
+        let opts = WriteOptions::default();
+        for (name, content) in [("empty", ""), ("titled", "# Fill PDF forms\n")] {
+            let skill_dir = temp_dir.path().join(name);
+            fs::create_dir_all(&skill_dir).unwrap();
+            fs::write(skill_dir.join("SKILL.md"), content).unwrap();
+            let skill = SkillFile {
+                name: name.to_string(),
+                path: skill_dir.join("SKILL.md"),
+                skill_type: SkillType::Skill,
+                source_dir: Some(skill_dir),
+            };
+            Tool::OpenCode
+                .write_file_with(&temp_dir.path().join("project"), "pdf", &skill, &opts)
+                .unwrap();
+        }
+
+        assert_eq!(opts.placeholder_descriptions.names(), vec!["pdf-empty"]);
+    }
+
+    #[test]
+    fn test_allowed_tools_alias_normalized_for_claude_and_opencode() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("reviewer.md");
+        fs::write(
+            &path,
+            "---\nname: reviewer\nallowed-tools: Read, Grep\n---\nReview code.\n",
+        )
+        .unwrap();
+        let agent = SkillFile {
+            name: "reviewer".to_string(),
+            path,
+            skill_type: SkillType::Agent,
+            source_dir: None,
+        };
+        let project = temp_dir.path().join("project");
+
+        let claude = Tool::Claude.write_file(&project, "bundle", &agent).unwrap();
+        assert_eq!(
+            fs::read_to_string(claude).unwrap(),
+            "---\nname: reviewer\ntools: Read, Grep\n---\nReview code.\n"
+        );
+
+        let opencode = Tool::OpenCode.write_file(&project, "bundle", &agent).unwrap();
+        let content = fs::read_to_string(opencode).unwrap();
+        assert!(content.contains("tools:\n  read: true\n  grep: true\n"));
+        assert!(!content.contains("allowed-tools"));
+    }
+
+    #[test]
+    fn test_permissions_map_is_not_mistaken_for_tools() {
+        let temp_dir = tempdir().unwrap();
+        let src_path = temp_dir.path().join("source.md");
+        let dest_path = temp_dir.path().join("dest.md");
+
+        let src_content = "---\nname: oc\npermissions:\n  edit: ask\n---\nBody\n";
+        fs::write(&src_path, src_content).unwrap();
+        let content = transform_file(&src_path, transform::agent_opencode_to_claude).unwrap();
+        fs::write(&dest_path, content).unwrap();
+        assert_eq!(fs::read_to_string(&dest_path).unwrap(), src_content);
+    }
+
+    #[test]
+    fn test_yaml_list_tools_and_dashes_in_description() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("lister.md");
+        fs::write(
+            &path,
+            "---\nname: lister\ndescription: \"Lists --- then stops\"\ntools: [Read, Grep]\n\
+             ---\nBody\n",
+        )
+        .unwrap();
+        assert_eq!(detect_agent_format(&path).unwrap(), AgentFormat::Claude);
+        let agent = SkillFile {
+            name: "lister".to_string(),
+            path,
+            skill_type: SkillType::Agent,
+            source_dir: None,
+        };
+        let project = temp_dir.path().join("project");
+
+        let claude = Tool::Claude.write_file(&project, "bundle", &agent).unwrap();
+        assert_eq!(
+            fs::read_to_string(claude).unwrap(),
+            "---\nname: lister\ndescription: \"Lists --- then stops\"\ntools: Read, Grep\n\
+             ---\nBody\n"
+        );
+
+        let opencode = Tool::OpenCode.write_file(&project, "bundle", &agent).unwrap();
+        let content = fs::read_to_string(opencode).unwrap();
+        assert!(content.contains("tools:\n  read: true\n  grep: true\n"), "{}", content);
+        assert!(content.contains("description: \"Lists --- then stops\"\n"));
+        assert!(content.ends_with("---\nBody\n"));
+    }
+
+    #[test]
+    fn test_model_override_in_agent_frontmatter() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("reviewer.md");
+        fs::write(
+            &path,
+            "---\nname: reviewer\ndescription: Reviews\ntools: Read, Grep\nmodel: opus\n---\nBody\n",
+        )
+        .unwrap();
+        let agent = SkillFile {
+            name: "reviewer".to_string(),
+            path,
+            skill_type: SkillType::Agent,
+            source_dir: None,
+        };
+        let project = temp_dir.path().join("project");
+        let opts = WriteOptions {
+            model: Some("sonnet".to_string()),
+            ..Default::default()
+        };
+
+        for tool in [Tool::Claude, Tool::OpenCode, Tool::Cursor] {
+            let dest = tool.write_file_with(&project, "bundle", &agent, &opts).unwrap();
+            let content = fs::read_to_string(dest).unwrap();
+            assert_eq!(content.matches("model: sonnet\n").count(), 1, "{:?}", tool);
+            assert!(!content.contains("opus"), "{:?}", tool);
+            assert!(content.ends_with("---\nBody\n"), "{:?}", tool);
+        }
+
+        assert_eq!(
+            transform::set_field("Body\n", "model", "sonnet"),
+            "---\nmodel: sonnet\n---\nBody\n"
+        );
+    }
+
     // ---- Phase 1: Reverse transform (OpenCode → Claude) ----
 
     #[test]
@@ -1154,7 +1643,8 @@ This is the agent content.
 
         let src_content = "---\nname: oc-agent\ndescription: An OpenCode agent\ntools:\n  read: true\n  write: true\n  grep: true\nmodel: sonnet\n---\nAgent body.\n";
         fs::write(&src_path, src_content).unwrap();
-        transform_agent_for_claude(&src_path, &dest_path).unwrap();
+        let content = transform_file(&src_path, transform::agent_opencode_to_claude).unwrap();
+        fs::write(&dest_path, content).unwrap();
 
         let result = fs::read_to_string(&dest_path).unwrap();
         assert!(result.contains("tools: Read, Write, Grep"));
@@ -1171,13 +1661,40 @@ This is the agent content.
 
         let src_content = "---\ntools:\n  read: true\n  write: false\n  bash: true\n---\nBody\n";
         fs::write(&src_path, src_content).unwrap();
-        transform_agent_for_claude(&src_path, &dest_path).unwrap();
+        let content = transform_file(&src_path, transform::agent_opencode_to_claude).unwrap();
+        fs::write(&dest_path, content).unwrap();
 
         let result = fs::read_to_string(&dest_path).unwrap();
         assert!(result.contains("tools: Read, Bash"));
         assert!(!result.contains("Write"));
     }
 
+    #[test]
+    fn test_tools_block_ends_at_next_key() {
+        let temp_dir = tempdir().unwrap();
+        let src_path = temp_dir.path().join("source.md");
+        let dest_path = temp_dir.path().join("dest.md");
+
+        let src_content = "---\nname: oc\ntools:\n  read: true\n  bash: true\nmodel: sonnet\n\
+                           hidden: true\nmetadata:\n  write: true\n---\nBody\n";
+        fs::write(&src_path, src_content).unwrap();
+        let content = transform_file(&src_path, transform::agent_opencode_to_claude).unwrap();
+        fs::write(&dest_path, content).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&dest_path).unwrap(),
+            "---\nname: oc\ntools: Read, Bash\nmodel: sonnet\nhidden: true\nmetadata:\n  write: true\n\
+             ---\nBody\n"
+        );
+
+        // Tool entries with nested settings: only the direct children are tools
+        let src_content = "---\ntools:\n    read: true\n    bash:\n      enabled: true\n    grep: true\n---\n";
+        fs::write(&src_path, src_content).unwrap();
+        let content = transform_file(&src_path, transform::agent_opencode_to_claude).unwrap();
+        fs::write(&dest_path, content).unwrap();
+        assert_eq!(fs::read_to_string(&dest_path).unwrap(), "---\ntools: Read, Grep\n---\n");
+    }
+
     // ---- Phase 4: Format detection ----
 
     #[test]
@@ -1390,7 +1907,8 @@ This is the agent content.
         let dest = temp_dir.path().join("dest.md");
 
         fs::write(&src, "---\ndescription: Existing desc\n---\n# Rule Content").unwrap();
-        transform_cursor_rule(&src, &dest, "test-rule").unwrap();
+        let content = transform_file(&src, transform::cursor_rule).unwrap();
+        fs::write(&dest, content).unwrap();
 
         let result = fs::read_to_string(&dest).unwrap();
         assert!(result.contains("description: Existing desc"));
@@ -1406,7 +1924,8 @@ This is the agent content.
         let dest = temp_dir.path().join("dest.md");
 
         fs::write(&src, "---\ndescription: Complete rule\nalwaysApply: true\n---\n# Content").unwrap();
-        transform_cursor_rule(&src, &dest, "test-rule").unwrap();
+        let content = transform_file(&src, transform::cursor_rule).unwrap();
+        fs::write(&dest, content).unwrap();
 
         let result = fs::read_to_string(&dest).unwrap();
         // Should be unchanged since both fields exist
@@ -1414,6 +1933,72 @@ This is the agent content.
         assert!(result.contains("alwaysApply: true"));
     }
 
+    #[test]
+    fn test_windsurf_rules_and_workflows() {
+        let temp_dir = tempdir().unwrap();
+        let target_dir = temp_dir.path().to_path_buf();
+
+        let rule_path = temp_dir.path().join("style.md");
+        fs::write(&rule_path, "---\ntrigger: always_on\n---\n# Style\n\nUse tabs.").unwrap();
+        let skill_path = temp_dir.path().join("SKILL.md");
+        fs::write(&skill_path, "# Review\n\nReviews changes.").unwrap();
+        let command_path = temp_dir.path().join("deploy.md");
+        fs::write(&command_path, "# Deploy").unwrap();
+
+        let write = |name: &str, path: &Path, skill_type| {
+            let skill = SkillFile {
+                name: name.to_string(),
+                path: path.to_path_buf(),
+                skill_type,
+                source_dir: None,
+            };
+            Tool::Windsurf.write_file(&target_dir, "tb", &skill).unwrap()
+        };
+
+        let rule = write("style", &rule_path, SkillType::Rule);
+        assert_eq!(rule, target_dir.join(".windsurf/rules/tb-style.md"));
+        let content = fs::read_to_string(&rule).unwrap();
+        assert!(content.contains("description: \"Style\"\n"), "{}", content);
+        assert_eq!(content.matches("trigger:").count(), 1);
+        assert!(content.contains("trigger: always_on"));
+
+        let skill = write("review", &skill_path, SkillType::Skill);
+        assert_eq!(skill, target_dir.join(".windsurf/rules/tb-review.md"));
+        let content = fs::read_to_string(&skill).unwrap();
+        let expected = "---\ndescription: \"Review\"\ntrigger: model_decision\n---\n";
+        assert!(content.starts_with(expected), "{}", content);
+
+        let command = write("deploy", &command_path, SkillType::Command);
+        assert_eq!(command, target_dir.join(".windsurf/workflows/tb-deploy.md"));
+        assert_eq!(fs::read_to_string(&command).unwrap(), "# Deploy");
+    }
+
+    #[test]
+    fn test_same_named_skill_and_rule_collide_in_flat_layout() {
+        let file = |skill_type| SkillFile {
+            name: "review".to_string(),
+            path: PathBuf::from("review.md"),
+            skill_type,
+            source_dir: None,
+        };
+        let skill = file(SkillType::Skill);
+        let rule = file(SkillType::Rule);
+        let command = file(SkillType::Command);
+        let files = [&skill, &command, &rule];
+        let target = Path::new("/project");
+        let opts = WriteOptions::default();
+
+        let collisions = Tool::Windsurf.dest_collisions(target, "tb", &files, &opts);
+        assert_eq!(collisions.len(), 1);
+        let (dest, first, second) = &collisions[0];
+        assert_eq!(*dest, target.join(".windsurf/rules/tb-review.md"));
+        assert_eq!(first.skill_type, SkillType::Skill);
+        assert_eq!(second.skill_type, SkillType::Rule);
+
+        // Folder-based layouts keep skills and rules apart
+        assert!(Tool::Claude.dest_collisions(target, "tb", &files, &opts).is_empty());
+    }
+
     #[test]
     fn test_cursor_agent_goes_to_agents_dir() {
         let temp_dir = tempdir().unwrap();
@@ -1446,6 +2031,37 @@ This is the agent content.
         assert!(!content.contains("tools: Read"));
     }
 
+    #[test]
+    fn test_opencode_command_gets_description() {
+        let temp_dir = tempdir().unwrap();
+        let target_dir = temp_dir.path().to_path_buf();
+        let src_path = temp_dir.path().join("source.md");
+        let skill = SkillFile {
+            name: "deploy".to_string(),
+            path: src_path.clone(),
+            skill_type: SkillType::Command,
+            source_dir: None,
+        };
+
+        // A bare Claude command gains frontmatter with a description
+        fs::write(&src_path, "# Deploy the app\n\nRun $ARGUMENTS.\n").unwrap();
+        let dest = Tool::OpenCode.write_file(&target_dir, "ops", &skill).unwrap();
+        assert_eq!(dest, target_dir.join(".opencode/commands/ops-deploy.md"));
+        assert_eq!(
+            fs::read_to_string(&dest).unwrap(),
+            "---\ndescription: \"Deploy the app\"\n---\n# Deploy the app\n\nRun $ARGUMENTS.\n"
+        );
+
+        // Existing fields are kept and the description is added after them
+        fs::write(&src_path, "---\nagent: plan\n---\nReview the diff.\n").unwrap();
+        let content = transform_file(&src_path, transform::opencode_command).unwrap();
+        assert!(content.starts_with("---\nagent: plan\ndescription: \"Review the diff.\"\n---\n"));
+
+        let described = "---\ndescription: Ship it\n---\n# Deploy\n";
+        fs::write(&src_path, described).unwrap();
+        assert_eq!(transform_file(&src_path, transform::opencode_command).unwrap(), described);
+    }
+
     #[test]
     fn test_cursor_command_goes_to_commands_dir() {
         let temp_dir = tempdir().unwrap();
@@ -1560,6 +2176,45 @@ Agent content here.
         assert!(dest_dir.join("scripts/lib/helper.py").exists());
     }
 
+    #[test]
+    fn test_no_docs_skips_readme_but_keeps_scripts() {
+        let temp_dir = tempdir().unwrap();
+        let target_dir = temp_dir.path().join("target");
+        let source_dir = temp_dir.path().join("source/skills/pptx");
+        fs::create_dir_all(source_dir.join("scripts")).unwrap();
+
+        let skill_md = source_dir.join("SKILL.md");
+        fs::write(&skill_md, "# PPTX Skill").unwrap();
+        fs::write(source_dir.join("README.md"), "# Readme").unwrap();
+        fs::write(source_dir.join("changelog.md"), "# Changes").unwrap();
+        fs::write(source_dir.join("scripts/build.sh"), "echo hi").unwrap();
+
+        let skill = SkillFile {
+            name: "pptx".to_string(),
+            path: skill_md,
+            skill_type: SkillType::Skill,
+            source_dir: Some(source_dir),
+        };
+
+        // README is a companion like any other by default
+        Tool::Claude.write_file(&target_dir, "b", &skill).unwrap();
+        let dest_dir = target_dir.join(".claude/skills/b-pptx");
+        assert!(dest_dir.join("README.md").exists());
+        fs::remove_dir_all(&target_dir).unwrap();
+
+        let opts = WriteOptions {
+            no_docs: true,
+            ..Default::default()
+        };
+        Tool::Claude
+            .write_file_with(&target_dir, "b", &skill, &opts)
+            .unwrap();
+        assert!(dest_dir.join("SKILL.md").exists());
+        assert!(dest_dir.join("scripts/build.sh").exists());
+        assert!(!dest_dir.join("README.md").exists());
+        assert!(!dest_dir.join("changelog.md").exists());
+    }
+
     #[test]
     fn test_companion_files_copied_opencode_skill() {
         let temp_dir = tempdir().unwrap();
@@ -1645,6 +2300,50 @@ Agent content here.
         assert!(!dest_dir.join("meta.yaml").exists());
     }
 
+    #[test]
+    fn test_companion_files_skip_ignored_paths() {
+        let temp_dir = tempdir().unwrap();
+        let target_dir = temp_dir.path().join("target");
+        let source_dir = temp_dir.path().join("source/skills/pptx");
+        for (path, content) in [
+            ("SKILL.md", "# PPTX Skill"),
+            ("template.txt", "template"),
+            ("scripts/build.sh", "echo hi"),
+            ("scripts/.DS_Store", "junk"),
+            (".DS_Store", "junk"),
+            (".git/HEAD", "ref: refs/heads/main"),
+            ("node_modules/left-pad/index.js", "module.exports = 1"),
+            ("scripts/__pycache__/util.cpython-312.pyc", "bytes"),
+            ("build/out.bin", "artifact"),
+            ("debug.log", "log"),
+            ("keep.log", "log"),
+            (".gitignore", "*.log\n"),
+            (".skmignore", "# build output\n/build/\n!keep.log\n"),
+        ] {
+            let path = source_dir.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, content).unwrap();
+        }
+
+        let skill = SkillFile {
+            name: "pptx".to_string(),
+            path: source_dir.join("SKILL.md"),
+            skill_type: SkillType::Skill,
+            source_dir: Some(source_dir),
+        };
+        Tool::Claude.write_file(&target_dir, "bundle", &skill).unwrap();
+
+        let dest_dir = target_dir.join(".claude/skills/bundle-pptx");
+        let mut copied: Vec<String> = walkdir::WalkDir::new(&dest_dir)
+            .into_iter()
+            .map(|e| e.unwrap())
+            .filter(|e| e.file_type().is_file())
+            .map(|e| e.path().strip_prefix(&dest_dir).unwrap().display().to_string())
+            .collect();
+        copied.sort();
+        assert_eq!(copied, ["SKILL.md", "keep.log", "scripts/build.sh", "template.txt"]);
+    }
+
     #[test]
     fn test_no_companion_files_when_source_dir_none() {
         let temp_dir = tempdir().unwrap();
@@ -1826,4 +2525,285 @@ Agent content here.
         assert_eq!(result, expected_path);
         assert!(expected_path.exists());
     }
+
+    #[test]
+    fn test_overlong_combined_name_is_shortened() {
+        let temp_dir = tempdir().unwrap();
+        let target_dir = temp_dir.path().to_path_buf();
+
+        let src_path = temp_dir.path().join("source.md");
+        fs::write(&src_path, "# Long\n\nContent").unwrap();
+
+        let long_name = "very-long-skill-title-".repeat(15);
+        let skill = SkillFile {
+            name: long_name.clone(),
+            path: src_path.clone(),
+            skill_type: SkillType::Skill,
+            source_dir: None,
+        };
+
+        let result = Tool::Claude.write_file(&target_dir, "bundle", &skill).unwrap();
+        let folder = result.parent().unwrap().file_name().unwrap().to_str().unwrap();
+        assert!(folder.len() <= MAX_FILE_NAME_BYTES - FILE_EXTENSION_RESERVE);
+        assert!(folder.starts_with("bundle-very-long-skill-title-"));
+        assert!(fs::read_to_string(&result)
+            .unwrap()
+            .contains(&format!("name: {}", folder)));
+
+        // Reinstalling lands in the same folder; a different long name doesn't
+        let again = Tool::Claude.write_file(&target_dir, "bundle", &skill).unwrap();
+        assert_eq!(again, result);
+        let other = SkillFile {
+            name: format!("{}x", long_name),
+            ..skill.clone()
+        };
+        let other_result = Tool::Claude.write_file(&target_dir, "bundle", &other).unwrap();
+        assert_ne!(other_result, result);
+
+        // Flat files keep room for the extension
+        let agent = SkillFile {
+            skill_type: SkillType::Agent,
+            ..skill
+        };
+        let agent_result = Tool::Cursor.write_file(&target_dir, "bundle", &agent).unwrap();
+        let file_name = agent_result.file_name().unwrap().to_str().unwrap();
+        assert!(file_name.len() <= MAX_FILE_NAME_BYTES);
+        assert!(file_name.ends_with(".md"));
+    }
+
+    #[test]
+    fn test_claude_agent_file_name_is_slugified() {
+        let temp_dir = tempdir().unwrap();
+        let src = temp_dir.path().join("reviewer.md");
+        fs::write(&src, "---\nname: Code Reviewer\ntools: Read, Grep\n---\nReview.\n").unwrap();
+        let agent = SkillFile {
+            name: "Code Reviewer".to_string(),
+            path: src,
+            skill_type: SkillType::Agent,
+            source_dir: None,
+        };
+
+        let project = temp_dir.path().join("project");
+        let dest = Tool::Claude.write_file(&project, "team", &agent).unwrap();
+
+        assert_eq!(dest, project.join(".claude/agents/team/code-reviewer.md"));
+        assert!(fs::read_to_string(&dest).unwrap().contains("name: Code Reviewer"));
+        assert_eq!(slugify("review/fix  it!"), "review-fix-it");
+        assert_eq!(slugify("code_review-v2.1"), "code_review-v2.1");
+    }
+
+    #[test]
+    fn test_ellipsize_multibyte_at_cut_point() {
+        // The emoji starts at byte 196, so a byte slice at 197 would panic
+        let text = format!("{}😀{}", "a".repeat(196), "b".repeat(10));
+        let desc = ellipsize(&text, 200);
+        assert_eq!(desc, format!("{}😀...", "a".repeat(196)));
+
+        // Browse listings cut at 37 chars; é straddles byte 37 here
+        let text = format!("{}é{}", "a".repeat(36), "z".repeat(10));
+        assert_eq!(ellipsize(&text, 40), format!("{}é...", "a".repeat(36)));
+        assert_eq!(ellipsize("Café", 40), "Café");
+    }
+
+    #[test]
+    fn test_fit_file_name_multibyte_boundary() {
+        let name = "é".repeat(200); // 400 bytes
+        let fitted = fit_file_name(&name);
+        assert!(fitted.len() <= MAX_FILE_NAME_BYTES - FILE_EXTENSION_RESERVE);
+        assert!(fitted.starts_with("éé"));
+        assert_eq!(fit_file_name("short-name"), "short-name");
+    }
+
+    #[cfg(unix)]
+    fn write_hook_script(dir: &Path, body: &str) -> PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+
+        let script = dir.join("hook.sh");
+        fs::write(&script, format!("#!/bin/sh\n{}\n", body)).unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+        script
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_hook_rewrites_written_file() {
+        let temp_dir = tempdir().unwrap();
+        let src_path = temp_dir.path().join("source.md");
+        fs::write(&src_path, "# Doc\n\nteam: marker\n").unwrap();
+
+        let skill = SkillFile {
+            name: "doc".to_string(),
+            path: src_path,
+            skill_type: SkillType::Command,
+            source_dir: None,
+        };
+        let opts = WriteOptions {
+            hook: Some(write_hook_script(temp_dir.path(), "sed 's/marker/MARKER/'")),
+            ..Default::default()
+        };
+
+        let target_dir = temp_dir.path().join("project");
+        let dest = Tool::Claude
+            .write_file_with(&target_dir, "bundle", &skill, &opts)
+            .unwrap();
+        let content = fs::read_to_string(&dest).unwrap();
+        assert!(content.contains("team: MARKER"));
+        assert!(!content.contains("marker"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_failing_hook_is_an_error() {
+        let temp_dir = tempdir().unwrap();
+        let src_path = temp_dir.path().join("source.md");
+        fs::write(&src_path, "# Doc").unwrap();
+
+        let skill = SkillFile {
+            name: "doc".to_string(),
+            path: src_path,
+            skill_type: SkillType::Command,
+            source_dir: None,
+        };
+        let opts = WriteOptions {
+            hook: Some(write_hook_script(temp_dir.path(), "exit 3")),
+            ..Default::default()
+        };
+
+        let err = Tool::Claude
+            .write_file_with(&temp_dir.path().join("project"), "bundle", &skill, &opts)
+            .unwrap_err();
+        assert!(err.to_string().contains("failed on"), "{:#}", err);
+    }
+
+    #[test]
+    fn test_dry_run_output_buffers_writes_and_leaves_target_untouched() {
+        let temp_dir = tempdir().unwrap();
+        let skill_dir = temp_dir.path().join("source/lint");
+        fs::create_dir_all(skill_dir.join("scripts")).unwrap();
+        fs::write(skill_dir.join("SKILL.md"), "# Lint\n\nLints the code.").unwrap();
+        fs::write(skill_dir.join("scripts/run.sh"), "echo lint").unwrap();
+        let agent_path = temp_dir.path().join("source/reviewer.md");
+        fs::write(&agent_path, "---\nname: reviewer\n---\nReview\n").unwrap();
+        let project = temp_dir.path().join("project");
+        fs::create_dir(&project).unwrap();
+
+        let skill = SkillFile {
+            name: "lint".to_string(),
+            path: skill_dir.join("SKILL.md"),
+            skill_type: SkillType::Skill,
+            source_dir: Some(skill_dir.clone()),
+        };
+        let agent = SkillFile {
+            name: "reviewer".to_string(),
+            path: agent_path,
+            skill_type: SkillType::Agent,
+            source_dir: None,
+        };
+        let opts = WriteOptions {
+            model: Some("sonnet".to_string()),
+            gitignore: true,
+            output: Output::dry_run(),
+            ..Default::default()
+        };
+
+        let skill_dest = Tool::Claude.write_file_with(&project, "tools", &skill, &opts).unwrap();
+        let agent_dest = Tool::Claude.write_file_with(&project, "tools", &agent, &opts).unwrap();
+
+        // Transforms ran, but only into the buffer
+        let skill_md = String::from_utf8(opts.output.buffered(&skill_dest).unwrap()).unwrap();
+        assert!(skill_md.starts_with("---\nname: tools-lint\n"), "{}", skill_md);
+        let agent_md = String::from_utf8(opts.output.buffered(&agent_dest).unwrap()).unwrap();
+        assert!(agent_md.contains("model: sonnet\n"));
+        let script = skill_dest.with_file_name("scripts").join("run.sh");
+        assert_eq!(opts.output.buffered(&script).unwrap(), b"echo lint");
+        assert!(opts.output.buffered(&project.join(".claude/.gitignore")).is_some());
+        assert_eq!(opts.written.pairs().len(), 3);
+        assert_eq!(fs::read_dir(&project).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_dest_template_redirects_install_path() {
+        let temp_dir = tempdir().unwrap();
+        let skill_dir = temp_dir.path().join("source/lint");
+        fs::create_dir_all(skill_dir.join("scripts")).unwrap();
+        fs::write(skill_dir.join("SKILL.md"), "# Lint\n\nLints the code.").unwrap();
+        fs::write(skill_dir.join("scripts/run.sh"), "echo lint").unwrap();
+        let project = temp_dir.path().join("project");
+
+        let skill = SkillFile {
+            name: "lint".to_string(),
+            path: skill_dir.join("SKILL.md"),
+            skill_type: SkillType::Skill,
+            source_dir: Some(skill_dir.clone()),
+        };
+        let destinations = DestTemplates::new(vec![(
+            Tool::Claude,
+            SkillType::Skill,
+            "shared/{bundle}/{name}/SKILL.md".to_string(),
+        )]);
+        let opts = WriteOptions {
+            destinations: destinations.clone(),
+            ..Default::default()
+        };
+
+        let dest = Tool::Claude.write_file_with(&project, "tools", &skill, &opts).unwrap();
+        assert_eq!(dest, project.join("shared/tools/lint/SKILL.md"));
+        assert!(dest.exists());
+        assert!(project.join("shared/tools/lint/scripts/run.sh").exists());
+        assert!(!project.join(".claude").exists());
+
+        // Other tools and types keep the built-in layout
+        let dest = Tool::Cursor.write_file_with(&project, "tools", &skill, &opts).unwrap();
+        assert_eq!(dest, project.join(".cursor/skills/tools-lint/SKILL.md"));
+        assert_eq!(
+            Tool::Claude.dest_info(SkillType::Skill, "tools", &destinations),
+            "shared/tools/*/SKILL.md"
+        );
+    }
+
+    #[test]
+    fn test_install_prefix_namespaces_install_paths() {
+        let temp_dir = tempdir().unwrap();
+        let source = temp_dir.path().join("source");
+        fs::create_dir_all(&source).unwrap();
+        fs::write(source.join("name.md"), "# Name\n\nDoes things.").unwrap();
+        fs::write(source.join("helper.md"), "---\nname: helper\n---\nHelps.").unwrap();
+        let project = temp_dir.path().join("project");
+        let item = |name: &str, skill_type| SkillFile {
+            name: name.to_string(),
+            path: source.join(format!("{}.md", name)),
+            skill_type,
+            source_dir: None,
+        };
+        let opts = WriteOptions {
+            install_prefix: Some("acme".to_string()),
+            ..Default::default()
+        };
+
+        let skill = item("name", SkillType::Skill);
+        let dest = Tool::Claude.write_file_with(&project, "bundle", &skill, &opts).unwrap();
+        assert_eq!(dest, project.join(".claude/skills/acme-bundle-name/SKILL.md"));
+        assert!(fs::read_to_string(&dest).unwrap().contains("name: acme-bundle-name\n"));
+
+        let agent = item("helper", SkillType::Agent);
+        let dest = Tool::Claude.write_file_with(&project, "bundle", &agent, &opts).unwrap();
+        assert_eq!(dest, project.join(".claude/agents/acme-bundle/helper.md"));
+        let dest = Tool::Cursor.write_file_with(&project, "bundle", &skill, &opts).unwrap();
+        assert_eq!(dest, project.join(".cursor/skills/acme-bundle-name/SKILL.md"));
+    }
+
+    #[test]
+    fn test_check_dest_template() {
+        assert!(check_dest_template(".claude/skills/{bundle}-{name}/SKILL.md").is_ok());
+        assert_eq!(
+            check_dest_template(".claude/skills/{name}/SKILL.md").unwrap_err(),
+            "must contain {bundle}"
+        );
+        assert_eq!(
+            check_dest_template("skills.md").unwrap_err(),
+            "must contain {bundle} and {name}"
+        );
+        assert!(check_dest_template("/etc/{bundle}/{name}.md").is_err());
+        assert!(check_dest_template("../{bundle}/{name}.md").is_err());
+    }
 }
@@ -0,0 +1,552 @@
+//! Content transforms applied when installing items for a tool.
+//!
+//! Each transform takes a file's content and returns the content to install,
+//! without touching the disk, so installs, `skm lint` and previews share them.
+
+use serde_yaml::{Mapping, Value};
+
+use crate::frontmatter::{Document, Frontmatter};
+use crate::target::ellipsize;
+
+/// Detected agent file format based on tools field syntax
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AgentFormat {
+    /// Claude format: `tools: Read, Grep, Glob` (PascalCase, comma-separated)
+    Claude,
+    /// OpenCode format: `tools:\n  read: true` (lowercase, YAML object)
+    OpenCode,
+    /// No tools field found
+    Unknown,
+}
+
+// ---------------------------------------------------------------------------
+// Phase 4: Agent format detection
+// ---------------------------------------------------------------------------
+
+/// Detect whether an agent uses Claude format (PascalCase comma string
+/// or YAML list) or OpenCode format (lowercase YAML object)
+pub fn agent_format(content: &str) -> AgentFormat {
+    let doc = Document::parse(content);
+    let Some(fm) = &doc.frontmatter else {
+        return AgentFormat::Unknown;
+    };
+    let Some(key) = fm.first_of(TOOLS_KEYS) else {
+        return AgentFormat::Unknown; // No tools field
+    };
+    match fm.get(key) {
+        // "tools:" with a YAML object (or nothing yet) below it
+        None | Some(Value::Null | Value::Mapping(_)) => AgentFormat::OpenCode,
+        // "tools: Read, Grep, ..." or "tools: [Read, Grep]"
+        Some(_) => AgentFormat::Claude,
+    }
+}
+
+/// Frontmatter keys used for an agent's tool list across tools
+const TOOLS_KEYS: &[&str] = &["tools", "allowed-tools", "permissions"];
+
+/// The tools of a Claude-style tools field: a comma-separated string or a
+/// YAML list. `None` for any other value, such as an OpenCode tools map.
+/// Empty entries (from `Read, Grep,` or `Read,, Grep`) are dropped.
+fn claude_tools(value: &Value) -> Option<Vec<String>> {
+    let tools: Vec<&str> = match value {
+        Value::String(list) => list.split(',').collect(),
+        Value::Sequence(items) => items.iter().filter_map(Value::as_str).collect(),
+        _ => return None,
+    };
+    Some(
+        tools
+            .into_iter()
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .map(String::from)
+            .collect(),
+    )
+}
+
+/// The first tools field holding a Claude-style list, with its tools
+fn claude_tools_field(fm: &Frontmatter) -> Option<(&'static str, Vec<String>)> {
+    let key = fm.first_of(TOOLS_KEYS)?;
+    Some((key, claude_tools(fm.get(key)?)?))
+}
+
+// ---------------------------------------------------------------------------
+// Phase 2: Skill file transformation with description injection
+// ---------------------------------------------------------------------------
+
+/// Ensure a skill has proper frontmatter with name and description fields.
+/// - Adds `name:` if missing
+/// - Adds `description:` if missing (extracted from body content)
+pub fn skill(content: &str, name: &str) -> String {
+    let mut doc = Document::parse(content);
+    let has = |key| doc.frontmatter.as_ref().is_some_and(|fm| fm.contains(key));
+    let (has_name, has_description) = (has("name"), has("description"));
+
+    if has_name && has_description && doc.closed {
+        // Already has both required fields, use as-is
+        return content.to_string();
+    }
+
+    let desc = skill_description(&doc, name);
+    let fm = doc.frontmatter_mut();
+    if !has_name {
+        fm.prepend("name", &yaml_scalar(name));
+    }
+    if !has_description {
+        fm.set("description", &yaml_quote(&desc));
+    }
+    doc.render()
+}
+
+/// Whether `skill` would give the skill `PLACEHOLDER_DESCRIPTION`, having
+/// no description and nothing in the body to take one from
+pub fn skill_gets_placeholder(content: &str, name: &str) -> bool {
+    let doc = Document::parse(content);
+    let has_description = doc.frontmatter.as_ref().is_some_and(|fm| fm.contains("description"));
+    !has_description && skill_description(&doc, name) == PLACEHOLDER_DESCRIPTION
+}
+
+/// The description `skill` fills in. With no body there is nothing to
+/// summarize, so a frontmatter-only file is described by its name.
+fn skill_description(doc: &Document, name: &str) -> String {
+    if doc.frontmatter.is_some() && doc.body.trim().is_empty() {
+        name.to_string()
+    } else {
+        body_description(doc.body)
+    }
+}
+
+/// Description given to items whose body has no heading or text to use
+pub const PLACEHOLDER_DESCRIPTION: &str = "Skill instructions";
+
+/// Extract a description from the markdown body content.
+/// Uses the first heading text or first non-empty paragraph.
+fn body_description(body: &str) -> String {
+    for line in body.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed == "---" {
+            continue;
+        }
+        if trimmed.starts_with('#') {
+            // Use heading text as description
+            let text = trimmed.trim_start_matches('#').trim();
+            return truncate_description(text);
+        }
+        // Use first paragraph text
+        return truncate_description(trimmed);
+    }
+    PLACEHOLDER_DESCRIPTION.to_string()
+}
+
+/// Render text as a double-quoted YAML scalar, escaping `\\`, `"` and control
+/// characters (newlines, tabs, ...) so any text stays a single valid value
+pub fn yaml_quote(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        match c {
+            '\\' => quoted.push_str("\\\\"),
+            '"' => quoted.push_str("\\\""),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Render text as a YAML scalar: plain when that reads back as the same
+/// string (`my-skill`), quoted by `serde_yaml` otherwise (`'true'`, `'#tag'`)
+pub fn yaml_scalar(text: &str) -> String {
+    match serde_yaml::to_string(text) {
+        Ok(yaml) => yaml.trim_end_matches('\n').to_string(),
+        Err(_) => yaml_quote(text),
+    }
+}
+
+/// Truncate a description to 200 characters max
+fn truncate_description(text: &str) -> String {
+    ellipsize(text, 200)
+}
+
+// ---------------------------------------------------------------------------
+// Phase 1: Agent file transformation (Claude → OpenCode)
+// ---------------------------------------------------------------------------
+
+/// Convert a Claude agent for OpenCode, turning its tools string (or list)
+/// into a YAML object. Tools without an OpenCode name are passed through
+/// as-is; `unknown_opencode_tools` lists them.
+pub fn agent_claude_to_opencode(content: &str) -> String {
+    let mut doc = Document::parse(content);
+    let Some(fm) = &mut doc.frontmatter else {
+        // No frontmatter, just copy as-is
+        return content.to_string();
+    };
+
+    if let Some((key, tools)) = claude_tools_field(fm) {
+        let mut map = Mapping::new();
+        for tool in &tools {
+            let opencode_tool = claude_to_opencode_tool(tool).unwrap_or(tool);
+            map.insert(Value::from(opencode_tool), Value::Bool(true));
+        }
+        fm.set_value(key, Value::Mapping(map));
+        fm.rename(key, "tools");
+    }
+    // Remove invalid color field (not supported by OpenCode)
+    fm.remove("color");
+
+    doc.render()
+}
+
+/// Tools of a Claude agent that have no OpenCode mapping
+pub fn unknown_opencode_tools(content: &str) -> Vec<String> {
+    let doc = Document::parse(content);
+    let Some((_, tools)) = doc.frontmatter.as_ref().and_then(claude_tools_field) else {
+        return Vec::new();
+    };
+    tools.into_iter().filter(|t| claude_to_opencode_tool(t).is_none()).collect()
+}
+
+/// Which way a `TOOL_NAMES` entry maps
+#[derive(Clone, Copy, PartialEq)]
+enum ToolMapping {
+    /// Equivalent tools, mapped both ways
+    Both,
+    /// Lossy: a Claude tool OpenCode lacks, collapsed to its closest tool
+    ToOpenCode,
+    /// An OpenCode tool mapped back to a Claude tool that maps elsewhere
+    ToClaude,
+}
+
+/// Claude tool names and their OpenCode counterparts. Each OpenCode name has
+/// one entry mapping it back to Claude; the one-way entries are the only places
+/// a round trip changes a tool.
+const TOOL_NAMES: &[(&str, &str, ToolMapping)] = &[
+    ("Read", "read", ToolMapping::Both),
+    ("Write", "write", ToolMapping::Both),
+    ("Edit", "edit", ToolMapping::Both),
+    ("Grep", "grep", ToolMapping::Both),
+    ("Glob", "glob", ToolMapping::Both),
+    ("Bash", "bash", ToolMapping::Both),
+    ("WebSearch", "websearch", ToolMapping::Both),
+    ("WebFetch", "webfetch", ToolMapping::Both),
+    ("TodoWrite", "todowrite", ToolMapping::Both),
+    ("TodoRead", "todoread", ToolMapping::Both),
+    ("AskUserQuestion", "question", ToolMapping::Both),
+    // OpenCode-native tools, named the same in Claude agents
+    ("lsp", "lsp", ToolMapping::Both),
+    ("patch", "patch", ToolMapping::Both),
+    ("skill", "skill", ToolMapping::Both),
+    // Claude-specific tools with no OpenCode equivalent
+    ("LS", "bash", ToolMapping::ToOpenCode),
+    ("MultiEdit", "edit", ToolMapping::ToOpenCode),
+    ("Task", "bash", ToolMapping::ToOpenCode),
+    ("NotebookEdit", "edit", ToolMapping::ToOpenCode),
+    ("NotebookRead", "read", ToolMapping::ToOpenCode),
+    ("KillBash", "bash", ToolMapping::ToOpenCode),
+    ("BashOutput", "bash", ToolMapping::ToOpenCode),
+    // OpenCode's directory listing comes back as Claude's `LS`
+    ("LS", "list", ToolMapping::ToClaude),
+];
+
+/// Map a Claude tool name to its OpenCode equivalent. OpenCode names map to
+/// themselves. Returns `None` for unknown tools, which callers pass through unchanged.
+fn claude_to_opencode_tool(tool: &str) -> Option<&'static str> {
+    TOOL_NAMES
+        .iter()
+        .find(|(claude, _, way)| *claude == tool && *way != ToolMapping::ToClaude)
+        .or_else(|| TOOL_NAMES.iter().find(|(_, opencode, _)| *opencode == tool))
+        .map(|(_, opencode, _)| *opencode)
+}
+
+/// Set a top-level frontmatter field in `content`, replacing any existing
+/// value and adding a frontmatter block if there is none
+pub fn set_field(content: &str, key: &str, value: &str) -> String {
+    let mut doc = Document::parse(content);
+    doc.frontmatter_mut().set(key, value);
+    doc.render()
+}
+
+/// Tidy a Claude-format agent for Claude: an aliased tools field
+/// (`allowed-tools:`, `permissions:`) is renamed to the `tools:` key Claude
+/// expects, and a YAML list of tools becomes Claude's comma-separated string.
+pub fn agent_claude_tools_key(content: &str) -> String {
+    let mut doc = Document::parse(content);
+    let Some(fm) = &mut doc.frontmatter else {
+        return content.to_string();
+    };
+    let key = fm.first_of(TOOLS_KEYS);
+    let Some((key, value)) = key.and_then(|k| Some((k, fm.get(k)?.clone()))) else {
+        return content.to_string();
+    };
+    match value {
+        Value::Sequence(_) => {
+            let tools = claude_tools(&value).unwrap_or_default();
+            fm.set(key, &tools.join(", "));
+        }
+        Value::String(_) if key != "tools" => {}
+        _ => return content.to_string(),
+    }
+    fm.rename(key, "tools");
+    doc.render()
+}
+
+// ---------------------------------------------------------------------------
+// Phase 1: Reverse agent transform (OpenCode → Claude)
+// ---------------------------------------------------------------------------
+
+/// Convert an OpenCode agent for Claude, turning its YAML object of tools
+/// back into a comma-separated PascalCase string
+pub fn agent_opencode_to_claude(content: &str) -> String {
+    let mut doc = Document::parse(content);
+    let Some(fm) = &mut doc.frontmatter else {
+        return content.to_string();
+    };
+
+    if let Some(key) = fm.first_of(TOOLS_KEYS) {
+        if let Some(Value::Mapping(map)) = fm.get(key) {
+            // Disabled (`false`) tools are skipped
+            let tools: Vec<&str> = map
+                .iter()
+                .filter(|(_, enabled)| enabled.as_bool() == Some(true))
+                .filter_map(|(tool, _)| tool.as_str())
+                .map(opencode_to_claude_tool)
+                .collect();
+            // An empty list means it's not a tool map (e.g. a permissions
+            // map): keep it untouched
+            if !tools.is_empty() {
+                let tools = tools.join(", ");
+                fm.set(key, &tools);
+                fm.rename(key, "tools");
+            }
+        }
+    }
+
+    doc.render()
+}
+
+/// Map an OpenCode tool name to its Claude equivalent. Unknown tools pass
+/// through as-is.
+fn opencode_to_claude_tool(tool: &str) -> &str {
+    TOOL_NAMES
+        .iter()
+        .find(|(_, opencode, way)| *opencode == tool && *way != ToolMapping::ToOpenCode)
+        .map_or(tool, |(claude, _, _)| claude)
+}
+
+// ---------------------------------------------------------------------------
+// Phase 3: Rule frontmatter enhancement
+// ---------------------------------------------------------------------------
+
+/// Turn a file into a Cursor rule with proper frontmatter. Ensures
+/// description and alwaysApply fields are present so Cursor's
+/// "Apply Intelligently" system can discover and use the rule.
+pub fn cursor_rule(content: &str) -> String {
+    ensure_rule_frontmatter(content, "alwaysApply", "false")
+}
+
+/// Turn a file into a Windsurf rule. Ensures description and trigger
+/// fields are present; `model_decision` lets Cascade apply the rule when
+/// the description matches the task.
+pub fn windsurf_rule(content: &str) -> String {
+    ensure_rule_frontmatter(content, "trigger", "model_decision")
+}
+
+/// Add `description:` (from the body) and `{switch_key}: {default}` to the
+/// frontmatter when missing, creating the frontmatter if there is none
+fn ensure_rule_frontmatter(content: &str, switch_key: &str, default: &str) -> String {
+    let mut doc = Document::parse(content);
+    let has = |key| doc.frontmatter.as_ref().is_some_and(|fm| fm.contains(key));
+    let (has_description, has_switch) = (has("description"), has(switch_key));
+
+    if has_description && has_switch {
+        return content.to_string();
+    }
+
+    let desc = body_description(doc.body);
+    let fm = doc.frontmatter_mut();
+    if !has_description {
+        fm.set("description", &yaml_quote(&desc));
+    }
+    if !has_switch {
+        fm.set(switch_key, default);
+    }
+    doc.render()
+}
+
+// ---------------------------------------------------------------------------
+// OpenCode command transformation
+// ---------------------------------------------------------------------------
+
+/// Prepare a command for OpenCode, which lists commands by the
+/// `description:` in their frontmatter. A missing description is taken from
+/// the body. `agent:` and `model:` are optional (OpenCode uses the current
+/// ones), so they are left for the command to set.
+pub fn opencode_command(content: &str) -> String {
+    let mut doc = Document::parse(content);
+    if let Some(fm) = &doc.frontmatter {
+        // Unclosed frontmatter: leave it for the user to fix
+        if !doc.closed || fm.contains("description") {
+            return content.to_string();
+        }
+    }
+
+    let desc = body_description(doc.body);
+    doc.frontmatter_mut().set("description", &yaml_quote(&desc));
+    doc.render()
+}
+
+// ---------------------------------------------------------------------------
+// Cursor agent (subagent) transformation
+// ---------------------------------------------------------------------------
+
+/// Convert an agent for Cursor, whose subagents use YAML frontmatter
+/// with name and description fields
+pub fn cursor_agent(content: &str, name: &str) -> String {
+    let mut doc = Document::parse(content);
+    let has = |key| doc.frontmatter.as_ref().is_some_and(|fm| fm.contains(key));
+    let (has_name, has_description) = (has("name"), has("description"));
+
+    if has_name && has_description {
+        return content.to_string();
+    }
+
+    let desc = body_description(doc.body);
+    let fm = doc.frontmatter_mut();
+    if !has_name {
+        fm.prepend("name", &yaml_scalar(name));
+    }
+    // Skip Claude-specific tools lists, which Cursor doesn't use
+    for key in TOOLS_KEYS {
+        if fm.get(key).and_then(claude_tools).is_some() {
+            fm.remove(key);
+        }
+    }
+    if !has_description {
+        fm.set("description", &yaml_quote(&desc));
+    }
+    doc.render()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_skill_fills_in_name_and_description() {
+        assert_eq!(
+            skill("# Review\n\nChecks code.\n", "tools-review"),
+            "---\nname: tools-review\ndescription: \"Review\"\n---\n# Review\n\nChecks code.\n"
+        );
+        let complete = "---\nname: x\ndescription: y\n---\nBody\n";
+        assert_eq!(skill(complete, "tools-x"), complete);
+
+        assert!(skill_gets_placeholder("", "empty"));
+        assert!(!skill_gets_placeholder("---\nname: x\n---\n", "empty"));
+        assert!(!skill_gets_placeholder("Text", "empty"));
+    }
+
+    #[test]
+    fn test_extract_description_truncation() {
+        let long_text = "A".repeat(250);
+        let result = truncate_description(&long_text);
+        assert_eq!(result.len(), 200);
+        assert!(result.ends_with("..."));
+    }
+
+    #[test]
+    fn test_yaml_quote_escapes_control_characters() {
+        assert_eq!(yaml_quote("tab\there\nline \u{7}"), "\"tab\\there\\nline \\u0007\"");
+        let fields: Mapping =
+            serde_yaml::from_str(&format!("d: {}", yaml_quote("a\tb\r\n\\ \u{1b}"))).unwrap();
+        assert_eq!(fields["d"], "a\tb\r\n\\ \u{1b}");
+        assert_eq!(yaml_scalar("my-skill"), "my-skill");
+        assert_eq!(yaml_scalar("true"), "'true'");
+    }
+
+    #[test]
+    fn test_agent_round_trip_between_formats() {
+        let claude = "---\nname: a\ntools: Read, Grep, Deploy\ncolor: red\n---\nBody\n";
+        assert_eq!(agent_format(claude), AgentFormat::Claude);
+        let opencode = agent_claude_to_opencode(claude);
+        assert_eq!(
+            opencode,
+            "---\nname: a\ntools:\n  read: true\n  grep: true\n  Deploy: true\n---\nBody\n"
+        );
+        assert_eq!(agent_format(&opencode), AgentFormat::OpenCode);
+        assert_eq!(unknown_opencode_tools(claude), ["Deploy"]);
+
+        assert_eq!(
+            agent_opencode_to_claude(&opencode),
+            "---\nname: a\ntools: Read, Grep, Deploy\n---\nBody\n"
+        );
+        assert_eq!(
+            agent_claude_tools_key("---\nallowed-tools: [Read, Grep]\n---\n"),
+            "---\ntools: Read, Grep\n---\n"
+        );
+        assert_eq!(agent_format("# No frontmatter"), AgentFormat::Unknown);
+    }
+
+    #[test]
+    fn test_tool_names_round_trip() {
+        for (claude, opencode, way) in TOOL_NAMES {
+            if *way == ToolMapping::Both {
+                assert_eq!(claude_to_opencode_tool(claude), Some(*opencode));
+                assert_eq!(opencode_to_claude_tool(opencode), *claude);
+            }
+            // Every OpenCode name, one-way targets included, maps back to one Claude tool
+            let back: Vec<_> = TOOL_NAMES
+                .iter()
+                .filter(|(_, o, w)| o == opencode && *w != ToolMapping::ToOpenCode)
+                .collect();
+            assert_eq!(back.len(), 1, "{}", opencode);
+        }
+        assert_eq!(claude_to_opencode_tool("read"), Some("read"));
+        assert_eq!(claude_to_opencode_tool("list"), Some("list"));
+        assert_eq!(claude_to_opencode_tool("Task"), Some("bash"));
+        assert_eq!(opencode_to_claude_tool("list"), "LS");
+        assert_eq!(claude_to_opencode_tool("Deploy"), None);
+        assert_eq!(opencode_to_claude_tool("deploy"), "deploy");
+    }
+
+    #[test]
+    fn test_agent_tools_as_yaml_sequences() {
+        let opencode_tools = "tools:\n  read: true\n  grep: true\n";
+        for claude in [
+            "---\nname: a\ntools: [Read, Grep]\n---\nBody\n",
+            "---\nname: a\ntools:\n  - Read\n  - Grep\n---\nBody\n",
+            "---\nname: a\ntools:\n- Read\n- Grep\n---\nBody\n",
+        ] {
+            assert_eq!(agent_format(claude), AgentFormat::Claude, "{}", claude);
+            let opencode = agent_claude_to_opencode(claude);
+            assert!(opencode.contains(opencode_tools), "{}", opencode);
+            assert!(unknown_opencode_tools(claude).is_empty());
+        }
+
+        // One tool and no comma is still a list
+        let single = "---\ntools: [Read]\n---\n";
+        assert_eq!(agent_format(single), AgentFormat::Claude);
+        assert_eq!(agent_claude_to_opencode(single), "---\ntools:\n  read: true\n---\n");
+    }
+
+    #[test]
+    fn test_rules_commands_and_cursor_agents() {
+        let body = "# Style\n\nUse tabs.\n";
+        assert_eq!(
+            cursor_rule(body),
+            "---\ndescription: \"Style\"\nalwaysApply: false\n---\n# Style\n\nUse tabs.\n"
+        );
+        assert!(windsurf_rule(body).contains("trigger: model_decision\n"));
+        assert_eq!(
+            opencode_command(body),
+            "---\ndescription: \"Style\"\n---\n# Style\n\nUse tabs.\n"
+        );
+        assert_eq!(
+            cursor_agent("---\ntools: Read\n---\n# Helper\n", "b-helper"),
+            "---\nname: b-helper\ndescription: \"Helper\"\n---\n# Helper\n"
+        );
+        assert_eq!(set_field(body, "model", "opus"), format!("---\nmodel: opus\n---\n{}", body));
+    }
+}
@@ -0,0 +1,290 @@
+//! Checks of a source's files before it is published (`skm validate`): problems
+//! that installs would otherwise skip over or paper over.
+
+use anyhow::Result;
+use colored::Colorize;
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+use crate::bundle::{dir_entries, Bundle, SkillType};
+use crate::frontmatter::parse_strict;
+use crate::source::Source;
+use crate::transform::unknown_opencode_tools;
+
+/// File names a skill folder is read from
+const SKILL_FILES: [&str; 2] = ["SKILL.md", "skill.md"];
+
+/// One problem found in a source
+#[derive(Debug, PartialEq)]
+pub struct Problem {
+    /// The file or folder at fault
+    pub path: PathBuf,
+    pub message: String,
+}
+
+impl Problem {
+    fn new(path: &Path, message: impl Into<String>) -> Self {
+        Problem {
+            path: path.to_path_buf(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Check every bundle of `source`. Returns the problems found, in bundle order.
+pub fn validate_source(source: &dyn Source) -> Result<Vec<Problem>> {
+    let bundles = source.list_bundles()?;
+    let mut problems = Vec::new();
+
+    let mut seen: HashMap<&str, &Path> = HashMap::new();
+    for bundle in &bundles {
+        if let Some(first) = seen.get(bundle.name.as_str()) {
+            problems.push(Problem::new(
+                &bundle.path,
+                format!("bundle name `{}` is also used by {}", bundle.name, first.display()),
+            ));
+        } else {
+            seen.insert(&bundle.name, &bundle.path);
+        }
+    }
+
+    for folder in skill_folders(&bundles) {
+        if !SKILL_FILES.iter().any(|file| folder.join(file).is_file()) {
+            problems.push(Problem::new(&folder, "skill folder has no SKILL.md"));
+        }
+    }
+
+    for bundle in &bundles {
+        for skill_type in SkillType::ALL {
+            for file in bundle.files_of_type(skill_type) {
+                let content = match source.read_file(&file.path) {
+                    Ok(content) => content,
+                    Err(e) => {
+                        problems.push(Problem::new(&file.path, format!("{:#}", e)));
+                        continue;
+                    }
+                };
+                if content.trim().is_empty() {
+                    problems.push(Problem::new(&file.path, "file is empty"));
+                    continue;
+                }
+                if let Err(e) = parse_strict(&content) {
+                    problems.push(Problem::new(&file.path, e));
+                    continue;
+                }
+                match skill_type {
+                    SkillType::Skill => {
+                        let described = Bundle::frontmatter_meta(&content)
+                            .and_then(|meta| meta.description)
+                            .is_some_and(|d| !d.trim().is_empty());
+                        if !described {
+                            problems.push(Problem::new(&file.path, "skill has no `description`"));
+                        }
+                    }
+                    SkillType::Agent => {
+                        let unknown = unknown_opencode_tools(&content);
+                        if !unknown.is_empty() {
+                            problems.push(Problem::new(
+                                &file.path,
+                                format!("`tools` names unknown tools: {}", unknown.join(", ")),
+                            ));
+                        }
+                    }
+                    SkillType::Command | SkillType::Rule => {}
+                }
+            }
+        }
+    }
+    Ok(problems)
+}
+
+/// The folders of `skills/` directories holding `bundles`, each of which
+/// should be a skill with a SKILL.md. Hidden and `_`-prefixed folders (drafts,
+/// templates) are left out.
+fn skill_folders(bundles: &[Bundle]) -> BTreeSet<PathBuf> {
+    let skills_dir = SkillType::Skill.dir_name();
+    let mut dirs = BTreeSet::new();
+    for bundle in bundles {
+        dirs.insert(bundle.path.join(skills_dir));
+        // Anthropic-format bundles are themselves folders of a `skills/` directory
+        for skill in &bundle.skills {
+            if let Some(parent) = skill.source_dir.as_deref().and_then(Path::parent) {
+                if parent.file_name().is_some_and(|n| n == skills_dir) {
+                    dirs.insert(parent.to_path_buf());
+                }
+            }
+        }
+    }
+
+    let mut folders = BTreeSet::new();
+    for dir in dirs.iter().filter(|d| d.is_dir()) {
+        for path in dir_entries(dir).unwrap_or_default() {
+            let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+            if path.is_dir() && !name.starts_with('.') && !name.starts_with('_') {
+                folders.insert(path);
+            }
+        }
+    }
+    folders
+}
+
+/// Print each problem with its path relative to the source root
+pub fn print_problems(source: &dyn Source, root: Option<&Path>, problems: &[Problem]) {
+    println!("{} {}", "Validating".bold(), source.display_path().cyan());
+    println!();
+    for problem in problems {
+        let path = root
+            .and_then(|root| problem.path.strip_prefix(root).ok())
+            .unwrap_or(&problem.path);
+        println!("  {} {}", "✗".red(), path.display());
+        println!("      {}", problem.message.yellow());
+    }
+    if !problems.is_empty() {
+        println!();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source::LocalSource;
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn write(root: &Path, path: &str, content: &str) {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    fn problems(root: &Path) -> Vec<(String, String)> {
+        validate_source(&LocalSource::new(root.to_path_buf()))
+            .unwrap()
+            .into_iter()
+            .map(|p| {
+                let path = p.path.strip_prefix(root).unwrap().to_string_lossy().into_owned();
+                (path, p.message)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_valid_source_has_no_problems() {
+        let dir = tempdir().unwrap();
+        write(dir.path(), "pdf/skills/fill/SKILL.md", "---\nname: fill\ndescription: Fill\n---\n");
+        write(dir.path(), "pdf/agents/reader.md", "---\nname: r\ntools: Read, Grep\n---\n");
+        write(dir.path(), "pdf/commands/extract.md", "# Extract\n");
+        assert_eq!(problems(dir.path()), vec![]);
+    }
+
+    #[test]
+    fn test_reports_missing_and_empty_skill_files() {
+        let dir = tempdir().unwrap();
+        write(dir.path(), "pdf/skills/fill/SKILL.md", "---\ndescription: Fill\n---\n");
+        write(dir.path(), "pdf/skills/merge/notes.txt", "todo\n");
+        write(dir.path(), "pdf/skills/split/SKILL.md", "\n  \n");
+        assert_eq!(
+            problems(dir.path()),
+            vec![
+                ("pdf/skills/merge".to_string(), "skill folder has no SKILL.md".to_string()),
+                ("pdf/skills/split/SKILL.md".to_string(), "file is empty".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reports_invalid_frontmatter_and_missing_description() {
+        let dir = tempdir().unwrap();
+        write(dir.path(), "pdf/skills/fill/SKILL.md", "---\nname: [fill\n---\n");
+        write(dir.path(), "pdf/skills/merge/SKILL.md", "---\nname: merge\n---\n# Merge\n");
+        write(dir.path(), "pdf/rules/style.md", "---\nglobs: *.md\ndescription: x\n");
+        let found = problems(dir.path());
+        assert_eq!(found.len(), 3, "{:?}", found);
+        assert_eq!(found[0].0, "pdf/skills/fill/SKILL.md");
+        assert!(found[0].1.contains("not valid YAML"), "{}", found[0].1);
+        assert_eq!(
+            found[1],
+            ("pdf/skills/merge/SKILL.md".to_string(), "skill has no `description`".to_string())
+        );
+        assert_eq!(found[2].0, "pdf/rules/style.md");
+        assert!(found[2].1.contains("closing"), "{}", found[2].1);
+    }
+
+    #[test]
+    fn test_reports_unknown_agent_tools() {
+        let dir = tempdir().unwrap();
+        write(dir.path(), "ops/agents/deploy.md", "---\ntools: [Read, Deploy, Bash, Ship]\n---\n");
+        assert_eq!(
+            problems(dir.path()),
+            vec![(
+                "ops/agents/deploy.md".to_string(),
+                "`tools` names unknown tools: Deploy, Ship".to_string()
+            )]
+        );
+    }
+
+    /// Source whose skill files only exist in memory
+    struct InMemorySource(Vec<(&'static str, &'static str)>);
+
+    impl Source for InMemorySource {
+        fn list_bundles(&self) -> Result<Vec<Bundle>> {
+            let skills = self
+                .0
+                .iter()
+                .map(|(name, _)| crate::bundle::SkillFile {
+                    name: name.to_string(),
+                    path: PathBuf::from(format!("mem/pdf/skills/{}.md", name)),
+                    skill_type: SkillType::Skill,
+                    source_dir: None,
+                })
+                .collect();
+            Ok(vec![Bundle {
+                name: "pdf".to_string(),
+                path: PathBuf::from("mem/pdf"),
+                skills,
+                agents: vec![],
+                commands: vec![],
+                rules: vec![],
+                meta: Default::default(),
+            }])
+        }
+
+        fn display_path(&self) -> String {
+            "mem".to_string()
+        }
+
+        fn read_file(&self, path: &Path) -> Result<String> {
+            let name = path.file_stem().unwrap().to_str().unwrap();
+            let (_, content) = self.0.iter().find(|(n, _)| *n == name).unwrap();
+            Ok(content.to_string())
+        }
+    }
+
+    #[test]
+    fn test_description_is_read_through_the_source() {
+        let source = InMemorySource(vec![
+            ("fill", "---\ndescription: Fill\n---\n"),
+            ("merge", "---\nname: merge\n---\n"),
+        ]);
+        let found = validate_source(&source).unwrap();
+        assert_eq!(
+            found,
+            vec![Problem::new(
+                Path::new("mem/pdf/skills/merge.md"),
+                "skill has no `description`"
+            )]
+        );
+    }
+
+    #[test]
+    fn test_reports_duplicate_bundle_names() {
+        let dir = tempdir().unwrap();
+        write(dir.path(), "skills/fill/SKILL.md", "---\nname: pdf\ndescription: Fill\n---\n");
+        write(dir.path(), "skills/merge/SKILL.md", "---\nname: pdf\ndescription: Merge\n---\n");
+        let found = problems(dir.path());
+        assert_eq!(found.len(), 1, "{:?}", found);
+        // Whichever folder is read second is the duplicate
+        assert!(["skills/fill", "skills/merge"].contains(&found[0].0.as_str()));
+        assert!(found[0].1.starts_with("bundle name `pdf` is also used by"), "{}", found[0].1);
+    }
+}